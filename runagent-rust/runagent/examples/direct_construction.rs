@@ -22,6 +22,37 @@ async fn main() -> runagent::RunAgentResult<()> {
         enable_registry: None,
         user_id: None,
         persistent_memory: None,
+        payload_path: None,
+        retry_policy: None,
+        require_api_key: None,
+        stream_fallback_to_run: None,
+        respond_to_server_pings: None,
+        http_client: None,
+        stream_reconnect: None,
+        max_response_mb: None,
+        max_request_mb: None,
+        unix_socket: None,
+        profile: None,
+        interceptors: Vec::new(),
+        token_provider: None,
+        detect_generator_misuse: None,
+        validate_input: None,
+        compression: None,
+        stream_transport: None,
+        chunk_timeout: None,
+        msgpack: None,
+        agent_name: None,
+        framework: None,
+        transport: None,
+        api_prefix: None,
+        tls: None,
+        unwrap_envelopes: None,
+        default_kwargs: None,
+        attachment_encoding: None,
+        user_agent_suffix: None,
+        connect_timeout: None,
+        region_hosts: Vec::new(),
+        region_discovery: None,
     })
     .await?;
 
@@ -22,11 +22,14 @@
 //! ```
 
 use crate::client::RunAgentClient as AsyncRunAgentClient;
-use crate::types::{RunAgentError, RunAgentResult};
+use crate::client::StreamPolicy;
+use crate::types::{AgentSummary, Attachment, EntryPoint, RunAgentError, RunAgentResult};
 use futures::Stream;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io::Write;
 use std::pin::Pin;
+use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 // Re-export for convenience
@@ -40,7 +43,7 @@ pub use crate::client::RunAgentClientConfig;
 /// Note: For better performance and resource usage, prefer the async client.
 pub struct RunAgentClient {
     inner: AsyncRunAgentClient,
-    runtime: Runtime,
+    runtime: Arc<Runtime>,
 }
 
 impl RunAgentClient {
@@ -53,7 +56,57 @@ impl RunAgentClient {
 
         let inner = runtime.block_on(AsyncRunAgentClient::new(config))?;
 
-        Ok(Self { inner, runtime })
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Create a blocking client for a local agent served at `host:port`
+    ///
+    /// See [`crate::RunAgentClient::local`].
+    pub fn local(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        host: impl Into<String>,
+        port: u16,
+    ) -> RunAgentResult<Self> {
+        Self::new(RunAgentClientConfig::local(
+            agent_id,
+            entrypoint_tag,
+            host,
+            port,
+        ))
+    }
+
+    /// Create a blocking client for a remote agent authenticated with `api_key`
+    ///
+    /// See [`crate::RunAgentClient::remote`].
+    pub fn remote(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> RunAgentResult<Self> {
+        Self::new(RunAgentClientConfig::remote(
+            agent_id,
+            entrypoint_tag,
+            api_key,
+        ))
+    }
+
+    /// Create a new blocking client, failing fast if the server is unreachable
+    ///
+    /// See [`crate::RunAgentClient::connect`].
+    pub fn connect(config: RunAgentClientConfig) -> RunAgentResult<Self> {
+        let runtime = Runtime::new()
+            .map_err(|e| RunAgentError::connection(format!("Failed to create runtime: {}", e)))?;
+
+        let inner = runtime.block_on(AsyncRunAgentClient::connect(config))?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
     }
 
     /// Execute a non-streaming entrypoint
@@ -73,11 +126,107 @@ impl RunAgentClient {
             .block_on(self.inner.run_with_args(input_args, input_kwargs))
     }
 
+    /// Execute a non-streaming entrypoint and deserialize the payload into `T`
+    ///
+    /// See [`crate::RunAgentClient::run_typed`].
+    pub fn run_typed<T: serde::de::DeserializeOwned>(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<T> {
+        self.runtime.block_on(self.inner.run_typed(input_kwargs))
+    }
+
+    /// Execute a non-streaming entrypoint, returning its timing and token usage
+    ///
+    /// See [`crate::RunAgentClient::run_with_metadata`].
+    pub fn run_with_metadata(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<(Value, crate::types::ExecutionMetadata)> {
+        self.runtime
+            .block_on(self.inner.run_with_metadata(input_kwargs))
+    }
+
+    /// Execute a non-streaming entrypoint, telling a `null` payload apart from a missing one
+    ///
+    /// See [`crate::RunAgentClient::run_outcome`].
+    pub fn run_outcome(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<crate::types::RunOutcome> {
+        self.runtime.block_on(self.inner.run_outcome(input_kwargs))
+    }
+
+    /// Execute a non-streaming entrypoint with a per-call timeout
+    ///
+    /// See [`crate::RunAgentClient::run_with_timeout`].
+    pub fn run_with_timeout(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        timeout: std::time::Duration,
+    ) -> RunAgentResult<Value> {
+        self.runtime
+            .block_on(self.inner.run_with_timeout(input_kwargs, timeout))
+    }
+
+    /// Execute a non-streaming entrypoint with an idempotency key
+    ///
+    /// See [`crate::RunAgentClient::run_idempotent`]; concurrent calls from other
+    /// threads with the same key share one request.
+    pub fn run_idempotent(
+        &self,
+        key: &str,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Value> {
+        self.runtime
+            .block_on(self.inner.run_idempotent(key, input_kwargs))
+    }
+
+    /// Execute many inputs concurrently, returning results in input order
+    ///
+    /// Drives [`crate::RunAgentClient::run_batch`] on the internal runtime: at most
+    /// `concurrency` runs are in flight, and each input gets its own result.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::blocking::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "score_lead").with_api_key("key"),
+    ///     )?;
+    ///
+    ///     let inputs = (1..=10).map(|n| vec![("lead_id", json!(n))]).collect();
+    ///     let scores = client.run_batch(inputs, 4);
+    ///     let failed = scores.iter().filter(|r| r.is_err()).count();
+    ///     println!("{} scored, {} failed", scores.len() - failed, failed);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn run_batch(
+        &self,
+        inputs: Vec<Vec<(&str, Value)>>,
+        concurrency: usize,
+    ) -> Vec<RunAgentResult<Value>> {
+        self.runtime
+            .block_on(self.inner.run_batch(inputs, concurrency))
+    }
+
+    /// POST exactly `body` to the agent run URL and return the raw response
+    ///
+    /// See [`crate::RunAgentClient::run_raw_body`].
+    pub fn run_raw_body(&self, body: Value) -> RunAgentResult<Value> {
+        self.runtime.block_on(self.inner.run_raw_body(body))
+    }
+
     /// Execute a streaming entrypoint
     ///
     /// Returns a blocking iterator that yields chunks as they arrive.
     /// This provides true streaming - chunks are processed incrementally,
-    /// not collected all at once.
+    /// not collected all at once. Dropped connections are resumed as set by
+    /// [`RunAgentClientConfig::with_stream_reconnect`].
     ///
     /// # Example
     ///
@@ -103,6 +252,45 @@ impl RunAgentClient {
         Ok(BlockingStream::new(stream))
     }
 
+    /// Execute a streaming entrypoint under a per-stream resilience policy
+    ///
+    /// See [`crate::RunAgentClient::run_stream_with_policy`].
+    pub fn run_stream_with_policy(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        policy: &StreamPolicy,
+    ) -> RunAgentResult<BlockingStream> {
+        let stream = self
+            .runtime
+            .block_on(self.inner.run_stream_with_policy(input_kwargs, policy))?;
+        Ok(BlockingStream::new(stream))
+    }
+
+    /// Execute a non-streaming entrypoint with binary attachments
+    ///
+    /// See [`crate::RunAgentClient::run_with_attachments`].
+    pub fn run_with_attachments(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        attachments: &[Attachment],
+    ) -> RunAgentResult<Value> {
+        self.runtime
+            .block_on(self.inner.run_with_attachments(input_kwargs, attachments))
+    }
+
+    /// Execute a streaming entrypoint, delivering errors as in-band items
+    ///
+    /// See [`crate::RunAgentClient::run_stream_lenient`].
+    pub fn run_stream_lenient(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<BlockingStream> {
+        let stream = self
+            .runtime
+            .block_on(self.inner.run_stream_lenient(input_kwargs))?;
+        Ok(BlockingStream::new(stream))
+    }
+
     /// Execute a streaming entrypoint with both args and kwargs
     ///
     /// Returns a blocking iterator that yields chunks as they arrive.
@@ -119,16 +307,100 @@ impl RunAgentClient {
         Ok(BlockingStream::new(stream))
     }
 
+    /// Execute a streaming entrypoint and collect every chunk
+    ///
+    /// See [`crate::RunAgentClient::run_stream_collect`].
+    pub fn run_stream_collect(&self, input_kwargs: &[(&str, Value)]) -> RunAgentResult<Vec<Value>> {
+        self.runtime
+            .block_on(self.inner.run_stream_collect(input_kwargs))
+    }
+
+    /// Execute a streaming entrypoint and concatenate the text of its chunks
+    ///
+    /// See [`crate::RunAgentClient::run_stream_concat_text`].
+    pub fn run_stream_concat_text(&self, input_kwargs: &[(&str, Value)]) -> RunAgentResult<String> {
+        self.runtime
+            .block_on(self.inner.run_stream_concat_text(input_kwargs))
+    }
+
+    /// Execute a streaming entrypoint, writing each chunk to `writer` as NDJSON
+    ///
+    /// Every chunk is written as one JSON line and the writer is flushed after each
+    /// line, so output appears live. Stops at the first stream or I/O error and
+    /// returns it; otherwise returns the number of chunks written.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::blocking::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "entrypoint_stream")
+    ///             .with_api_key("key")
+    ///     )?;
+    ///
+    ///     client.run_stream_to_writer(&[("message", json!("Hello"))], std::io::stdout())?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn run_stream_to_writer(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        writer: impl Write,
+    ) -> RunAgentResult<usize> {
+        let stream = self.run_stream(input_kwargs)?;
+        write_ndjson(stream, writer)
+    }
+
+    /// Get a client for another entrypoint of the same agent
+    ///
+    /// See [`crate::RunAgentClient::with_entrypoint`]. The new client shares this
+    /// client's runtime.
+    pub fn with_entrypoint(&self, tag: &str) -> RunAgentResult<Self> {
+        Ok(Self {
+            inner: self.inner.with_entrypoint(tag)?,
+            runtime: self.runtime.clone(),
+        })
+    }
+
     /// Get agent architecture
     pub fn get_agent_architecture(&self) -> RunAgentResult<Value> {
         self.runtime.block_on(self.inner.get_agent_architecture())
     }
 
+    /// List the agents deployed by the authenticated account
+    ///
+    /// See [`crate::RunAgentClient::list_remote_agents`].
+    pub fn list_remote_agents(&self) -> RunAgentResult<Vec<AgentSummary>> {
+        self.runtime.block_on(self.inner.list_remote_agents())
+    }
+
+    /// Check the client's setup and explain what is wrong, if anything
+    ///
+    /// See [`crate::RunAgentClient::diagnose`].
+    pub fn diagnose(&self) -> crate::client::DiagnosticReport {
+        self.runtime.block_on(self.inner.diagnose())
+    }
+
     /// Health check
     pub fn health_check(&self) -> RunAgentResult<bool> {
         self.runtime.block_on(self.inner.health_check())
     }
 
+    /// Poll the health endpoint until the agent responds or `timeout` elapses
+    ///
+    /// See [`crate::RunAgentClient::wait_until_ready`].
+    pub fn wait_until_ready(
+        &self,
+        timeout: std::time::Duration,
+        poll_interval: std::time::Duration,
+    ) -> RunAgentResult<()> {
+        self.runtime
+            .block_on(self.inner.wait_until_ready(timeout, poll_interval))
+    }
+
     /// Get agent ID
     pub fn agent_id(&self) -> &str {
         self.inner.agent_id()
@@ -139,6 +411,28 @@ impl RunAgentClient {
         self.inner.entrypoint_tag()
     }
 
+    /// Entrypoints listed in the agent architecture
+    ///
+    /// See [`crate::RunAgentClient::entrypoints`].
+    pub fn entrypoints(&self) -> Vec<EntryPoint> {
+        self.inner.entrypoints()
+    }
+
+    /// Whether the agent architecture lists the entrypoint `tag`
+    pub fn has_entrypoint(&self, tag: &str) -> bool {
+        self.inner.has_entrypoint(tag)
+    }
+
+    /// Entrypoints whose tag ends in `_stream`
+    pub fn streaming_entrypoints(&self) -> Vec<EntryPoint> {
+        self.inner.streaming_entrypoints()
+    }
+
+    /// Entrypoints whose tag does not end in `_stream`
+    pub fn non_streaming_entrypoints(&self) -> Vec<EntryPoint> {
+        self.inner.non_streaming_entrypoints()
+    }
+
     /// Get extra parameters
     pub fn extra_params(&self) -> Option<&HashMap<String, Value>> {
         self.inner.extra_params()
@@ -154,12 +448,32 @@ impl RunAgentClient {
         self.inner.persistent_memory()
     }
 
+    /// Get the regional base URL this client switched to, if any
+    pub fn region_base_url(&self) -> Option<&str> {
+        self.inner.region_base_url()
+    }
+
     /// Check if this is a local client
     pub fn is_local(&self) -> bool {
         self.inner.is_local()
     }
 }
 
+fn write_ndjson(
+    chunks: impl Iterator<Item = RunAgentResult<Value>>,
+    mut writer: impl Write,
+) -> RunAgentResult<usize> {
+    let mut written = 0;
+    for chunk in chunks {
+        let chunk = chunk?;
+        serde_json::to_writer(&mut writer, &chunk)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        written += 1;
+    }
+    Ok(written)
+}
+
 /// Blocking iterator over a streaming response
 ///
 /// This iterator yields chunks as they arrive from the agent,
@@ -231,3 +545,99 @@ impl Iterator for BlockingStream {
         self.receiver.recv().ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_write_ndjson_lines() {
+        let stream = BlockingStream::new(Box::pin(futures::stream::iter(vec![
+            Ok(json!("Hello")),
+            Ok(json!({"content": "multi\nline"})),
+        ])));
+        let mut output = Vec::new();
+
+        let written = write_ndjson(stream, &mut output).unwrap();
+
+        assert_eq!(written, 2);
+        let text = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec![r#""Hello""#, r#"{"content":"multi\nline"}"#]);
+    }
+
+    #[test]
+    fn test_stream_with_policy_resumes_dropped_connection() {
+        use crate::client::SocketClientConfig;
+        use futures::{SinkExt, StreamExt};
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // The first connection drops after one chunk, the second completes
+        let runtime = Runtime::new().unwrap();
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        runtime.spawn(async move {
+            for (frame, close) in [
+                (json!({"type": "data", "content": "first"}), false),
+                (json!({"type": "completed"}), true),
+            ] {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                ws.next().await; // start request
+                ws.send(Message::Text(frame.to_string())).await.unwrap();
+                if close {
+                    ws.close(None).await.unwrap();
+                }
+            }
+        });
+
+        let config = RunAgentClientConfig::new("agent-id", "chat_stream")
+            .with_api_key("key")
+            .with_base_url(format!("http://{}", addr));
+        let client = RunAgentClient {
+            inner: runtime
+                .block_on(AsyncRunAgentClient::build(config))
+                .unwrap(),
+            runtime: Arc::new(runtime),
+        };
+        let policy = StreamPolicy::new().with_reconnect(
+            SocketClientConfig::default()
+                .with_max_reconnects(1)
+                .with_reconnect_delay(Duration::from_millis(10)),
+        );
+
+        let chunks: Vec<Value> = client
+            .run_stream_with_policy(&[], &policy)
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect();
+
+        assert_eq!(
+            chunks,
+            vec![
+                json!("first"),
+                json!({"type": "reconnecting", "attempt": 1}),
+                json!({"type": "completed"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_ndjson_stops_at_error() {
+        let chunks = vec![
+            Ok(json!(1)),
+            Err(RunAgentError::server("boom")),
+            Ok(json!(2)),
+        ];
+        let mut output = Vec::new();
+
+        let result = write_ndjson(chunks.into_iter(), &mut output);
+
+        assert!(result.is_err());
+        assert_eq!(output, b"1\n");
+    }
+}
@@ -0,0 +1,214 @@
+//! Multi-turn chat sessions over a single conversation thread
+//!
+//! Chat agents such as the LangGraph SQLite example take `user_id` and
+//! `thread_id` keyword arguments on every entrypoint. A [`ChatSession`] holds
+//! both and adds them to each call, so callers only pass the message.
+
+use crate::client::runagent_client::RunAgentClient;
+use crate::client::stream::AgentStream;
+use crate::types::{AgentResponse, RunAgentResult};
+use serde_json::{json, Value};
+
+/// Entrypoint returning a thread's messages, when the agent provides one
+const HISTORY_ENTRYPOINT: &str = "get_history";
+
+/// One conversation thread of a chat agent
+///
+/// Every call sends `message`, `user_id` and `thread_id` as keyword arguments.
+/// `send` uses the client's entrypoint (e.g. `chat`) and `send_stream` its
+/// `_stream` sibling (e.g. `chat_stream`). Each call goes through the wrapped
+/// client, so its retry policy and stream reconnect settings apply.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::client::ChatSession;
+/// use runagent::{RunAgentClient, RunAgentClientConfig};
+///
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     let client = RunAgentClient::new(
+///         RunAgentClientConfig::new("agent-id", "chat")
+///             .with_api_key("key")
+///             .with_user_id("t19")
+///             .with_persistent_memory(true),
+///     )
+///     .await?;
+///
+///     let mut session = ChatSession::new(client, "t19", "conversation_001");
+///     let reply = session.send("What is my name?").await?;
+///     println!("[Assistant]: {}", reply.text().unwrap_or_default());
+///     println!("{} messages so far", session.message_count());
+///
+///     if let Some(history) = session.history().await? {
+///         println!("{}", history.as_value());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ChatSession {
+    client: RunAgentClient,
+    user_id: String,
+    thread_id: String,
+    message_count: u64,
+}
+
+impl ChatSession {
+    /// Start a session on `client`'s agent for one user's thread
+    ///
+    /// A client for a `_stream` entrypoint is accepted too; `send` then uses the
+    /// tag without the suffix.
+    pub fn new(client: RunAgentClient, user_id: &str, thread_id: &str) -> Self {
+        Self {
+            client,
+            user_id: user_id.to_string(),
+            thread_id: thread_id.to_string(),
+            message_count: 0,
+        }
+    }
+
+    /// Send a message and wait for the reply
+    ///
+    /// The message count follows the `message_count` the agent reports, or
+    /// grows by two (the message and its reply) when it reports none.
+    pub async fn send(&mut self, message: &str) -> RunAgentResult<AgentResponse> {
+        let client = self.client.with_entrypoint(self.chat_tag())?;
+        let response = AgentResponse::from(client.run(&self.kwargs(message)).await?);
+        self.message_count = response.message_count().unwrap_or(self.message_count + 2);
+        Ok(response)
+    }
+
+    /// Send a message and stream the reply
+    ///
+    /// The message count grows by two once the stream opens.
+    pub async fn send_stream(&mut self, message: &str) -> RunAgentResult<AgentStream> {
+        let stream_tag = format!("{}_stream", self.chat_tag());
+        let client = self.client.with_entrypoint(&stream_tag)?;
+        let stream = AgentStream::new(client.run_stream(&self.kwargs(message)).await?);
+        self.message_count += 2;
+        Ok(stream)
+    }
+
+    /// Messages of this thread from the agent's `get_history` entrypoint
+    ///
+    /// Returns `None` when the agent architecture has no `get_history` entrypoint.
+    pub async fn history(&self) -> RunAgentResult<Option<AgentResponse>> {
+        if self.client.lists_entrypoint(HISTORY_ENTRYPOINT) == Some(false) {
+            return Ok(None);
+        }
+        let client = self.client.with_entrypoint(HISTORY_ENTRYPOINT)?;
+        let history = client
+            .run(&[
+                ("user_id", json!(self.user_id)),
+                ("thread_id", json!(self.thread_id)),
+            ])
+            .await?;
+        Ok(Some(AgentResponse::from(history)))
+    }
+
+    /// User the session's messages are stored under
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Conversation thread the session continues
+    pub fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    /// Messages exchanged in the thread, as far as this session knows
+    pub fn message_count(&self) -> u64 {
+        self.message_count
+    }
+
+    /// Get the underlying client
+    pub fn client(&self) -> &RunAgentClient {
+        &self.client
+    }
+
+    fn chat_tag(&self) -> &str {
+        let tag = self.client.entrypoint_tag();
+        tag.strip_suffix("_stream").unwrap_or(tag)
+    }
+
+    fn kwargs(&self, message: &str) -> Vec<(&'static str, Value)> {
+        vec![
+            ("message", json!(message)),
+            ("user_id", json!(self.user_id)),
+            ("thread_id", json!(self.thread_id)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::RunAgentClientConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers run requests like the LangGraph chat agent, counting messages per server
+    async fn chat_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut messages = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body: Value = loop {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((_, body)) = text.split_once("\r\n\r\n") {
+                        if let Ok(body) = serde_json::from_str(body) {
+                            break body;
+                        }
+                    }
+                };
+
+                let kwargs = &body["input_kwargs"];
+                let data = match body["entrypoint_tag"].as_str() {
+                    Some("chat") => {
+                        messages += 2;
+                        json!({
+                            "response": format!("echo: {}", kwargs["message"].as_str().unwrap()),
+                            "thread_id": kwargs["thread_id"],
+                            "message_count": messages
+                        })
+                    }
+                    _ => json!({"user_id": kwargs["user_id"], "thread_id": kwargs["thread_id"]}),
+                };
+                let response = json!({"success": true, "data": data}).to_string();
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_session_threads_ids_and_counts_messages() {
+        let config = RunAgentClientConfig::new("agent-id", "chat_stream")
+            .with_api_key("key")
+            .with_base_url(chat_server().await);
+        let client = RunAgentClient::build(config).await.unwrap();
+        let mut session = ChatSession::new(client, "u1", "t1");
+
+        let reply = session.send("hi").await.unwrap();
+        assert_eq!(reply.text(), Some("echo: hi"));
+        assert_eq!(reply.thread_id(), Some("t1"));
+        session.send("again").await.unwrap();
+        assert_eq!(session.message_count(), 4);
+
+        let history = session.history().await.unwrap().unwrap();
+        assert_eq!(
+            history.as_value(),
+            &json!({"user_id": "u1", "thread_id": "t1"})
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Connection diagnostics for a configured client
+//!
+//! [`RunAgentClient::diagnose`](crate::RunAgentClient::diagnose) runs each check
+//! in turn and collects the outcomes in a [`DiagnosticReport`], so a failing
+//! setup can be explained without trial and error.
+
+use std::fmt;
+
+/// What a diagnostic check verifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The server answers its health endpoint
+    Reachability,
+    /// The server accepts the client's credentials (remote agents)
+    Authentication,
+    /// The agent exists and exposes the client's entrypoint
+    Entrypoint,
+    /// The agent is in the local agent registry (local agents)
+    Registry,
+}
+
+impl DiagnosticKind {
+    /// Short lowercase name, as shown in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reachability => "reachability",
+            Self::Authentication => "authentication",
+            Self::Entrypoint => "entrypoint",
+            Self::Registry => "registry",
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outcome of a diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStatus {
+    Passed,
+    Failed,
+    /// Not applicable to this client, or blocked by an earlier failure
+    Skipped,
+}
+
+/// Result of one diagnostic check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticCheck {
+    pub kind: DiagnosticKind,
+    pub status: DiagnosticStatus,
+    /// What was found
+    pub message: String,
+    /// How to fix a failed check
+    pub suggestion: Option<String>,
+}
+
+impl DiagnosticCheck {
+    pub(crate) fn passed(kind: DiagnosticKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status: DiagnosticStatus::Passed,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    pub(crate) fn failed(
+        kind: DiagnosticKind,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            status: DiagnosticStatus::Failed,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+        }
+    }
+
+    pub(crate) fn skipped(kind: DiagnosticKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status: DiagnosticStatus::Skipped,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+}
+
+/// Checks run by [`RunAgentClient::diagnose`](crate::RunAgentClient::diagnose), in order
+///
+/// `Display` renders one line per check, followed by its suggestion when it
+/// failed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosticReport {
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticReport {
+    /// Whether no check failed
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// The checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &DiagnosticCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == DiagnosticStatus::Failed)
+    }
+
+    /// The result of one kind of check
+    pub fn get(&self, kind: DiagnosticKind) -> Option<&DiagnosticCheck> {
+        self.checks.iter().find(|check| check.kind == kind)
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                DiagnosticStatus::Passed => "PASS",
+                DiagnosticStatus::Failed => "FAIL",
+                DiagnosticStatus::Skipped => "SKIP",
+            };
+            writeln!(f, "[{}] {}: {}", status, check.kind, check.message)?;
+            if let Some(suggestion) = &check.suggestion {
+                writeln!(f, "       {}", suggestion)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! Hooks run around every REST request
+//!
+//! Interceptors see each request before it is sent and each response before its
+//! body is read, e.g. to inject headers, refresh credentials or log slow calls.
+//! Both hooks have no-op defaults, so an interceptor only implements what it
+//! needs. Hooks are async via [`async_trait`]; a body that never awaits keeps
+//! an interceptor effectively synchronous.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use runagent::client::{RequestInterceptor, RequestParts};
+//! use runagent::{async_trait, RunAgentClient, RunAgentClientConfig, RunAgentResult};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! struct TraceAndTime;
+//!
+//! #[async_trait]
+//! impl RequestInterceptor for TraceAndTime {
+//!     async fn on_request(&self, request: &mut RequestParts) -> RunAgentResult<()> {
+//!         request.set_header("X-Trace-Id", &uuid::Uuid::new_v4().to_string())
+//!     }
+//!
+//!     async fn on_response(&self, response: &reqwest::Response, elapsed: Duration) {
+//!         if elapsed > Duration::from_secs(2) {
+//!             tracing::warn!("slow response from {}: {:?}", response.url(), elapsed);
+//!         }
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> RunAgentResult<()> {
+//!     let client = RunAgentClient::new(
+//!         RunAgentClientConfig::new("agent-id", "entrypoint")
+//!             .with_api_key("key")
+//!             .with_interceptor(Arc::new(TraceAndTime)),
+//!     )
+//!     .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::types::{RunAgentError, RunAgentResult};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Response};
+use std::fmt;
+use std::time::Duration;
+use url::Url;
+
+/// Mutable view of an outgoing REST request
+///
+/// The body is not exposed; it is sent as built by the client.
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    /// HTTP method
+    pub method: Method,
+    /// Full request URL, including query parameters
+    pub url: Url,
+    /// Request headers, including `Authorization` when an API key is set
+    pub headers: HeaderMap,
+}
+
+impl RequestParts {
+    /// Set a header, replacing any existing value
+    pub fn set_header(&mut self, name: &str, value: &str) -> RunAgentResult<()> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| RunAgentError::validation(format!("Invalid header name: {}", e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| RunAgentError::validation(format!("Invalid header value: {}", e)))?;
+        self.headers.insert(name, value);
+        Ok(())
+    }
+}
+
+/// Hook into every request sent by a [`RestClient`](crate::RestClient)
+///
+/// Interceptors run in registration order. An error from `on_request` aborts
+/// the request and is returned to the caller. `on_response` runs for every
+/// response received, including HTTP error statuses, but not for requests that
+/// fail to connect.
+#[async_trait]
+pub trait RequestInterceptor: Send + Sync {
+    /// Inspect or modify a request before it is sent
+    async fn on_request(&self, _request: &mut RequestParts) -> RunAgentResult<()> {
+        Ok(())
+    }
+
+    /// Observe a response and how long it took, before its body is read
+    async fn on_response(&self, _response: &Response, _elapsed: Duration) {}
+}
+
+impl fmt::Debug for dyn RequestInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RequestInterceptor")
+    }
+}
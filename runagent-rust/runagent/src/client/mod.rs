@@ -1,10 +1,34 @@
 //! Client components for interacting with RunAgent deployments
 
+pub mod chat;
+pub mod diagnostics;
+pub mod interceptor;
+pub mod pool;
 pub mod rest_client;
+pub mod retry;
 pub mod runagent_client;
 pub mod socket_client;
+pub mod sse_client;
+pub mod stream;
+pub mod tls;
+pub mod token;
+pub mod transport;
+#[cfg(all(unix, feature = "unix-socket"))]
+pub(crate) mod unix;
 
 // Re-export the main client
+pub use chat::ChatSession;
+pub use diagnostics::{DiagnosticCheck, DiagnosticKind, DiagnosticReport, DiagnosticStatus};
+pub use interceptor::{RequestInterceptor, RequestParts};
+pub use pool::RunAgentClientPool;
 pub use rest_client::RestClient;
+pub use retry::RetryPolicy;
 pub use runagent_client::{RunAgentClient, RunAgentClientConfig};
-pub use socket_client::SocketClient;
+pub use socket_client::{
+    SessionSender, SocketClient, SocketClientConfig, StreamPolicy, StreamSession,
+};
+pub use sse_client::{SseClient, StreamTransport};
+pub use stream::{AgentStream, ChunkStreamExt, RunStream, SequencedChunk, StreamItem};
+pub use tls::TlsConfig;
+pub use token::{BearerToken, TokenProvider};
+pub use transport::Transport;
@@ -0,0 +1,278 @@
+//! Shared HTTP connections and architecture caching across clients
+
+use crate::client::runagent_client::with_connect_deadline;
+use crate::client::tls::TlsConfig;
+use crate::client::{RunAgentClient, RunAgentClientConfig};
+use crate::constants::DEFAULT_ARCHITECTURE_CACHE_TTL_SECONDS;
+use crate::types::RunAgentResult;
+use crate::utils::clock::{Clock, SystemClock};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Factory for [`RunAgentClient`]s that share one connection pool
+///
+/// Every client created through the pool reuses the same `reqwest::Client`, so
+/// connections and TLS sessions are kept across instances. Agent architectures
+/// (and the regional endpoint announced by the server) are cached per agent for
+/// a configurable TTL, so creating another client for a known agent skips the
+/// setup round-trips.
+///
+/// Configs with [`TlsConfig`] settings cannot use the shared client, which carries
+/// no client certificate. The pool builds one HTTP client per distinct
+/// `TlsConfig` instead, and shares it between the clients using those settings.
+///
+/// The pool is cheap to clone; clones share the HTTP clients and the cache.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::{RunAgentClientConfig, RunAgentClientPool};
+/// use std::time::Duration;
+///
+/// # async fn example() -> runagent::RunAgentResult<()> {
+/// let pool = RunAgentClientPool::new()?.with_architecture_ttl(Duration::from_secs(60));
+///
+/// let first = pool.client(RunAgentClientConfig::new("agent-id", "generic")).await?;
+/// // Reuses the pooled connections and the cached architecture
+/// let second = pool.client(RunAgentClientConfig::new("agent-id", "generic_stream")).await?;
+///
+/// // Force the next client for this agent to fetch the architecture again
+/// pool.invalidate("agent-id");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RunAgentClientPool {
+    http_client: Client,
+    tls_clients: Arc<Mutex<HashMap<TlsConfig, Client>>>,
+    architecture_ttl: Duration,
+    cache: Arc<Mutex<HashMap<CacheKey, CachedSetup>>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// Cache entries are scoped to the server they were fetched from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    base_url: String,
+    agent_id: String,
+}
+
+#[derive(Debug, Clone)]
+struct CachedSetup {
+    fetched_at: Instant,
+    architecture: Value,
+    region_base_url: Option<String>,
+}
+
+impl RunAgentClientPool {
+    /// Create a pool with the SDK's default HTTP client
+    pub fn new() -> RunAgentResult<Self> {
+        Ok(Self::with_http_client(
+            crate::RestClient::default_http_client()?,
+        ))
+    }
+
+    /// Create a pool around an existing HTTP client
+    pub fn with_http_client(http_client: Client) -> Self {
+        Self {
+            http_client,
+            tls_clients: Arc::default(),
+            architecture_ttl: Duration::from_secs(DEFAULT_ARCHITECTURE_CACHE_TTL_SECONDS),
+            cache: Arc::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Set how long fetched architectures are reused (`Duration::ZERO` disables caching)
+    pub fn with_architecture_ttl(mut self, ttl: Duration) -> Self {
+        self.architecture_ttl = ttl;
+        self
+    }
+
+    #[cfg(test)]
+    fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Create a client that shares this pool's connections and cache
+    ///
+    /// Any `http_client` set on the config is replaced by the pool's client, or by
+    /// the pool's client for the config's TLS settings if it has any.
+    pub async fn client(&self, config: RunAgentClientConfig) -> RunAgentResult<RunAgentClient> {
+        let http_client = match &config.tls {
+            Some(tls) => self.tls_http_client(tls)?,
+            None => self.http_client.clone(),
+        };
+        with_connect_deadline(
+            config.connect_timeout,
+            self.client_within(config, http_client),
+        )
+        .await
+    }
+
+    async fn client_within(
+        &self,
+        config: RunAgentClientConfig,
+        http_client: Client,
+    ) -> RunAgentResult<RunAgentClient> {
+        let mut client = RunAgentClient::build_pooled(config, http_client).await?;
+        let key = CacheKey {
+            base_url: client.endpoint_key(),
+            agent_id: client.agent_id().to_string(),
+        };
+
+        if let Some(cached) = self.cached(&key) {
+            tracing::debug!("Reusing cached architecture for agent {}", key.agent_id);
+            client.restore_setup_state(cached.architecture, cached.region_base_url)?;
+            return Ok(client);
+        }
+
+        client.discover_region().await;
+        client.initialize_architecture().await?;
+
+        if let Some((architecture, region_base_url)) = client.setup_state() {
+            self.store(key, architecture, region_base_url);
+        }
+        Ok(client)
+    }
+
+    /// Drop cached architectures for an agent, on every server
+    pub fn invalidate(&self, agent_id: &str) {
+        self.lock_cache().retain(|key, _| key.agent_id != agent_id);
+    }
+
+    /// Drop all cached architectures
+    pub fn clear(&self) {
+        self.lock_cache().clear();
+    }
+
+    /// Get the shared HTTP client
+    pub fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+
+    /// Shared HTTP client presenting `tls`, built on first use
+    fn tls_http_client(&self, tls: &TlsConfig) -> RunAgentResult<Client> {
+        let mut clients = self.tls_clients.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(client) = clients.get(tls) {
+            return Ok(client.clone());
+        }
+        let client = tls.http_client()?;
+        clients.insert(tls.clone(), client.clone());
+        Ok(client)
+    }
+
+    fn cached(&self, key: &CacheKey) -> Option<CachedSetup> {
+        let now = self.clock.now();
+        let mut cache = self.lock_cache();
+        match cache.get(key) {
+            Some(entry) if now.duration_since(entry.fetched_at) < self.architecture_ttl => {
+                Some(entry.clone())
+            }
+            Some(_) => {
+                cache.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(&self, key: CacheKey, architecture: Value, region_base_url: Option<String>) {
+        if self.architecture_ttl.is_zero() {
+            return;
+        }
+        let entry = CachedSetup {
+            fetched_at: self.clock.now(),
+            architecture,
+            region_base_url,
+        };
+        self.lock_cache().insert(key, entry);
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<CacheKey, CachedSetup>> {
+        // The cache holds no invariants a panicking holder could break
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for RunAgentClientPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunAgentClientPool")
+            .field("architecture_ttl", &self.architecture_ttl)
+            .field("cached_agents", &self.lock_cache().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::TestClock;
+    use serde_json::json;
+
+    fn key(agent_id: &str) -> CacheKey {
+        CacheKey {
+            base_url: "http://localhost:8450".to_string(),
+            agent_id: agent_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_architecture_cache_expires_and_invalidates() {
+        let clock = Arc::new(TestClock::new());
+        let pool = RunAgentClientPool::with_http_client(Client::new())
+            .with_architecture_ttl(Duration::from_secs(60))
+            .with_clock(clock.clone());
+        let architecture = json!({"entrypoints": [{"tag": "generic"}]});
+
+        pool.store(key("a"), architecture.clone(), None);
+        pool.store(key("b"), architecture.clone(), None);
+        assert_eq!(pool.cached(&key("a")).unwrap().architecture, architecture);
+
+        pool.invalidate("b");
+        assert!(pool.cached(&key("b")).is_none());
+
+        clock.advance(Duration::from_secs(60));
+        assert!(pool.cached(&key("a")).is_none());
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_pooled_clients_share_one_http_client_per_tls_config() {
+        use crate::client::Transport;
+
+        /// Answers every request with an agent exposing a `chat` entrypoint
+        struct Agent;
+
+        #[async_trait::async_trait]
+        impl Transport for Agent {
+            async fn execute(
+                &self,
+                _request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                let body = json!({"success": true, "data": {"entrypoints": [{"tag": "chat"}]}});
+                Ok(http::Response::new(body.to_string()).into())
+            }
+        }
+
+        let pool = RunAgentClientPool::with_http_client(Client::new());
+        let config = |agent_id: &str| {
+            RunAgentClientConfig::remote(agent_id, "chat", "key")
+                .with_transport(Arc::new(Agent))
+                .with_tls(TlsConfig::new())
+        };
+
+        pool.client(config("a")).await.unwrap();
+        pool.client(config("b")).await.unwrap();
+        assert_eq!(pool.tls_clients.lock().unwrap().len(), 1);
+
+        // Unreadable certificates still fail with a config error
+        let missing = config("c").with_tls(TlsConfig::new().with_root_ca("/nonexistent/ca.pem"));
+        let err = pool.client(missing).await.err().unwrap();
+        assert!(matches!(err, crate::RunAgentError::Config { .. }));
+    }
+}
@@ -1,19 +1,53 @@
 //! REST client for HTTP API interactions
 
-use crate::types::{RunAgentError, RunAgentResult};
+use crate::client::interceptor::{RequestInterceptor, RequestParts};
+use crate::client::token::TokenProvider;
+use crate::client::transport::Transport;
+#[cfg(all(unix, feature = "unix-socket"))]
+use crate::client::unix::UnixTransport;
+use crate::constants::{
+    COMPRESSION_THRESHOLD_BYTES, IDEMPOTENCY_KEY_HEADER, LIST_AGENTS_PAGE_SIZE, REGION_URL_FIELD,
+    REGION_URL_HEADER, STREAMED_BODY_CHUNK_BYTES, STREAMED_BODY_THRESHOLD_BYTES,
+    STREAM_TIMEOUT_SECONDS,
+};
+#[cfg(feature = "archive")]
+use crate::constants::{MAX_UPLOAD_FOLDER_MB, RUNAGENT_IGNORE_FILE};
+use crate::types::attachment::MultipartBody;
+use crate::types::{
+    extract_error, AgentSummary, ErrorCode, PaginatedResponse, RunAgentError, RunAgentResult,
+};
+#[cfg(feature = "archive")]
+use crate::utils::archive;
 use crate::utils::config::Config;
-use reqwest::{Client, Method, Response};
+use crate::utils::serializer::json_len;
+#[cfg(feature = "archive")]
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Client, Method, RequestBuilder, Response};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+#[cfg(feature = "archive")]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use url::Url;
 
 /// REST client for API interactions
+#[derive(Clone)]
 pub struct RestClient {
     client: Client,
     base_url: String,
     api_key: Option<String>,
     api_prefix: String,
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    token_provider: Option<TokenProvider>,
+    compress_requests: bool,
+    accept_msgpack: bool,
+    max_request_bytes: Option<usize>,
+    transport: Option<Arc<dyn Transport>>,
+    user_agent: String,
+    region_hosts: Vec<String>,
+    #[cfg(all(unix, feature = "unix-socket"))]
+    unix: Option<UnixTransport>,
 }
 
 impl RestClient {
@@ -23,21 +57,209 @@ impl RestClient {
         api_key: Option<String>,
         api_prefix: Option<&str>,
     ) -> RunAgentResult<Self> {
-        // Increase timeout to 10 minutes (600 seconds) to match agent execution timeout
-        let client = Client::builder()
-            .timeout(Duration::from_secs(600))
-            .user_agent("RunAgent-Rust-SDK/0.1.0")
-            .build()?;
+        let client = Self::default_http_client()?;
+        Ok(Self::with_http_client(
+            client, base_url, api_key, api_prefix,
+        ))
+    }
 
+    /// Create a REST client on top of an existing HTTP client
+    ///
+    /// `reqwest::Client` clones share one connection pool, so passing clones of the
+    /// same client lets many REST clients reuse connections.
+    pub fn with_http_client(
+        client: Client,
+        base_url: &str,
+        api_key: Option<String>,
+        api_prefix: Option<&str>,
+    ) -> Self {
         let base_url = base_url.trim_end_matches('/').to_string();
         let api_prefix = api_prefix.unwrap_or("/api/v1").to_string();
 
-        Ok(Self {
+        Self {
             client,
             base_url,
             api_key,
             api_prefix,
-        })
+            interceptors: Vec::new(),
+            token_provider: None,
+            compress_requests: false,
+            accept_msgpack: false,
+            max_request_bytes: None,
+            transport: None,
+            user_agent: sdk_user_agent(None),
+            region_hosts: Vec::new(),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            unix: None,
+        }
+    }
+
+    /// Identify the calling app after the SDK in the `User-Agent` header
+    ///
+    /// `suffix` is appended as-is, e.g. `lead-agent/1.0` gives
+    /// `RunAgent-Rust-SDK/<version> lead-agent/1.0`. An empty suffix leaves the
+    /// header unchanged.
+    pub fn with_user_agent_suffix(self, suffix: &str) -> Self {
+        self.with_user_agent(sdk_user_agent(Some(suffix)))
+    }
+
+    pub(crate) fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// `User-Agent` header sent with every request
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Also trust regional endpoints announced on these hosts
+    ///
+    /// By default only the base host and its subdomains are followed, see
+    /// [`RestClient::resolve_region`]. Hosts are compared case-insensitively and
+    /// without ports.
+    pub fn with_region_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.region_hosts = hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Extra hosts regional endpoints may be announced on
+    pub(crate) fn region_hosts(&self) -> &[String] {
+        &self.region_hosts
+    }
+
+    /// Gzip JSON request bodies larger than 16 KB (default: disabled)
+    ///
+    /// Compressed bodies are sent with `Content-Encoding: gzip`, which the server
+    /// must accept. Response decompression is independent of this setting.
+    pub fn with_request_compression(mut self, enabled: bool) -> Self {
+        self.compress_requests = enabled;
+        self
+    }
+
+    /// Whether large JSON request bodies are gzipped
+    pub(crate) fn request_compression(&self) -> bool {
+        self.compress_requests
+    }
+
+    /// Reject JSON request bodies larger than `max_mb` megabytes before sending
+    /// (default: no limit)
+    ///
+    /// The size is measured without building the body, so an oversized request
+    /// fails fast with a validation error naming its size.
+    pub fn with_max_request_mb(self, max_mb: f64) -> Self {
+        self.with_max_request_bytes(Some((max_mb * 1024.0 * 1024.0) as usize))
+    }
+
+    /// Set or clear the request body size limit, in bytes
+    pub(crate) fn with_max_request_bytes(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_request_bytes = max_bytes;
+        self
+    }
+
+    /// Largest JSON request body sent, in bytes
+    pub(crate) fn max_request_bytes(&self) -> Option<usize> {
+        self.max_request_bytes
+    }
+
+    /// Ask for MessagePack responses, decoding them into JSON values (default: disabled)
+    ///
+    /// Requests send `Accept: application/msgpack, application/json;q=0.9`, so
+    /// servers without MessagePack support keep answering with JSON. Responses are
+    /// decoded by their `Content-Type`.
+    #[cfg(feature = "msgpack")]
+    pub fn with_msgpack(mut self, enabled: bool) -> Self {
+        self.accept_msgpack = enabled;
+        self
+    }
+
+    /// Whether MessagePack responses are requested
+    #[cfg(feature = "msgpack")]
+    pub(crate) fn msgpack(&self) -> bool {
+        self.accept_msgpack
+    }
+
+    /// Authenticate with tokens from `provider` instead of the static API key
+    ///
+    /// A request answered with 401 is retried once with a refreshed token, unless
+    /// its body is streamed.
+    pub fn with_token_provider(mut self, provider: TokenProvider) -> Self {
+        self.token_provider = Some(provider);
+        self
+    }
+
+    /// Token provider used instead of the static API key, if any
+    pub(crate) fn token_provider(&self) -> Option<&TokenProvider> {
+        self.token_provider.as_ref()
+    }
+
+    /// Run `interceptor` around every request, after those already registered
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn RequestInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Interceptors run around every request, in order
+    pub(crate) fn interceptors(&self) -> &[Arc<dyn RequestInterceptor>] {
+        &self.interceptors
+    }
+
+    /// Send requests through `transport` instead of the HTTP client
+    ///
+    /// Takes precedence over a Unix socket. Interceptors and token refresh still
+    /// run around the transport.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Custom transport requests are sent through, if any
+    pub(crate) fn transport(&self) -> Option<&Arc<dyn Transport>> {
+        self.transport.as_ref()
+    }
+
+    /// Send requests over a Unix domain socket instead of TCP
+    ///
+    /// URLs are still built from the base URL (e.g. `http://localhost`), but the
+    /// host and port are never resolved: every connection goes to `path`.
+    #[cfg(all(unix, feature = "unix-socket"))]
+    pub fn with_unix_socket(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.unix = Some(UnixTransport::new(path.into()));
+        self
+    }
+
+    /// Get the Unix domain socket requests are sent over, if any
+    pub fn unix_socket(&self) -> Option<&std::path::Path> {
+        #[cfg(all(unix, feature = "unix-socket"))]
+        {
+            self.unix.as_ref().map(|unix| unix.path())
+        }
+        #[cfg(not(all(unix, feature = "unix-socket")))]
+        {
+            None
+        }
+    }
+
+    /// Build the HTTP client the SDK uses by default
+    pub fn default_http_client() -> RunAgentResult<Client> {
+        Ok(Self::default_http_client_builder().build()?)
+    }
+
+    /// Builder preset with the default HTTP client's settings
+    pub(crate) fn default_http_client_builder() -> reqwest::ClientBuilder {
+        // Increase timeout to 10 minutes (600 seconds) to match agent execution timeout
+        Client::builder()
+            .timeout(Duration::from_secs(600))
+            .user_agent(sdk_user_agent(None))
+    }
+
+    /// Get the underlying HTTP client
+    pub fn http_client(&self) -> &Client {
+        &self.client
     }
 
     /// Create a default REST client using configuration
@@ -47,6 +269,11 @@ impl RestClient {
         Self::new(&config.base_url(), config.api_key(), Some("/api/v1"))
     }
 
+    /// Get the base URL this client targets
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     fn get_url(&self, path: &str) -> RunAgentResult<Url> {
         let path = path.strip_prefix('/').unwrap_or(path);
         let full_path = format!("{}{}/{}", self.base_url, self.api_prefix, path);
@@ -54,83 +281,122 @@ impl RestClient {
     }
 
     async fn handle_response(&self, response: Response) -> RunAgentResult<Value> {
+        if !response.status().is_success() {
+            return Err(self.response_error(response).await);
+        }
+
+        #[cfg(feature = "msgpack")]
+        if is_msgpack(&response) {
+            let bytes = response.bytes().await?;
+            return crate::utils::serializer::msgpack_to_value(&bytes);
+        }
+
+        let json: Value = response.json().await?;
+        Ok(json)
+    }
+
+    /// Map a non-2xx response to the matching error
+    async fn response_error(&self, response: Response) -> RunAgentError {
         let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        let raw_body = match response.text().await {
+            Ok(raw_body) => raw_body,
+            Err(e) => return e.into(),
+        };
+        let body = serde_json::from_str::<Value>(&raw_body).ok();
+        let error_msg = match body.as_ref().and_then(error_message) {
+            Some(message) => message,
+            None if raw_body.is_empty() => format!("HTTP Error: {}", status),
+            None => raw_body.clone(),
+        };
+        // Structured code from an `{"error": {"code": ...}}` body, if any
+        let code = body
+            .as_ref()
+            .and_then(|json| json.get("error"))
+            .and_then(|error| error.get("code"))
+            .and_then(|code| code.as_str())
+            .map(ErrorCode::from_code);
 
-        if status.is_success() {
-            let json: Value = response.json().await?;
-            Ok(json)
-        } else {
-            let error_text = response.text().await?;
-            let error_msg = if error_text.is_empty() {
-                format!("HTTP Error: {}", status)
-            } else {
-                // Try to parse as JSON to get error details
-                if let Ok(json) = serde_json::from_str::<Value>(&error_text) {
-                    // Try to extract nested error message
-                    if let Some(error_obj) = json.get("error") {
-                        if let Some(message) = error_obj.get("message").and_then(|m| m.as_str()) {
-                            message.to_string()
-                        } else if let Some(detail) = json.get("detail").and_then(|d| d.as_str()) {
-                            detail.to_string()
-                        } else if let Some(message) = json.get("message").and_then(|m| m.as_str()) {
-                            message.to_string()
-                        } else if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
-                            error.to_string()
-                        } else {
-                            error_text
-                        }
-                    } else if let Some(detail) = json.get("detail").and_then(|d| d.as_str()) {
-                        detail.to_string()
-                    } else if let Some(message) = json.get("message").and_then(|m| m.as_str()) {
-                        message.to_string()
-                    } else if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
-                        error.to_string()
-                    } else {
-                        error_text
-                    }
-                } else {
-                    error_text
-                }
-            };
+        if status.as_u16() == 403 || code == Some(ErrorCode::PermissionDenied) {
+            return RunAgentError::execution(
+                ErrorCode::PermissionDenied.as_str().to_string(),
+                format!("Access denied: {}", error_msg),
+                Some("Check that the agent belongs to your account, that your API key may access it, and that the agent ID is correct".to_string()),
+                body,
+            );
+        }
 
-            // Check if error message contains permission/403 info even if status is 500
-            if error_msg.contains("permission")
-                || error_msg.contains("403")
-                || error_msg.contains("do not have permission")
-            {
-                return Err(RunAgentError::authentication(format!(
-                    "Access denied: {}. This usually means:\n  - The agent doesn't belong to your account\n  - Your API key doesn't have permission to access this agent\n  - The agent ID is incorrect", error_msg
-                )));
-            }
+        match status.as_u16() {
+            401 => RunAgentError::authentication(error_msg),
+            429 => RunAgentError::rate_limited(error_msg, retry_after),
+            _ if code.is_some_and(|code| code != ErrorCode::Unknown) => RunAgentError::execution(
+                code.map(|code| code.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                error_msg,
+                None,
+                body,
+            ),
+            400 | 422 => RunAgentError::validation(error_msg),
+            404 => RunAgentError::validation(format!("Not found: {}", error_msg)),
+            500..=599 => RunAgentError::server_response(error_msg, status.as_u16(), &raw_body),
+            _ => RunAgentError::connection(error_msg),
+        }
+    }
 
-            match status.as_u16() {
-                401 => Err(RunAgentError::authentication(error_msg)),
-                403 => Err(RunAgentError::authentication(format!(
-                    "Access denied: {}. This usually means:\n  - The agent doesn't belong to your account\n  - Your API key doesn't have permission to access this agent\n  - The agent ID is incorrect", error_msg
-                ))),
-                400 | 422 => Err(RunAgentError::validation(error_msg)),
-                404 => Err(RunAgentError::validation(format!("Not found: {}", error_msg))),
-                500..=599 => Err(RunAgentError::server(format!("Server error: {}", error_msg))),
-                _ => Err(RunAgentError::connection(error_msg)),
-            }
+    fn build_request(&self, method: Method, path: &str) -> RunAgentResult<RequestBuilder> {
+        let mut url = self.get_url(path)?;
+
+        // Add API key as token query parameter if available (matching WebSocket behavior)
+        if let Some(ref api_key) = self.api_key {
+            url.query_pairs_mut().append_pair("token", api_key);
         }
+
+        // Set per request so caller-supplied HTTP clients send it too
+        let mut request_builder = self
+            .client
+            .request(method, url)
+            .header(reqwest::header::USER_AGENT, &self.user_agent);
+
+        // Add Authorization header if API key is available
+        if let Some(ref api_key) = self.api_key {
+            request_builder =
+                request_builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        Ok(request_builder)
     }
 
+    #[tracing::instrument(
+        name = "runagent.http",
+        skip(self, body, params, timeout),
+        fields(base_url = %self.base_url)
+    )]
     async fn request(
         &self,
         method: Method,
         path: &str,
-        data: Option<&Value>,
+        body: Option<JsonBody<'_>>,
         params: Option<&HashMap<String, String>>,
+        timeout: Option<Duration>,
     ) -> RunAgentResult<Value> {
-        let mut url = self.get_url(path)?;
+        let mut request_builder = self.build_request(method, path)?;
 
-        // Add API key as token query parameter if available (matching WebSocket behavior)
-        if let Some(ref api_key) = self.api_key {
-            url.query_pairs_mut().append_pair("token", api_key);
+        // Override the client-wide timeout for this request
+        if let Some(timeout) = timeout {
+            request_builder = request_builder.timeout(timeout);
         }
 
-        let mut request_builder = self.client.request(method, url);
+        if self.accept_msgpack {
+            request_builder = request_builder.header(
+                reqwest::header::ACCEPT,
+                "application/msgpack, application/json;q=0.9",
+            );
+        }
 
         // Add query parameters
         if let Some(params) = params {
@@ -138,20 +404,138 @@ impl RestClient {
         }
 
         // Add JSON body for POST/PUT requests
-        if let Some(data) = data {
-            request_builder = request_builder
-                .header("Content-Type", "application/json")
-                .json(data);
+        if let Some(body) = body {
+            // Repeat a run's idempotency key as a header for servers that dedupe on it
+            if let Some(key) = body.value().get("idempotency_key").and_then(Value::as_str) {
+                request_builder = request_builder.header(IDEMPOTENCY_KEY_HEADER, key);
+            }
+            request_builder = request_builder.header("Content-Type", "application/json");
+            request_builder = self.attach_json(request_builder, body)?;
         }
 
-        // Add Authorization header if API key is available
-        if let Some(ref api_key) = self.api_key {
-            request_builder =
-                request_builder.header("Authorization", format!("Bearer {}", api_key));
+        let response = self.send(request_builder).await?;
+        self.handle_response(response).await
+    }
+
+    /// Attach a JSON body, checking its size and streaming or gzipping it as configured
+    fn attach_json(
+        &self,
+        request_builder: RequestBuilder,
+        body: JsonBody<'_>,
+    ) -> RunAgentResult<RequestBuilder> {
+        let streamable = matches!(body, JsonBody::Shared(_)) && self.streams_bodies();
+        if self.max_request_bytes.is_some() || streamable {
+            let size = json_len(body.value())?;
+            self.check_request_size(size)?;
+            if let (JsonBody::Shared(data), true) =
+                (&body, streamable && size >= STREAMED_BODY_THRESHOLD_BYTES)
+            {
+                tracing::debug!("Streaming {} byte request body", size);
+                return Ok(request_builder
+                    .header(reqwest::header::CONTENT_LENGTH, size)
+                    .body(streamed_json(data.clone())));
+            }
         }
 
-        let response = request_builder.send().await?;
-        self.handle_response(response).await
+        let body = serde_json::to_vec(body.value())?;
+        Ok(
+            if self.compress_requests && body.len() >= COMPRESSION_THRESHOLD_BYTES {
+                request_builder
+                    .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                    .body(gzip(&body)?)
+            } else {
+                request_builder.body(body)
+            },
+        )
+    }
+
+    /// Fail when a body of `size` bytes is over the request size limit
+    fn check_request_size(&self, size: usize) -> RunAgentResult<()> {
+        const MIB: f64 = 1024.0 * 1024.0;
+        match self.max_request_bytes {
+            Some(limit) if size > limit => Err(RunAgentError::validation(format!(
+                "Request body is {} bytes ({:.2} MB), exceeding the {} byte ({:.2} MB) limit; \
+                 send less input per run or raise it with RunAgentClientConfig::with_max_request_mb",
+                size,
+                size as f64 / MIB,
+                limit,
+                limit as f64 / MIB
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether bodies can be sent while they are serialized
+    ///
+    /// Gzipped bodies are compressed whole, and the Unix socket transport needs
+    /// the full body up front.
+    fn streams_bodies(&self) -> bool {
+        if self.compress_requests {
+            return false;
+        }
+        #[cfg(all(unix, feature = "unix-socket"))]
+        if self.unix.is_some() && self.transport.is_none() {
+            return false;
+        }
+        true
+    }
+
+    /// Send a request over the configured transport
+    async fn send(&self, request_builder: RequestBuilder) -> RunAgentResult<Response> {
+        let mut request = request_builder.build()?;
+        let Some(provider) = &self.token_provider else {
+            return self.dispatch(request).await;
+        };
+
+        set_token(&mut request, &provider.token().await?)?;
+        let retry = request.try_clone();
+        let response = self.dispatch(request).await?;
+        match retry {
+            Some(mut retry) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                tracing::debug!("🔑 Request unauthorized, retrying with a refreshed token");
+                set_token(&mut retry, &provider.refresh().await?)?;
+                self.dispatch(retry).await
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Run interceptors around one request on the configured transport
+    async fn dispatch(&self, mut request: reqwest::Request) -> RunAgentResult<Response> {
+        if !self.interceptors.is_empty() {
+            let mut parts = RequestParts {
+                method: request.method().clone(),
+                url: request.url().clone(),
+                headers: std::mem::take(request.headers_mut()),
+            };
+            for interceptor in &self.interceptors {
+                interceptor.on_request(&mut parts).await?;
+            }
+            *request.method_mut() = parts.method;
+            *request.url_mut() = parts.url;
+            *request.headers_mut() = parts.headers;
+        }
+
+        let started = Instant::now();
+        let response = self.execute(request).await?;
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&response, started.elapsed()).await;
+        }
+        Ok(response)
+    }
+
+    /// Execute a built request on the configured transport
+    async fn execute(&self, request: reqwest::Request) -> RunAgentResult<Response> {
+        if let Some(transport) = &self.transport {
+            return transport.execute(request).await;
+        }
+
+        #[cfg(all(unix, feature = "unix-socket"))]
+        if let Some(unix) = &self.unix {
+            return unix.send(request).await;
+        }
+
+        Transport::execute(&self.client, request).await
     }
 
     /// Send a GET request
@@ -165,22 +549,36 @@ impl RestClient {
         path: &str,
         params: Option<&HashMap<String, String>>,
     ) -> RunAgentResult<Value> {
-        self.request(Method::GET, path, None, params).await
+        self.request(Method::GET, path, None, params, None).await
     }
 
     /// Send a POST request
     pub async fn post(&self, path: &str, data: &Value) -> RunAgentResult<Value> {
-        self.request(Method::POST, path, Some(data), None).await
+        self.request(
+            Method::POST,
+            path,
+            Some(JsonBody::Borrowed(data)),
+            None,
+            None,
+        )
+        .await
     }
 
     /// Send a PUT request
     pub async fn put(&self, path: &str, data: &Value) -> RunAgentResult<Value> {
-        self.request(Method::PUT, path, Some(data), None).await
+        self.request(
+            Method::PUT,
+            path,
+            Some(JsonBody::Borrowed(data)),
+            None,
+            None,
+        )
+        .await
     }
 
     /// Send a DELETE request
     pub async fn delete(&self, path: &str) -> RunAgentResult<Value> {
-        self.request(Method::DELETE, path, None, None).await
+        self.request(Method::DELETE, path, None, None, None).await
     }
 
     /// Run an agent via REST API
@@ -193,6 +591,25 @@ impl RestClient {
         user_id: Option<&str>,
         persistent_memory: bool,
     ) -> RunAgentResult<Value> {
+        let data = Self::run_request(
+            entrypoint_tag,
+            input_args,
+            input_kwargs,
+            user_id,
+            persistent_memory,
+        );
+        self.send_run_request(agent_id, entrypoint_tag, &data, None)
+            .await
+    }
+
+    /// Build the body `run_agent` posts to the agent run endpoint
+    pub fn run_request(
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &HashMap<String, Value>,
+        user_id: Option<&str>,
+        persistent_memory: bool,
+    ) -> Value {
         let mut data = serde_json::json!({
             "id": "run_start",
             "entrypoint_tag": entrypoint_tag,
@@ -217,6 +634,52 @@ impl RestClient {
             }
         }
 
+        data
+    }
+
+    /// Post a run request body built with [`RestClient::run_request`]
+    ///
+    /// `timeout` overrides the client-wide request timeout; when it elapses the
+    /// call fails with [`RunAgentError::Timeout`].
+    pub async fn send_run_request(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        data: &Value,
+        timeout: Option<Duration>,
+    ) -> RunAgentResult<Value> {
+        self.post_run_request(agent_id, entrypoint_tag, JsonBody::Borrowed(data), timeout)
+            .await
+    }
+
+    /// Post a shared run request body, streaming it when it is large
+    ///
+    /// Bodies of 4 MB and more are serialized while they are sent instead of
+    /// being built in memory first, unless request compression or the Unix socket
+    /// transport is in use. A streamed body is not retried on 401.
+    pub(crate) async fn send_shared_run_request(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        data: &Arc<Value>,
+        timeout: Option<Duration>,
+    ) -> RunAgentResult<Value> {
+        self.post_run_request(
+            agent_id,
+            entrypoint_tag,
+            JsonBody::Shared(data.clone()),
+            timeout,
+        )
+        .await
+    }
+
+    async fn post_run_request(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        body: JsonBody<'_>,
+        timeout: Option<Duration>,
+    ) -> RunAgentResult<Value> {
         let path = format!("agents/{}/run", agent_id);
         let url = self.get_url(&path)?;
         tracing::debug!(
@@ -226,17 +689,106 @@ impl RestClient {
             url
         );
 
-        self.post(&path, &data).await
-            .map_err(|e| {
-                if e.category() == "validation" && e.to_string().contains("Not found") {
-                    RunAgentError::validation(format!(
-                        "Agent {} not found on server at {}. Check that:\n  - The agent exists and is deployed\n  - The agent ID is correct\n  - The base URL ({}) is correct\n  - Your API key is valid (if required)",
-                        agent_id, url, self.base_url
-                    ))
-                } else {
-                    e
-                }
-            })
+        self.request(Method::POST, &path, Some(body), None, timeout)
+            .await
+            .map_err(|e| self.run_error(agent_id, &url, e))
+    }
+
+    /// Post a `multipart/form-data` run request carrying attachments
+    ///
+    /// The body counts toward the request size limit as a whole.
+    pub(crate) async fn send_multipart_run_request(
+        &self,
+        agent_id: &str,
+        request: &Value,
+        body: &MultipartBody,
+        timeout: Option<Duration>,
+    ) -> RunAgentResult<Value> {
+        let path = format!("agents/{}/run", agent_id);
+        let url = self.get_url(&path)?;
+        tracing::debug!(
+            "Running agent {} with a {} byte multipart request at {}",
+            agent_id,
+            body.bytes.len(),
+            url
+        );
+        self.check_request_size(body.bytes.len())?;
+
+        let mut request_builder = self
+            .build_request(Method::POST, &path)?
+            .header(reqwest::header::CONTENT_TYPE, &body.content_type)
+            .body(body.bytes.clone());
+        if let Some(timeout) = timeout {
+            request_builder = request_builder.timeout(timeout);
+        }
+        if self.accept_msgpack {
+            request_builder = request_builder.header(
+                reqwest::header::ACCEPT,
+                "application/msgpack, application/json;q=0.9",
+            );
+        }
+        if let Some(key) = request.get("idempotency_key").and_then(Value::as_str) {
+            request_builder = request_builder.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+
+        let result = match self.send(request_builder).await {
+            Ok(response) => self.handle_response(response).await,
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| self.run_error(agent_id, &url, e))
+    }
+
+    /// Explain a "Not found" answer to a run request
+    fn run_error(&self, agent_id: &str, url: &Url, e: RunAgentError) -> RunAgentError {
+        if e.category() == "validation" && e.to_string().contains("Not found") {
+            RunAgentError::execution(
+                ErrorCode::AgentNotFound.as_str().to_string(),
+                format!("Agent {} not found on server at {}", agent_id, url),
+                Some(format!(
+                    "Check that the agent exists and is deployed, that the agent ID is correct, that the base URL ({}) is correct and that your API key is valid (if required)",
+                    self.base_url
+                )),
+                None,
+            )
+        } else {
+            e
+        }
+    }
+
+    /// POST a caller-built body to the agent run endpoint as-is
+    ///
+    /// Non-2xx responses still map to errors; successful responses are returned
+    /// without any interpretation.
+    pub async fn run_agent_raw(&self, agent_id: &str, body: &Value) -> RunAgentResult<Value> {
+        let path = format!("agents/{}/run", agent_id);
+        tracing::debug!("Sending raw run body for agent {}", agent_id);
+        self.post(&path, body).await
+    }
+
+    /// POST a stream start request and return the `text/event-stream` response
+    ///
+    /// Returns once the response headers arrive, leaving the body unread. The
+    /// request may run for the stream's server-side timeout rather than the
+    /// client-wide request timeout.
+    pub(crate) async fn open_event_stream(
+        &self,
+        agent_id: &str,
+        data: &Value,
+    ) -> RunAgentResult<Response> {
+        let path = format!("agents/{}/run-stream", agent_id);
+        tracing::debug!("Opening event stream for agent {}", agent_id);
+        let request_builder = self
+            .build_request(Method::POST, &path)?
+            .timeout(Duration::from_secs(STREAM_TIMEOUT_SECONDS))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(data);
+
+        let response = self.send(request_builder).await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(self.response_error(response).await)
+        }
     }
 
     /// Get agent architecture information
@@ -247,10 +799,15 @@ impl RestClient {
         let response = self.get(&path).await
             .map_err(|e| {
                 if e.category() == "validation" && e.to_string().contains("Not found") {
-                    RunAgentError::validation(format!(
-                        "Agent {} not found at {}. Check that:\n  - The agent ID is correct\n  - The agent exists and is deployed\n  - Your API key has access to this agent\n  - The base URL ({}) is correct",
-                        agent_id, url, self.base_url
-                    ))
+                    RunAgentError::execution(
+                        ErrorCode::AgentNotFound.as_str().to_string(),
+                        format!("Agent {} not found at {}", agent_id, url),
+                        Some(format!(
+                            "Check that the agent ID is correct, that the agent exists and is deployed, that your API key has access to this agent and that the base URL ({}) is correct",
+                            self.base_url
+                        )),
+                        None,
+                    )
                 } else {
                     e
                 }
@@ -321,6 +878,125 @@ impl RestClient {
         self.get("health").await
     }
 
+    /// Health check that also reports a regional base URL announced by the server
+    ///
+    /// The server may point clients at a region-specific base URL via the
+    /// `X-RunAgent-Region-URL` response header, a `region_url` field in the health
+    /// response, or by redirecting the health request (checked in that order).
+    /// Returns `None` when no hint is present, it names the current base URL, or
+    /// it is not a trusted region (see [`RestClient::with_region_hosts`]).
+    pub async fn resolve_region(&self) -> RunAgentResult<Option<String>> {
+        let response = self
+            .send(self.build_request(Method::GET, "health")?)
+            .await?;
+        // Only the HTTP client follows redirects; other transports report a placeholder URL
+        let final_url = self.follows_redirects().then(|| response.url().clone());
+        let header = response
+            .headers()
+            .get(REGION_URL_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let body = self.handle_response(response).await?;
+
+        Ok(self.region_from_health(header.as_deref(), &body, final_url.as_ref()))
+    }
+
+    fn region_from_health(
+        &self,
+        header: Option<&str>,
+        body: &Value,
+        final_url: Option<&Url>,
+    ) -> Option<String> {
+        let hinted = header
+            .or_else(|| body.get(REGION_URL_FIELD).and_then(|v| v.as_str()))
+            .map(|url| url.trim_end_matches('/').to_string());
+
+        let region = match hinted {
+            Some(region) => region,
+            None => {
+                // A followed redirect leaves the final URL on another origin
+                let final_url = final_url?;
+                let requested = self.get_url("health").ok()?;
+                if final_url.origin() == requested.origin() {
+                    return None;
+                }
+                let suffix = format!("{}/health", self.api_prefix);
+                let base_path = final_url.path().strip_suffix(&suffix).unwrap_or("");
+                format!(
+                    "{}{}",
+                    final_url.origin().ascii_serialization(),
+                    base_path.trim_end_matches('/')
+                )
+            }
+        };
+
+        (!region.is_empty() && region != self.base_url && self.trusts_region(&region))
+            .then_some(region)
+    }
+
+    /// Whether the API key may be sent to the regional base URL `region`
+    ///
+    /// The region must not downgrade `https` to `http`, and its host must be
+    /// listed in [`RestClient::with_region_hosts`], be the base host, or be a
+    /// subdomain of it (`eu.backend.run-agent.ai` for `backend.run-agent.ai`).
+    fn trusts_region(&self, region: &str) -> bool {
+        let (Ok(base), Ok(url)) = (Url::parse(&self.base_url), Url::parse(region)) else {
+            tracing::warn!("Ignoring regional endpoint {}: not a valid URL", region);
+            return false;
+        };
+        let secure = url.scheme() == "https" || (url.scheme() == "http" && base.scheme() == "http");
+        if !secure {
+            tracing::warn!("Ignoring regional endpoint {}: insecure scheme", region);
+            return false;
+        }
+        let (Some(host), Some(base_host)) = (url.host(), base.host()) else {
+            tracing::warn!("Ignoring regional endpoint {}: no host", region);
+            return false;
+        };
+        let allowed = self
+            .region_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&host.to_string()))
+            || match (&host, &base_host) {
+                (url::Host::Domain(domain), url::Host::Domain(base_domain)) => {
+                    is_same_or_subdomain(domain, base_domain)
+                }
+                _ => host == base_host,
+            };
+        if !allowed {
+            tracing::warn!(
+                "Ignoring regional endpoint {}: host is not on {}",
+                region,
+                base_host
+            );
+        }
+        allowed
+    }
+
+    /// Whether responses carry the URL a followed redirect ended on
+    fn follows_redirects(&self) -> bool {
+        #[cfg(all(unix, feature = "unix-socket"))]
+        if self.unix.is_some() {
+            return false;
+        }
+        self.transport.is_none()
+    }
+
+    /// Path prefix of the API routes, e.g. `/api/v1`
+    pub fn api_prefix(&self) -> &str {
+        &self.api_prefix
+    }
+
+    /// Get the API key this client authenticates with, if any
+    pub(crate) fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+
+    /// Whether requests carry credentials, from an API key or a token provider
+    pub(crate) fn has_credentials(&self) -> bool {
+        self.api_key.is_some() || self.token_provider.is_some()
+    }
+
     /// Validate API connection
     pub async fn validate_api_connection(&self) -> RunAgentResult<Value> {
         match self.health_check().await {
@@ -331,7 +1007,7 @@ impl RestClient {
                     "base_url": self.base_url
                 });
 
-                if self.api_key.is_some() {
+                if self.has_credentials() {
                     // Test authentication if API key is provided
                     match self.get_local_db_limits().await {
                         Ok(limits_result) => {
@@ -367,7 +1043,7 @@ impl RestClient {
 
     /// Get local database limits from backend API
     pub async fn get_local_db_limits(&self) -> RunAgentResult<Value> {
-        if self.api_key.is_none() {
+        if !self.has_credentials() {
             return Ok(serde_json::json!({
                 "success": false,
                 "error": "No API key provided",
@@ -436,20 +1112,59 @@ impl RestClient {
         }
     }
 
-    /// Upload agent to remote server
+    /// Upload an agent folder and return the agent ID assigned by the server
+    ///
+    /// The folder is sent as a gzipped tar archive, built while it is streamed as
+    /// the `file` part of a multipart `POST agents/upload`; `metadata` goes in a
+    /// JSON `metadata` part. Paths listed in a `.runagentignore` file are left out,
+    /// and folders over [`MAX_UPLOAD_FOLDER_MB`] are rejected before anything is
+    /// sent. Requires the `archive` feature.
+    #[cfg(feature = "archive")]
     pub async fn upload_agent(
         &self,
         folder_path: &str,
         metadata: Option<&HashMap<String, Value>>,
-    ) -> RunAgentResult<Value> {
-        // This would implement file upload functionality
-        // For now, return a placeholder
-        let _folder_path = folder_path;
-        let _metadata = metadata;
+    ) -> RunAgentResult<String> {
+        let folder = PathBuf::from(folder_path);
+        let entries = tokio::task::spawn_blocking(move || {
+            archive::collect_files(
+                &folder,
+                RUNAGENT_IGNORE_FILE,
+                MAX_UPLOAD_FOLDER_MB * 1024 * 1024,
+            )
+        })
+        .await
+        .map_err(|e| RunAgentError::generic(format!("Failed to scan agent folder: {}", e)))??;
+        tracing::debug!(
+            "Uploading {} files from {} as an archive",
+            entries.len(),
+            folder_path
+        );
 
-        Err(RunAgentError::generic(
-            "Upload functionality not yet implemented",
-        ))
+        let archive = Part::stream(Body::wrap_stream(archive::tar_gz_stream(entries)))
+            .file_name("agent.tar.gz")
+            .mime_str("application/gzip")?;
+        let metadata = Part::text(serde_json::to_string(
+            &metadata.cloned().unwrap_or_default(),
+        )?)
+        .mime_str("application/json")?;
+        let form = Form::new().part("file", archive).part("metadata", metadata);
+
+        let request = self
+            .build_request(Method::POST, "agents/upload")?
+            .multipart(form);
+        let response = self.handle_response(self.send(request).await?).await?;
+
+        response
+            .pointer("/data/agent_id")
+            .or_else(|| response.get("agent_id"))
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                extract_error(&response).unwrap_or_else(|| {
+                    RunAgentError::server("Upload response did not include an agent_id")
+                })
+            })
     }
 
     /// Start a remote agent
@@ -463,16 +1178,254 @@ impl RestClient {
         self.post(&path, &serde_json::json!(data)).await
     }
 
+    /// Delete a remote agent
+    pub async fn delete_agent(&self, agent_id: &str) -> RunAgentResult<Value> {
+        let path = format!("agents/{}", agent_id);
+        self.delete(&path).await
+    }
+
     /// Get agent status
     pub async fn get_agent_status(&self, agent_id: &str) -> RunAgentResult<Value> {
         let path = format!("agents/{}/status", agent_id);
         self.get(&path).await
     }
+
+    /// List every agent deployed by the authenticated account
+    ///
+    /// Fetches pages of [`LIST_AGENTS_PAGE_SIZE`](crate::constants::LIST_AGENTS_PAGE_SIZE) until the server reports no more.
+    pub async fn list_agents(&self) -> RunAgentResult<Vec<AgentSummary>> {
+        let mut agents = Vec::new();
+        for page in 1.. {
+            let listed = self.list_agents_page(page, LIST_AGENTS_PAGE_SIZE).await?;
+            let has_next = listed.has_next && !listed.items.is_empty();
+            agents.extend(listed.items);
+            if !has_next {
+                break;
+            }
+        }
+        Ok(agents)
+    }
+
+    /// List one page of the authenticated account's agents, counting pages from 1
+    ///
+    /// Sends `GET agents?page=&per_page=`. The agents are read from `agents` or
+    /// `items`, at the top level or under `data`. Without a `has_next` or `total`
+    /// in the response, a full page is taken to mean more may follow. Page 0 is
+    /// rejected with a validation error.
+    pub async fn list_agents_page(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> RunAgentResult<PaginatedResponse<AgentSummary>> {
+        if page == 0 {
+            return Err(RunAgentError::validation(
+                "Agent list pages are counted from 1",
+            ));
+        }
+        let params = HashMap::from([
+            ("page".to_string(), page.to_string()),
+            ("per_page".to_string(), per_page.to_string()),
+        ]);
+        let response = self.get_with_params("agents", Some(&params)).await?;
+        if response.get("success").and_then(Value::as_bool) == Some(false) {
+            return Err(extract_error(&response)
+                .unwrap_or_else(|| RunAgentError::server("Failed to list agents")));
+        }
+        let body = response.get("data").unwrap_or(&response);
+        let listed = ["agents", "items"]
+            .iter()
+            .find_map(|key| body.get(key))
+            .unwrap_or(body);
+        let items: Vec<AgentSummary> = serde_json::from_value(listed.clone())
+            .map_err(|e| RunAgentError::server(format!("Unexpected agent list response: {}", e)))?;
+        let pagination = body.get("pagination").unwrap_or(body);
+        let count = |key: &str| {
+            pagination
+                .get(key)
+                .and_then(Value::as_u64)
+                .map(|n| n as usize)
+        };
+        let total = count("total").unwrap_or_else(|| {
+            (page - 1)
+                .saturating_mul(per_page)
+                .saturating_add(items.len())
+        });
+        let has_next = pagination
+            .get("has_next")
+            .and_then(Value::as_bool)
+            .unwrap_or_else(|| match count("total") {
+                Some(total) => page.saturating_mul(per_page) < total,
+                None => items.len() >= per_page,
+            });
+        Ok(PaginatedResponse {
+            items,
+            total,
+            page,
+            per_page,
+            has_next,
+            has_prev: page > 1,
+        })
+    }
+}
+
+/// `User-Agent` identifying the SDK and its version, then `suffix` if any
+pub(crate) fn sdk_user_agent(suffix: Option<&str>) -> String {
+    let sdk = format!("RunAgent-Rust-SDK/{}", crate::VERSION);
+    match suffix.map(str::trim).filter(|suffix| !suffix.is_empty()) {
+        Some(suffix) => format!("{} {}", sdk, suffix),
+        None => sdk,
+    }
+}
+
+/// Whether `host` is `base_host` or a subdomain of it
+///
+/// Sibling hosts are not trusted: without a public suffix list, the parent of
+/// `x.co.uk` or `app.herokuapp.com` cannot be told apart from a shared suffix.
+fn is_same_or_subdomain(host: &str, base_host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let base_host = base_host.trim_end_matches('.').to_ascii_lowercase();
+    host == base_host || host.ends_with(&format!(".{}", base_host))
+}
+
+/// Parse a `Retry-After` header given as delay seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "retry now"
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Human-readable message from an error response body
+fn error_message(json: &Value) -> Option<String> {
+    let nested = json.get("error").and_then(|error| error.get("message"));
+    [
+        nested,
+        json.get("detail"),
+        json.get("message"),
+        json.get("error"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|value| value.as_str())
+    .map(str::to_string)
+}
+
+/// Whether a response body is MessagePack, judging by its `Content-Type`
+#[cfg(feature = "msgpack")]
+fn is_msgpack(response: &Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| {
+            matches!(
+                mime.trim(),
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+            )
+        })
+}
+
+/// JSON body of a request
+enum JsonBody<'a> {
+    /// Serialized in full before sending
+    Borrowed(&'a Value),
+    /// Owned by the caller and shared across retries; may be streamed
+    Shared(Arc<Value>),
+}
+
+impl JsonBody<'_> {
+    fn value(&self) -> &Value {
+        match self {
+            Self::Borrowed(value) => value,
+            Self::Shared(value) => value,
+        }
+    }
+}
+
+/// Body serializing `data` on a blocking thread while the request is sent
+fn streamed_json(data: Arc<Value>) -> Body {
+    use std::io::Write;
+
+    let (sender, receiver) = tokio::sync::mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let mut writer = std::io::BufWriter::with_capacity(
+            STREAMED_BODY_CHUNK_BYTES,
+            ChunkSender(sender.clone()),
+        );
+        let written = serde_json::to_writer(&mut writer, &*data)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writer.flush());
+        if let Err(e) = written {
+            let _ = sender.blocking_send(Err(e));
+        }
+    });
+    Body::wrap_stream(futures::stream::unfold(
+        receiver,
+        |mut receiver| async move { receiver.recv().await.map(|chunk| (chunk, receiver)) },
+    ))
+}
+
+/// Writer handing each buffered piece of a body to the request
+struct ChunkSender(tokio::sync::mpsc::Sender<std::io::Result<Vec<u8>>>);
+
+impl std::io::Write for ChunkSender {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.blocking_send(Ok(buf.to_vec())).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "request body dropped")
+        })?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Gzip a request body
+fn gzip(body: &[u8]) -> RunAgentResult<Vec<u8>> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body)?;
+    Ok(encoder.finish()?)
+}
+
+/// Replace the `token` query parameter and bearer header of a built request
+fn set_token(request: &mut reqwest::Request, token: &str) -> RunAgentResult<()> {
+    let pairs: Vec<(String, String)> = request
+        .url()
+        .query_pairs()
+        .filter(|(key, _)| key != "token")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    request
+        .url_mut()
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("token", token);
+
+    let value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|e| RunAgentError::authentication(format!("Invalid bearer token: {}", e)))?;
+    request
+        .headers_mut()
+        .insert(reqwest::header::AUTHORIZATION, value);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_url_construction() {
@@ -481,6 +1434,17 @@ mod tests {
         assert_eq!(url.as_str(), "http://localhost:8000/api/v1/agents/test");
     }
 
+    #[test]
+    fn test_url_construction_with_custom_prefix() {
+        let client =
+            RestClient::new("http://proxy.local/", None, Some("/runagent/api/v2")).unwrap();
+        let url = client.get_url("agents/test/run").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://proxy.local/runagent/api/v2/agents/test/run"
+        );
+    }
+
     #[test]
     fn test_url_construction_with_leading_slash() {
         let client = RestClient::new("http://localhost:8000", None, Some("/api/v1")).unwrap();
@@ -493,4 +1457,632 @@ mod tests {
         let client = RestClient::new("http://localhost:8000", None, None);
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_region_from_health() {
+        let client =
+            RestClient::new("https://backend.run-agent.ai", None, Some("/api/v1")).unwrap();
+        let same = Url::parse("https://backend.run-agent.ai/api/v1/health").unwrap();
+        let empty = serde_json::json!({"status": "ok"});
+
+        assert_eq!(client.region_from_health(None, &empty, Some(&same)), None);
+        assert_eq!(client.region_from_health(None, &empty, None), None);
+        assert_eq!(
+            client.region_from_health(
+                Some("https://eu.backend.run-agent.ai/"),
+                &empty,
+                Some(&same)
+            ),
+            Some("https://eu.backend.run-agent.ai".to_string())
+        );
+
+        let body =
+            serde_json::json!({"status": "ok", "region_url": "https://us.backend.run-agent.ai"});
+        assert_eq!(
+            client.region_from_health(None, &body, Some(&same)),
+            Some("https://us.backend.run-agent.ai".to_string())
+        );
+        assert_eq!(
+            client.region_from_health(Some("https://eu.backend.run-agent.ai"), &body, Some(&same)),
+            Some("https://eu.backend.run-agent.ai".to_string())
+        );
+
+        let redirected =
+            Url::parse("https://ap.backend.run-agent.ai/api/v1/health?token=x").unwrap();
+        assert_eq!(
+            client.region_from_health(None, &empty, Some(&redirected)),
+            Some("https://ap.backend.run-agent.ai".to_string())
+        );
+    }
+
+    #[test]
+    fn test_region_from_health_ignores_untrusted_regions() {
+        let client =
+            RestClient::new("https://backend.run-agent.ai", None, Some("/api/v1")).unwrap();
+        let empty = serde_json::json!({"status": "ok"});
+        let region = |hint: &str| client.region_from_health(Some(hint), &empty, None);
+
+        // Another domain, including look-alikes, would receive the API key
+        assert_eq!(region("https://attacker.example"), None);
+        assert_eq!(region("https://run-agent.ai.attacker.example"), None);
+        assert_eq!(region("https://evilrun-agent.ai"), None);
+        // Siblings may belong to someone else under a shared suffix
+        assert_eq!(region("https://eu.run-agent.ai"), None);
+        // Downgrades to plain HTTP would expose it
+        assert_eq!(region("http://eu.backend.run-agent.ai"), None);
+        assert_eq!(region("not a url"), None);
+        assert_eq!(
+            region("https://eu.backend.run-agent.ai"),
+            Some("https://eu.backend.run-agent.ai".to_string())
+        );
+
+        let redirected = Url::parse("https://attacker.example/api/v1/health").unwrap();
+        assert_eq!(
+            client.region_from_health(None, &empty, Some(&redirected)),
+            None
+        );
+
+        let allowed = client.with_region_hosts(["EU.Example.com"]);
+        assert_eq!(
+            allowed.region_from_health(Some("https://eu.example.com"), &empty, None),
+            Some("https://eu.example.com".to_string())
+        );
+
+        // Plain HTTP bases may keep their scheme, but not leave their host
+        let local = RestClient::new("http://127.0.0.1:8000", None, None).unwrap();
+        assert_eq!(
+            local.region_from_health(Some("http://127.0.0.1:9000"), &empty, None),
+            Some("http://127.0.0.1:9000".to_string())
+        );
+        assert_eq!(
+            local.region_from_health(Some("http://10.0.0.1:8000"), &empty, None),
+            None
+        );
+
+        for base in ["https://x.co.uk", "https://app.herokuapp.com"] {
+            let client = RestClient::new(base, None, None).unwrap();
+            for hint in ["https://evil.co.uk", "https://evil.herokuapp.com"] {
+                assert_eq!(client.region_from_health(Some(hint), &empty, None), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_request_persistent_memory_fields() {
+        let body = RestClient::run_request("chat", &[], &HashMap::new(), Some("u1"), true);
+        assert_eq!(body["user_id"], "u1");
+        assert_eq!(body["persistent_memory"], true);
+
+        let body = RestClient::run_request("chat", &[], &HashMap::new(), None, false);
+        assert!(body.get("user_id").is_none());
+        assert!(body.get("persistent_memory").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_request_timeout() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+
+        let client = RestClient::new(&format!("http://{}", addr), None, None).unwrap();
+        let body = RestClient::run_request("chat", &[], &HashMap::new(), None, false);
+        let err = client
+            .send_run_request("agent", "chat", &body, Some(Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, RunAgentError::Timeout { elapsed } if elapsed >= Duration::from_millis(100))
+        );
+        assert!(err.is_retryable());
+    }
+
+    /// Serve one canned HTTP response per connection, returning the base URL
+    async fn respond_with(status: &'static str, body: Value) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let body = body.to_string();
+                let reply = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(reply.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_interceptors_wrap_requests() {
+        use crate::client::interceptor::{RequestInterceptor, RequestParts};
+        use std::sync::Mutex;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        struct Recorder(&'static str, Arc<Mutex<Vec<String>>>);
+
+        #[async_trait::async_trait]
+        impl RequestInterceptor for Recorder {
+            async fn on_request(&self, request: &mut RequestParts) -> RunAgentResult<()> {
+                self.1.lock().unwrap().push(format!("{} request", self.0));
+                request.set_header("X-Trace-Id", self.0)
+            }
+
+            async fn on_response(&self, response: &Response, _elapsed: Duration) {
+                self.1.lock().unwrap().push(format!(
+                    "{} response {}",
+                    self.0,
+                    response.status().as_u16()
+                ));
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let read = socket.read(&mut buf).await.unwrap();
+            let body = json!({"status": "healthy"}).to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..read]).to_lowercase()
+        });
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let client = RestClient::new(&format!("http://{}", addr), None, None)
+            .unwrap()
+            .with_interceptor(Arc::new(Recorder("first", calls.clone())))
+            .with_interceptor(Arc::new(Recorder("second", calls.clone())));
+        client.health_check().await.unwrap();
+
+        // The later interceptor's header wins
+        assert!(server.await.unwrap().contains("x-trace-id: second"));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "first request",
+                "second request",
+                "first response 200",
+                "second response 200"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_provider_refreshes_on_401() {
+        use crate::client::token::TokenProvider;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Only the second token is accepted
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let read = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..read]).to_lowercase();
+                let authorized = request.contains("authorization: bearer token-2")
+                    && request.contains("token=token-2");
+                let (status, body) = if authorized {
+                    ("200 OK", json!({"status": "healthy"}))
+                } else {
+                    ("401 Unauthorized", json!({"detail": "token expired"}))
+                };
+                let body = body.to_string();
+                let reply = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let provider = TokenProvider::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { Ok::<_, RunAgentError>(format!("token-{}", n)) }
+        });
+        let client = RestClient::new(&format!("http://{}", addr), Some("static".into()), None)
+            .unwrap()
+            .with_token_provider(provider);
+
+        assert_eq!(client.health_check().await.unwrap()["status"], "healthy");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Only one retry: a third token is rejected as well
+        let err = client.health_check().await.unwrap_err();
+        assert_eq!(err.category(), "authentication");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_error_codes_from_status_and_body() {
+        let url = respond_with("403 Forbidden", json!({"detail": "not your agent"})).await;
+        let err = RestClient::new(&url, None, None)
+            .unwrap()
+            .health_check()
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::PermissionDenied));
+
+        // Message text alone no longer decides the category
+        let url = respond_with(
+            "500 Internal Server Error",
+            json!({"detail": "no permission"}),
+        )
+        .await;
+        let err = RestClient::new(&url, None, None)
+            .unwrap()
+            .health_check()
+            .await
+            .unwrap_err();
+        assert_eq!(err.category(), "server");
+        assert_eq!(err.to_string(), "Server error: no permission");
+        assert_eq!(err.http_status(), Some(500));
+        assert_eq!(err.response_body(), Some(r#"{"detail":"no permission"}"#));
+        assert!(format!("{:?}", err).contains("status: Some(500)"));
+
+        let body = json!({"error": {"code": "AGENT_NOT_FOUND_REMOTE", "message": "gone"}});
+        let url = respond_with("400 Bad Request", body).await;
+        let err = RestClient::new(&url, None, None)
+            .unwrap()
+            .health_check()
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::AgentNotFound));
+        assert!(err.to_string().contains("gone"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let later = chrono::Utc::now() + chrono::Duration::seconds(90);
+        let delay = parse_retry_after(&later.to_rfc2822()).unwrap();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response() {
+        let url = respond_with(
+            "429 Too Many Requests\r\nRetry-After: 7",
+            json!({"detail": "Run limit reached for this API key"}),
+        )
+        .await;
+        let err = RestClient::new(&url, None, None)
+            .unwrap()
+            .health_check()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            &err,
+            RunAgentError::RateLimited { retry_after: Some(delay), .. } if *delay == Duration::from_secs(7)
+        ));
+        assert!(err.is_retryable());
+        assert_eq!(err.error_code(), Some(ErrorCode::RateLimited));
+    }
+
+    #[cfg(feature = "archive")]
+    #[tokio::test]
+    async fn test_upload_agent_streams_archive() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.py"), "print('hi')").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // The archive is streamed, so the body ends with the last chunk marker
+            while !request.ends_with(b"0\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                assert!(read > 0);
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = json!({"success": true, "data": {"agent_id": "agent-123"}}).to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let client = RestClient::new(&format!("http://{}", addr), None, None).unwrap();
+        let metadata = HashMap::from([("name".to_string(), json!("demo"))]);
+        let agent_id = client
+            .upload_agent(dir.path().to_str().unwrap(), Some(&metadata))
+            .await
+            .unwrap();
+        assert_eq!(agent_id, "agent-123");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/v1/agents/upload"));
+        assert!(request.contains("filename=\"agent.tar.gz\""));
+        assert!(request.contains(r#"{"name":"demo"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_large_bodies_gzipped_when_enabled() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 8192];
+            let header_end = loop {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+                if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            let length: usize = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length: "))
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            while request.len() < header_end + length {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = json!({"ok": true}).to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            (headers, request[header_end..].to_vec())
+        });
+
+        let payload = json!((0..5000)
+            .map(|i| json!({"id": i, "text": "hello"}))
+            .collect::<Vec<_>>());
+        let raw = serde_json::to_vec(&payload).unwrap();
+        let client = RestClient::new(&format!("http://{}", addr), None, None)
+            .unwrap()
+            .with_request_compression(true);
+        client.post("batch", &payload).await.unwrap();
+
+        let (headers, body) = server.await.unwrap();
+        assert!(headers.contains("content-encoding: gzip"));
+        assert!(body.len() * 10 < raw.len());
+        let mut decoded = Vec::new();
+        GzDecoder::new(body.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, raw);
+    }
+
+    #[tokio::test]
+    async fn test_request_size_limit_and_streamed_bodies() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Over the limit: fails before anything is sent to the (closed) port
+        let payload = json!({"input_kwargs": {"candidates": vec!["x".repeat(100); 20_000]}});
+        let client = RestClient::new("http://127.0.0.1:9", None, None)
+            .unwrap()
+            .with_max_request_mb(1.0);
+        let err = client
+            .send_shared_run_request("agent", "score", &Arc::new(payload), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert!(err.to_string().contains("exceeding the 1048576 byte"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 65536];
+            let header_end = loop {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+                if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            let length: usize = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length: "))
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            while request.len() < header_end + length {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = json!({"success": true}).to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            request[header_end..].to_vec()
+        });
+
+        // Over the streaming threshold: the body arrives whole and byte-identical
+        let payload = json!({"input_kwargs": {"candidates": vec!["x".repeat(100); 50_000]}});
+        let raw = serde_json::to_vec(&payload).unwrap();
+        assert!(raw.len() > STREAMED_BODY_THRESHOLD_BYTES);
+        let client = RestClient::new(&format!("http://{}", addr), None, None)
+            .unwrap()
+            .with_max_request_mb(10.0);
+        client
+            .send_shared_run_request("agent", "score", &Arc::new(payload), None)
+            .await
+            .unwrap();
+        assert_eq!(server.await.unwrap(), raw);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[tokio::test]
+    async fn test_msgpack_responses_decoded() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let envelope = json!({"success": true, "data": {"embedding": [0.5, 1.5]}});
+        let body = crate::utils::serializer::value_to_msgpack(&envelope).unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let read = socket.read(&mut buf).await.unwrap();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/msgpack\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+            String::from_utf8_lossy(&buf[..read]).to_lowercase()
+        });
+
+        let client = RestClient::new(&format!("http://{}", addr), None, None)
+            .unwrap()
+            .with_msgpack(true);
+        assert_eq!(client.get("agents/a/run").await.unwrap(), envelope);
+        assert!(server
+            .await
+            .unwrap()
+            .contains("accept: application/msgpack, application/json;q=0.9"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport_answers_requests() {
+        use std::sync::Mutex;
+
+        /// Answers from fixtures by path, recording each request seen
+        struct Fixtures(Mutex<Vec<String>>);
+
+        #[async_trait::async_trait]
+        impl Transport for Fixtures {
+            async fn execute(&self, request: reqwest::Request) -> RunAgentResult<Response> {
+                let path = request.url().path().to_string();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", request.method(), path));
+                let (status, body) = match path.as_str() {
+                    "/api/v1/health" => (200, json!({"status": "healthy"})),
+                    _ => (404, json!({"detail": "no fixture"})),
+                };
+                let response = http::Response::builder()
+                    .status(status)
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string())
+                    .unwrap();
+                Ok(Response::from(response))
+            }
+        }
+
+        // Nothing listens on the base URL; every request goes to the fixtures
+        let fixtures = Arc::new(Fixtures(Mutex::new(Vec::new())));
+        let client = RestClient::new("http://127.0.0.1:9", None, None)
+            .unwrap()
+            .with_transport(fixtures.clone());
+
+        assert_eq!(
+            client.health_check().await.unwrap(),
+            json!({"status": "healthy"})
+        );
+        let err = client.get("agents/missing").await.unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert_eq!(
+            *fixtures.0.lock().unwrap(),
+            vec!["GET /api/v1/health", "GET /api/v1/agents/missing"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_agents_follows_pages() {
+        /// Serves 51 agents in pages, first under `data.agents`, then as `items`
+        struct Agents;
+
+        #[async_trait::async_trait]
+        impl Transport for Agents {
+            async fn execute(&self, request: reqwest::Request) -> RunAgentResult<Response> {
+                let page = request
+                    .url()
+                    .query_pairs()
+                    .find(|(key, _)| key == "page")
+                    .map(|(_, page)| page.parse::<usize>().unwrap())
+                    .unwrap();
+                let body = match page {
+                    1 => {
+                        let agents: Vec<Value> = (0..LIST_AGENTS_PAGE_SIZE)
+                            .map(|i| json!({"id": format!("agent-{}", i), "agent_name": "bot"}))
+                            .collect();
+                        json!({"success": true, "data": {"agents": agents}})
+                    }
+                    _ => json!({
+                        "items": [{"agent_id": "last", "framework": "langgraph", "region": "eu"}],
+                        "has_next": false
+                    }),
+                };
+                Ok(Response::from(http::Response::new(body.to_string())))
+            }
+        }
+
+        let client = RestClient::new("http://127.0.0.1:9", None, None)
+            .unwrap()
+            .with_transport(Arc::new(Agents));
+        let agents = client.list_agents().await.unwrap();
+
+        assert_eq!(agents.len(), LIST_AGENTS_PAGE_SIZE + 1);
+        assert_eq!(agents[0].agent_id, "agent-0");
+        assert_eq!(agents[0].name.as_deref(), Some("bot"));
+        let last = agents.last().unwrap();
+        assert_eq!(last.framework.as_deref(), Some("langgraph"));
+        assert_eq!(last.extra["region"], "eu");
+
+        let page = client.list_agents_page(2, 10).await.unwrap();
+        assert!(page.has_prev && !page.has_next);
+        assert_eq!(page.total, 11);
+
+        let err = client.list_agents_page(0, 10).await.err().unwrap();
+        assert_eq!(err.category(), "validation");
+    }
 }
@@ -0,0 +1,316 @@
+//! Retry policy for transient client failures
+
+use crate::types::RunAgentResult;
+use crate::utils::clock::{Clock, SystemClock};
+use std::future::Future;
+use std::time::Duration;
+
+/// Retry policy with exponential backoff and an overall time budget
+///
+/// Only errors reported as retryable by [`RunAgentError::is_retryable`] are retried.
+/// When the server sent a `Retry-After` delay ([`RunAgentError::RateLimited`]), that
+/// delay replaces the backoff for the next attempt.
+/// `max_attempts` is an upper bound: when a `deadline` is set, no further attempt is
+/// started once waiting for the next backoff would exceed it, so the time budget can
+/// cap the attempt count well below `max_attempts`. The last error is returned.
+///
+/// [`RunAgentError::is_retryable`]: crate::RunAgentError::is_retryable
+/// [`RunAgentError::RateLimited`]: crate::RunAgentError::RateLimited
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(5)
+///     .with_initial_delay(Duration::from_millis(500))
+///     .with_deadline(Duration::from_secs(30));
+/// assert_eq!(policy.max_attempts, 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound for a single backoff delay
+    pub max_delay: Duration,
+    /// Multiplier applied to the delay after each retry
+    pub backoff_multiplier: f64,
+    /// Overall time budget across all attempts and delays
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            backoff_multiplier: 2.0,
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the given maximum number of attempts
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// A policy that makes a single attempt and never retries
+    pub fn disabled() -> Self {
+        Self::new(1)
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the upper bound for a single backoff delay
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set the backoff multiplier
+    ///
+    /// Multipliers below 1.0 and NaN are raised to 1.0 (constant delays); an
+    /// infinite multiplier backs off straight to `max_delay`.
+    pub fn with_backoff_multiplier(mut self, multiplier: f64) -> Self {
+        self.backoff_multiplier = if multiplier.is_nan() {
+            1.0
+        } else {
+            multiplier.clamp(1.0, f64::MAX)
+        };
+        self
+    }
+
+    /// Set the overall time budget across all attempts
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Backoff delay to wait after the given (1-based) failed attempt
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let factor = self.backoff_multiplier.powi(exponent);
+        // Delays too large (or invalid) for a Duration are capped, not a panic
+        Duration::try_from_secs_f64(self.initial_delay.as_secs_f64() * factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+
+    /// Run an operation, retrying retryable failures within the policy limits
+    pub async fn retry<T, F, Fut>(&self, operation: F) -> RunAgentResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RunAgentResult<T>>,
+    {
+        self.retry_with_clock(&SystemClock, operation).await
+    }
+
+    /// [`RetryPolicy::retry`] with delays and the deadline measured by `clock`
+    pub(crate) async fn retry_with_clock<T, F, Fut>(
+        &self,
+        clock: &dyn Clock,
+        mut operation: F,
+    ) -> RunAgentResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RunAgentResult<T>>,
+    {
+        let started = clock.now();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+
+            if !error.is_retryable() || attempt >= self.max_attempts {
+                return Err(error);
+            }
+
+            let delay = error
+                .retry_after()
+                .unwrap_or_else(|| self.delay_for(attempt));
+            if let Some(deadline) = self.deadline {
+                if clock.now() - started + delay >= deadline {
+                    tracing::debug!(
+                        "Retry budget of {:?} exhausted after {} attempt(s)",
+                        deadline,
+                        attempt
+                    );
+                    return Err(error);
+                }
+            }
+
+            tracing::warn!(
+                "Attempt {}/{} failed ({}), retrying in {:?}",
+                attempt,
+                self.max_attempts,
+                error,
+                delay
+            );
+            clock.sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RunAgentError;
+    use crate::utils::clock::TestClock;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_delays() {
+        let policy = RetryPolicy::new(5)
+            .with_initial_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(300));
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_backoff_delays_cap_overflowing_products() {
+        let max_delay = Duration::from_secs(30);
+        let large = RetryPolicy::new(u32::MAX).with_backoff_multiplier(1e300);
+        assert_eq!(large.delay_for(1), Duration::from_secs(1));
+        assert_eq!(large.delay_for(3), max_delay);
+        assert_eq!(large.delay_for(u32::MAX), max_delay);
+
+        let infinite = RetryPolicy::new(5).with_backoff_multiplier(f64::INFINITY);
+        assert_eq!(infinite.delay_for(2), max_delay);
+
+        for invalid in [f64::NAN, -2.0, 0.5] {
+            let policy = RetryPolicy::new(5).with_backoff_multiplier(invalid);
+            assert_eq!(policy.backoff_multiplier, 1.0);
+            assert_eq!(policy.delay_for(4), Duration::from_secs(1));
+        }
+
+        // Fields are public, so invalid multipliers set directly must not panic either
+        let direct = RetryPolicy {
+            backoff_multiplier: f64::NAN,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(direct.delay_for(2), max_delay);
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_max_attempts() {
+        let policy = RetryPolicy::new(3).with_initial_delay(Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+
+        let result: RunAgentResult<()> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(RunAgentError::connection("refused"))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_limits_attempts() {
+        let clock = TestClock::new();
+        let policy = RetryPolicy::new(10)
+            .with_initial_delay(Duration::from_millis(20))
+            .with_deadline(Duration::from_millis(50));
+        let attempts = AtomicU32::new(0);
+
+        let result: RunAgentResult<()> = policy
+            .retry_with_clock(&clock, || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(RunAgentError::server("unavailable"))
+            })
+            .await;
+
+        // 20ms fits in the budget, the following 40ms backoff does not
+        assert!(matches!(result, Err(RunAgentError::Server { .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(clock.sleeps(), vec![Duration::from_millis(20)]);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_counts_time_spent_in_attempts() {
+        let clock = TestClock::new();
+        let policy = RetryPolicy::new(10)
+            .with_initial_delay(Duration::from_secs(1))
+            .with_backoff_multiplier(1.0)
+            .with_deadline(Duration::from_secs(10));
+        let attempts = AtomicU32::new(0);
+
+        let result: RunAgentResult<()> = policy
+            .retry_with_clock(&clock, || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                // Each attempt takes 3s of (simulated) time
+                clock.advance(Duration::from_secs(3));
+                async { Err(RunAgentError::connection("timed out")) }
+            })
+            .await;
+
+        // 3s + 1s, 7s + 1s fit; 11s already exceeds the 10s budget
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.sleeps().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_overrides_backoff() {
+        let clock = TestClock::new();
+        let policy = RetryPolicy::new(3).with_initial_delay(Duration::from_millis(10));
+        let attempts = AtomicU32::new(0);
+
+        let result: RunAgentResult<()> = policy
+            .retry_with_clock(&clock, || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(RunAgentError::rate_limited(
+                        "slow down",
+                        Some(Duration::from_secs(2)),
+                    )),
+                    1 => Err(RunAgentError::rate_limited("slow down", None)),
+                    _ => Ok(()),
+                }
+            })
+            .await;
+
+        // Without a Retry-After the regular backoff applies
+        assert!(result.is_ok());
+        assert_eq!(
+            clock.sleeps(),
+            vec![Duration::from_secs(2), Duration::from_millis(20)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_is_not_retried() {
+        let policy = RetryPolicy::new(5).with_initial_delay(Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+
+        let result: RunAgentResult<()> = policy
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(RunAgentError::validation("bad input"))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,18 +1,54 @@
 //! Main RunAgent client for interacting with deployed agents
 
+use crate::client::diagnostics::{DiagnosticCheck, DiagnosticKind, DiagnosticReport};
+use crate::client::interceptor::RequestInterceptor;
 use crate::client::rest_client::RestClient;
-use crate::client::socket_client::SocketClient;
-use crate::types::{RunAgentError, RunAgentResult};
+use crate::client::retry::RetryPolicy;
+use crate::client::socket_client::{
+    error_item, SocketClient, SocketClientConfig, StreamPolicy, StreamSession,
+};
+use crate::client::sse_client::{SseClient, StreamTransport};
+use crate::client::stream::{chunk_text, AgentStream, RunStream, SequencedChunk};
+use crate::client::tls::TlsConfig;
+use crate::client::token::{BearerToken, TokenProvider};
+use crate::client::transport::Transport;
+use crate::types::attachment::{MultipartBody, ATTACHMENTS_KWARG};
+use crate::types::{
+    AgentResponse, AgentSummary, Attachment, AttachmentEncoding, EntryPoint, ExecutionMetadata,
+    ResponseEnvelope, RunAgentError, RunAgentResult, RunOutcome,
+};
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::config::Config;
+use crate::utils::input_schema::validate_kwargs;
+use crate::utils::metrics;
 use crate::utils::serializer::CoreSerializer;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use futures::Stream;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 #[cfg(feature = "db")]
 use crate::db::DatabaseService;
+#[cfg(feature = "db")]
+use crate::utils::framework::detect_framework;
+
+/// Result of an idempotent run, awaited by every caller using its key
+type InFlightRun = Shared<BoxFuture<'static, Result<Value, Arc<RunAgentError>>>>;
 
 /// Main client for interacting with RunAgent deployments
+///
+/// Clones share the HTTP connection pool and the fetched architecture.
+#[derive(Clone)]
 pub struct RunAgentClient {
     agent_id: String,
     entrypoint_tag: String,
@@ -27,6 +63,30 @@ pub struct RunAgentClient {
     user_id: Option<String>,
     /// Enable persistent memory for this user (matches Python SDK RunAgentClient.persistent_memory)
     persistent_memory: bool,
+    /// JSON pointer overriding where the payload is read from run responses
+    payload_path: Option<String>,
+    /// Retry policy applied to non-streaming runs
+    retry_policy: RetryPolicy,
+    /// Regional base URL announced by the server, if the client switched to one
+    region_base_url: Option<String>,
+    /// Follow regional endpoints announced during setup
+    region_discovery: bool,
+    /// Downgrade streaming runs to the non-streaming sibling when WebSockets fail
+    stream_fallback_to_run: bool,
+    /// Reject `run` results that are a Python generator repr
+    detect_generator_misuse: bool,
+    /// Check kwargs against entrypoint input schemas before sending
+    validate_input: bool,
+    /// Transport for streaming runs
+    stream_transport: StreamTransport,
+    /// Longest wait for the next stream chunk
+    chunk_timeout: Option<Duration>,
+    /// Kwargs sent with every run unless the call sets them
+    default_kwargs: HashMap<String, Value>,
+    /// How `run_with_attachments` sends attachments
+    attachment_encoding: AttachmentEncoding,
+    /// Idempotent runs in progress by key, shared across clones
+    in_flight: Arc<Mutex<HashMap<String, InFlightRun>>>,
 
     #[cfg(feature = "db")]
     #[allow(dead_code)] // Reserved for future use
@@ -35,7 +95,9 @@ pub struct RunAgentClient {
 
 /// Configuration for creating a RunAgent client
 ///
-/// All fields except `agent_id` and `entrypoint_tag` are optional.
+/// All fields except `agent_id` and `entrypoint_tag` are optional. With the `db`
+/// feature, `agent_id` may be left empty when `agent_name` or `framework`
+/// identifies a single local agent.
 ///
 /// # Direct Construction
 ///
@@ -56,6 +118,37 @@ pub struct RunAgentClient {
 ///         enable_registry: None,
 ///         user_id: None,
 ///         persistent_memory: None,
+///         payload_path: None,
+///         retry_policy: None,
+///         require_api_key: None,
+///         stream_fallback_to_run: None,
+///         respond_to_server_pings: None,
+///         http_client: None,
+///         stream_reconnect: None,
+///         max_response_mb: None,
+///         max_request_mb: None,
+///         unix_socket: None,
+///         profile: None,
+///         interceptors: Vec::new(),
+///         token_provider: None,
+///         detect_generator_misuse: None,
+///         validate_input: None,
+///         compression: None,
+///         stream_transport: None,
+///         chunk_timeout: None,
+///         msgpack: None,
+///         agent_name: None,
+///         framework: None,
+///         transport: None,
+///         api_prefix: None,
+///         tls: None,
+///         unwrap_envelopes: None,
+///         default_kwargs: None,
+///         attachment_encoding: None,
+///         user_agent_suffix: None,
+///         connect_timeout: None,
+///         region_hosts: Vec::new(),
+///         region_discovery: None,
 ///     }).await?;
 ///     Ok(())
 /// }
@@ -85,9 +178,11 @@ pub struct RunAgentClientConfig {
     pub entrypoint_tag: String,
     /// Whether this is a local agent (default: false)
     pub local: Option<bool>,
-    /// Host for local agents (optional, will lookup from DB if not provided and local=true)
+    /// Host for local agents (optional, will lookup from DB if not provided and local=true;
+    /// required without the `db` feature)
     pub host: Option<String>,
-    /// Port for local agents (optional, will lookup from DB if not provided and local=true)
+    /// Port for local agents (optional, will lookup from DB if not provided and local=true;
+    /// required without the `db` feature)
     pub port: Option<u16>,
     /// API key for remote agents (optional, can also use RUNAGENT_API_KEY env var)
     pub api_key: Option<String>,
@@ -101,6 +196,78 @@ pub struct RunAgentClientConfig {
     pub user_id: Option<String>,
     /// Enable persistent memory for this user
     pub persistent_memory: Option<bool>,
+    /// JSON pointer (e.g. `/result/output`) locating the payload in run responses
+    pub payload_path: Option<String>,
+    /// Retry policy for transient failures (default: no retries)
+    pub retry_policy: Option<RetryPolicy>,
+    /// Fail at construction if a remote client has no API key (default: false)
+    pub require_api_key: Option<bool>,
+    /// Fall back to the non-streaming sibling entrypoint when the stream connection
+    /// cannot be established (default: false)
+    pub stream_fallback_to_run: Option<bool>,
+    /// Answer server-initiated `{"type": "ping"}` frames on streams (default: true)
+    pub respond_to_server_pings: Option<bool>,
+    /// HTTP client to share across clients (default: a new client per instance)
+    pub http_client: Option<reqwest::Client>,
+    /// Reconnection behaviour for dropped streams (default: never reconnect)
+    pub stream_reconnect: Option<SocketClientConfig>,
+    /// Largest response payload or stream chunk accepted, in megabytes (default: 10)
+    pub max_response_mb: Option<f64>,
+    /// Largest JSON request body sent, in megabytes (default: no limit)
+    pub max_request_mb: Option<f64>,
+    /// Unix domain socket of a local agent server (Unix only, requires the
+    /// `unix-socket` feature; replaces host/port)
+    pub unix_socket: Option<PathBuf>,
+    /// Named profile in the user config file supplying `base_url`/`api_key` defaults
+    /// (default: the one named by `RUNAGENT_PROFILE`, if any)
+    pub profile: Option<String>,
+    /// Hooks run around every REST request, in order (default: none)
+    pub interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    /// Source of short-lived bearer tokens used instead of `api_key`
+    pub token_provider: Option<TokenProvider>,
+    /// Fail `run` calls that return a Python generator repr, a sign the entrypoint
+    /// streams (default: true)
+    pub detect_generator_misuse: Option<bool>,
+    /// Check kwargs against the entrypoint's `input_schema` before sending
+    /// (default: false)
+    pub validate_input: Option<bool>,
+    /// Gzip large JSON request bodies sent to remote agents (default: false)
+    pub compression: Option<bool>,
+    /// Transport for streaming runs (default: WebSocket)
+    pub stream_transport: Option<StreamTransport>,
+    /// Longest wait for the next stream chunk before failing (default: no limit)
+    pub chunk_timeout: Option<Duration>,
+    /// Negotiate MessagePack responses (requires the `msgpack` feature; default: false)
+    pub msgpack: Option<bool>,
+    /// Name of the local agent to use when `agent_id` is empty: the last
+    /// component of its deployment path (requires the `db` feature)
+    pub agent_name: Option<String>,
+    /// Framework of the local agent to use when `agent_id` is empty (requires
+    /// the `db` feature)
+    pub framework: Option<String>,
+    /// Sends REST requests instead of the HTTP client (default: the HTTP client)
+    pub transport: Option<Arc<dyn Transport>>,
+    /// Path prefix of the API routes on the server (default: `/api/v1`)
+    pub api_prefix: Option<String>,
+    /// Client certificate and extra root CAs for remote `https`/`wss` servers
+    /// (requires the `tls` feature)
+    pub tls: Option<TlsConfig>,
+    /// Unwrap `{type, payload}` envelopes around results and chunks (default: true)
+    pub unwrap_envelopes: Option<bool>,
+    /// Kwargs merged under every `run`/`run_stream` call; call kwargs win
+    pub default_kwargs: Option<HashMap<String, Value>>,
+    /// How `run_with_attachments` sends attachments (default: base64 kwarg)
+    pub attachment_encoding: Option<AttachmentEncoding>,
+    /// App identifier appended to the SDK's `User-Agent`, e.g. `lead-agent/1.0`
+    pub user_agent_suffix: Option<String>,
+    /// Ceiling on client setup: registry lookup, region discovery and the
+    /// architecture fetch (default: no limit)
+    pub connect_timeout: Option<Duration>,
+    /// Hosts besides the base URL's site that regional endpoints may be announced on
+    pub region_hosts: Vec<String>,
+    /// Health-check remote servers during setup and follow the regional endpoint
+    /// they announce (default: false)
+    pub region_discovery: Option<bool>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -118,6 +285,37 @@ impl Default for RunAgentClientConfig {
             enable_registry: None,
             user_id: None,
             persistent_memory: None,
+            payload_path: None,
+            retry_policy: None,
+            require_api_key: None,
+            stream_fallback_to_run: None,
+            respond_to_server_pings: None,
+            http_client: None,
+            stream_reconnect: None,
+            max_response_mb: None,
+            max_request_mb: None,
+            unix_socket: None,
+            profile: None,
+            interceptors: Vec::new(),
+            token_provider: None,
+            detect_generator_misuse: None,
+            validate_input: None,
+            compression: None,
+            stream_transport: None,
+            chunk_timeout: None,
+            msgpack: None,
+            agent_name: None,
+            framework: None,
+            transport: None,
+            api_prefix: None,
+            tls: None,
+            unwrap_envelopes: None,
+            default_kwargs: None,
+            attachment_encoding: None,
+            user_agent_suffix: None,
+            connect_timeout: None,
+            region_hosts: Vec::new(),
+            region_discovery: None,
         }
     }
 }
@@ -128,19 +326,47 @@ impl RunAgentClientConfig {
         Self {
             agent_id: agent_id.into(),
             entrypoint_tag: entrypoint_tag.into(),
-            local: None,
-            host: None,
-            port: None,
-            api_key: None,
-            base_url: None,
-            extra_params: None,
-            enable_registry: None,
-            user_id: None,
-            persistent_memory: None,
+            ..Self::default()
         }
     }
 
+    /// Config for a local agent served at `host:port`
+    ///
+    /// Registry lookup is disabled, so no `db` feature or local database is
+    /// involved.
+    pub fn local(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        host: impl Into<String>,
+        port: u16,
+    ) -> Self {
+        Self::new(agent_id, entrypoint_tag)
+            .with_local(true)
+            .with_address(host, port)
+            .with_enable_registry(false)
+    }
+
+    /// Config for a remote agent authenticated with `api_key`
+    ///
+    /// The base URL comes from `RUNAGENT_BASE_URL` or the default; registry
+    /// lookup is disabled.
+    pub fn remote(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self::new(agent_id, entrypoint_tag)
+            .with_local(false)
+            .with_api_key(api_key)
+            .with_enable_registry(false)
+    }
+
     /// Set local flag
+    ///
+    /// Local clients without an address look it up in the local agent registry,
+    /// which needs the `db` feature. Without it, also call
+    /// [`with_address`](Self::with_address) or construction fails with
+    /// [`RunAgentError::Config`].
     pub fn with_local(mut self, local: bool) -> Self {
         self.local = Some(local);
         self
@@ -154,6 +380,9 @@ impl RunAgentClientConfig {
     }
 
     /// Set API key
+    ///
+    /// An empty key sends no credentials, even when `RUNAGENT_API_KEY` or the
+    /// config file has one.
     pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
         self.api_key = Some(api_key.into());
         self
@@ -171,6 +400,71 @@ impl RunAgentClientConfig {
         self
     }
 
+    /// Send these kwargs with every `run`/`run_stream` call (parameter presets)
+    ///
+    /// Defaults are merged under the call's kwargs: a key given in the call
+    /// replaces the default, so `with_defaults({"temperature": 0.2})` followed by
+    /// `run(&[("temperature", json!(0.9))])` sends `0.9`. Input validation
+    /// checks the merged kwargs.
+    pub fn with_defaults(mut self, defaults: HashMap<String, Value>) -> Self {
+        self.default_kwargs = Some(defaults);
+        self
+    }
+
+    /// Choose how [`RunAgentClient::run_with_attachments`] sends attachments
+    pub fn with_attachment_encoding(mut self, encoding: AttachmentEncoding) -> Self {
+        self.attachment_encoding = Some(encoding);
+        self
+    }
+
+    /// Identify the calling app to the server, e.g. `with_user_agent_suffix("lead-agent/1.0")`
+    ///
+    /// REST requests and WebSocket handshakes send
+    /// `User-Agent: RunAgent-Rust-SDK/<version> lead-agent/1.0`, so server-side
+    /// analytics can attribute traffic to apps and their versions.
+    pub fn with_user_agent_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.user_agent_suffix = Some(suffix.into());
+        self
+    }
+
+    /// Fail client setup that takes longer than `connect_timeout`
+    ///
+    /// Bounds everything `RunAgentClient::new` (and `connect`) waits on before
+    /// returning: the local registry lookup, region discovery and the
+    /// architecture fetch. On expiry setup fails with
+    /// [`RunAgentError::Timeout`]. Runs are bounded separately, e.g. by
+    /// [`RunAgentClient::run_with_timeout`].
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Follow the regional endpoint a remote server announces (default: disabled)
+    ///
+    /// Setup then makes an extra health request before fetching the architecture.
+    /// Only trusted regions are followed, see
+    /// [`with_region_hosts`](Self::with_region_hosts).
+    pub fn with_region_discovery(mut self, enabled: bool) -> Self {
+        self.region_discovery = Some(enabled);
+        self
+    }
+
+    /// Also follow regional endpoints announced on `hosts`
+    ///
+    /// By default a client with [region discovery](Self::with_region_discovery)
+    /// only switches to its base URL's host or subdomains of it, e.g.
+    /// `eu.backend.run-agent.ai` for `backend.run-agent.ai`, and never from
+    /// `https` to `http`; other hints are ignored so the API key stays on trusted
+    /// hosts.
+    pub fn with_region_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.region_hosts = hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Enable or disable registry lookup
     pub fn with_enable_registry(mut self, enable: bool) -> Self {
         self.enable_registry = Some(enable);
@@ -188,12 +482,254 @@ impl RunAgentClientConfig {
         self.persistent_memory = Some(persistent);
         self
     }
+
+    /// Set a JSON pointer (e.g. `/result/output`) for extracting the payload
+    ///
+    /// Useful for self-hosted servers with a custom response envelope. When the
+    /// pointer does not resolve, the built-in `data`/`output_data` heuristics apply.
+    pub fn with_payload_path(mut self, payload_path: impl Into<String>) -> Self {
+        self.payload_path = Some(payload_path.into());
+        self
+    }
+
+    /// Retry transient failures of non-streaming runs with the given policy
+    ///
+    /// `max_attempts` is capped by the policy's deadline, if one is set.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Require an API key for remote agents
+    ///
+    /// When enabled, construction fails if no key is set in the config or
+    /// `RUNAGENT_API_KEY`, instead of warning and failing on the first run.
+    pub fn with_require_api_key(mut self, require: bool) -> Self {
+        self.require_api_key = Some(require);
+        self
+    }
+
+    /// Fall back from `_stream` entrypoints to their non-streaming sibling
+    ///
+    /// When the WebSocket connection fails (e.g. on networks that block
+    /// WebSockets), `run_stream` calls the entrypoint without the `_stream` suffix
+    /// and yields its result as a single-item stream, losing incremental output.
+    pub fn with_stream_fallback_to_run(mut self, fallback: bool) -> Self {
+        self.stream_fallback_to_run = Some(fallback);
+        self
+    }
+
+    /// Enable or disable replies to server-initiated ping frames on streams
+    pub fn with_respond_to_server_pings(mut self, respond: bool) -> Self {
+        self.respond_to_server_pings = Some(respond);
+        self
+    }
+
+    /// Reuse an existing HTTP client and its connection pool
+    ///
+    /// See also [`RunAgentClientPool`](crate::RunAgentClientPool), which shares one
+    /// HTTP client and caches agent architectures.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Reconnect streams whose connection drops before the run completes
+    ///
+    /// See [`SocketClient::run_stream`] for the reconnect contract.
+    pub fn with_stream_reconnect(mut self, stream_reconnect: SocketClientConfig) -> Self {
+        self.stream_reconnect = Some(stream_reconnect);
+        self
+    }
+
+    /// Raise or lower the size limit for response payloads and stream chunks
+    ///
+    /// Larger payloads fail with [`RunAgentError::Serialization`].
+    pub fn with_max_response_mb(mut self, max_response_mb: f64) -> Self {
+        self.max_response_mb = Some(max_response_mb);
+        self
+    }
+
+    /// Fail runs whose JSON request body is over `max_request_mb` megabytes
+    ///
+    /// The body size is measured before it is built, so oversized inputs (e.g. a
+    /// huge candidate list) fail fast with a validation error naming the size.
+    pub fn with_max_request_mb(mut self, max_request_mb: f64) -> Self {
+        self.max_request_mb = Some(max_request_mb);
+        self
+    }
+
+    /// Reach a local agent server over a Unix domain socket
+    ///
+    /// Implies a local client: host/port and the registry lookup are skipped, and
+    /// both REST and WebSocket traffic go through `path`. Requires the
+    /// `unix-socket` feature; without it, building the client fails with a config
+    /// error.
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Run `interceptor` around every REST request made by the client
+    ///
+    /// Interceptors run in the order they are added. Streaming runs over
+    /// WebSockets are not intercepted.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn RequestInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Fetch bearer tokens from `fetch` instead of using a static API key
+    ///
+    /// `fetch` returns a `String`, or a [`BearerToken`] with an expiry to cache it
+    /// until then. REST requests answered with 401 are retried once with a
+    /// refreshed token. See [`TokenProvider`].
+    pub fn with_token_provider<F, Fut, T>(mut self, fetch: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = RunAgentResult<T>> + Send + 'static,
+        T: Into<BearerToken>,
+    {
+        self.token_provider = Some(TokenProvider::new(fetch));
+        self
+    }
+
+    /// Enable or disable the generator repr check on `run` results (default: enabled)
+    pub fn with_detect_generator_misuse(mut self, detect: bool) -> Self {
+        self.detect_generator_misuse = Some(detect);
+        self
+    }
+
+    /// Check kwargs against the entrypoint's input schema before each run
+    ///
+    /// Applies when the architecture gives the entrypoint an `input_schema`;
+    /// see [`validate_kwargs`](crate::utils::validate_kwargs) for what is checked.
+    pub fn with_validate_input(mut self, validate: bool) -> Self {
+        self.validate_input = Some(validate);
+        self
+    }
+
+    /// Gzip JSON request bodies over 16 KB (e.g. large batch inputs)
+    ///
+    /// Only applies to remote agents; local servers always receive plain JSON.
+    /// Compressed responses are decoded regardless of this setting.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = Some(enabled);
+        self
+    }
+
+    /// Stream over server-sent events instead of a WebSocket
+    ///
+    /// SSE streams yield the same chunks but are not reconnected when dropped, and
+    /// [`RunAgentClient::open_session`] always uses a WebSocket. See
+    /// [`SseClient`](crate::client::SseClient).
+    pub fn with_stream_transport(mut self, transport: StreamTransport) -> Self {
+        self.stream_transport = Some(transport);
+        self
+    }
+
+    /// Fail streams that go `chunk_timeout` without yielding a chunk
+    ///
+    /// The stream yields [`RunAgentError::Timeout`] and ends. This detects stalled
+    /// agents without capping how long a steadily streaming run may take; server
+    /// heartbeats do not count as chunks.
+    pub fn with_chunk_timeout(mut self, chunk_timeout: Duration) -> Self {
+        self.chunk_timeout = Some(chunk_timeout);
+        self
+    }
+
+    /// Ask the server for MessagePack instead of JSON responses
+    ///
+    /// Requires the `msgpack` feature; enabling it without the feature fails at
+    /// construction. Servers that do not support MessagePack keep answering with
+    /// JSON, which is decoded as usual. Streams are unaffected.
+    pub fn with_msgpack(mut self, enabled: bool) -> Self {
+        self.msgpack = Some(enabled);
+        self
+    }
+
+    /// Pick the local agent deployed from a directory with this name
+    ///
+    /// Only used when `agent_id` is empty, and combinable with
+    /// [`with_framework`](Self::with_framework). Construction looks the agent up
+    /// in the local registry and fails with [`RunAgentError::Validation`] unless
+    /// exactly one agent matches. Requires the `db` feature; resolved agents are
+    /// local unless `local` is set.
+    pub fn with_agent_name(mut self, name: impl Into<String>) -> Self {
+        self.agent_name = Some(name.into());
+        self
+    }
+
+    /// Pick the local agent built with this framework (e.g. `langgraph`)
+    ///
+    /// Only used when `agent_id` is empty; see [`with_agent_name`](Self::with_agent_name).
+    /// Agents registered without a framework match the one
+    /// [`detect_framework`](crate::utils::detect_framework) finds in their directory.
+    pub fn with_framework(mut self, framework: impl Into<String>) -> Self {
+        self.framework = Some(framework.into());
+        self
+    }
+
+    /// Mount point of the API routes, e.g. `/runagent/api/v2` behind a reverse proxy
+    ///
+    /// Applies to REST and WebSocket URLs alike (default: `/api/v1`). A missing
+    /// leading slash is added and a trailing one dropped.
+    pub fn with_api_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.api_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Present a client certificate or trust extra root CAs (mutual TLS)
+    ///
+    /// Applies to REST requests and WebSocket streams of remote agents. Cannot be
+    /// combined with [`with_http_client`](Self::with_http_client); configure TLS on
+    /// the shared client instead. [`RunAgentClientPool`](crate::RunAgentClientPool)
+    /// shares one HTTP client per distinct `TlsConfig`. See [`TlsConfig`].
+    ///
+    /// Requires the `tls` feature; without it, building the client fails with a
+    /// config error.
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Unwrap `{type, payload}` envelopes around results and chunks (default: true)
+    ///
+    /// Nested and JSON-encoded envelopes are unwrapped down to the innermost
+    /// payload. Disable to receive envelopes unchanged. See
+    /// [`CoreSerializer::with_envelope_unwrap`].
+    pub fn with_envelope_unwrap(mut self, unwrap: bool) -> Self {
+        self.unwrap_envelopes = Some(unwrap);
+        self
+    }
+
+    /// Send REST requests through `transport` instead of the HTTP client
+    ///
+    /// Useful for answering requests from a mock in tests. Retries, interceptors
+    /// and token refresh still apply; streaming runs keep their own connection.
+    /// See [`Transport`].
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Take `base_url` and `api_key` from a named profile of the user config file
+    ///
+    /// Values set on this config still win; the profile's values are resolved by
+    /// [`Config::load_profile`](crate::utils::Config::load_profile), so
+    /// `RUNAGENT_*` environment variables override them.
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
 }
 
 impl RunAgentClient {
     /// Create a new RunAgent client from configuration
     ///
-    /// This is the single entry point for creating clients.
+    /// This is the single entry point for creating clients; see
+    /// [`RunAgentClient::for_agent`] and [`RunAgentClient::with_address`] for
+    /// positional shorthands.
     ///
     /// # Examples
     ///
@@ -218,95 +754,490 @@ impl RunAgentClient {
     /// }
     /// ```
     pub async fn new(config: RunAgentClientConfig) -> RunAgentResult<Self> {
-        use crate::constants::{DEFAULT_BASE_URL, ENV_RUNAGENT_API_KEY, ENV_RUNAGENT_BASE_URL};
+        with_connect_deadline(config.connect_timeout, async {
+            let mut client = Self::build(config).await?;
+            client.discover_region().await;
+            client.initialize_architecture().await?;
+            Ok(client)
+        })
+        .await
+    }
 
-        let local = config.local.unwrap_or(false);
-        let enable_registry = config.enable_registry.unwrap_or(local);
+    /// Convenience constructor for a local or remote agent
+    ///
+    /// Equivalent to [`RunAgentClient::new`] with
+    /// `RunAgentClientConfig::new(agent_id, entrypoint_tag).with_local(local)`:
+    /// local agents are found in the registry, remote ones use the
+    /// `RUNAGENT_*` settings.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::RunAgentClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::for_agent("agent-id", "generic", true).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn for_agent(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        local: bool,
+    ) -> RunAgentResult<Self> {
+        Self::new(RunAgentClientConfig::new(agent_id, entrypoint_tag).with_local(local)).await
+    }
 
-        // Resolve host/port for local agents
-        let (host, port) = if local {
-            // If host/port provided, use them
-            if let (Some(h), Some(p)) = (&config.host, &config.port) {
-                (Some(h.clone()), Some(*p))
-            } else if enable_registry {
-                // Try database lookup if enabled
-                #[cfg(feature = "db")]
-                {
-                    let db_service = DatabaseService::new(None).await?;
-                    if let Some(agent_info) = db_service.get_agent(&config.agent_id).await? {
-                        tracing::info!(
-                            "🔍 Found agent in database: {}:{}",
-                            agent_info.host,
-                            agent_info.port
-                        );
-                        (Some(agent_info.host), Some(agent_info.port as u16))
-                    } else {
-                        (config.host.clone(), config.port)
-                    }
-                }
-                #[cfg(not(feature = "db"))]
-                {
-                    (config.host.clone(), config.port)
-                }
-            } else {
-                (config.host.clone(), config.port)
-            }
-        } else {
-            (None, None)
-        };
+    /// Convenience constructor for an agent at an explicit address
+    ///
+    /// Equivalent to [`RunAgentClient::new`] with
+    /// [`RunAgentClientConfig::with_address`]; a missing `host` or `port` falls
+    /// back to the registry lookup or the default address.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::RunAgentClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client =
+    ///         RunAgentClient::with_address("agent-id", "generic", true, Some("localhost"), Some(8450))
+    ///             .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_address(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        local: bool,
+        host: Option<&str>,
+        port: Option<u16>,
+    ) -> RunAgentResult<Self> {
+        let mut config = RunAgentClientConfig::new(agent_id, entrypoint_tag).with_local(local);
+        config.host = host.map(str::to_string);
+        config.port = port;
+        Self::new(config).await
+    }
 
-        // Resolve API key (config > env var)
-        let api_key = config
-            .api_key
-            .or_else(|| std::env::var(ENV_RUNAGENT_API_KEY).ok());
+    /// Create a client for a local agent served at `host:port`
+    ///
+    /// Shorthand for [`RunAgentClient::new`] with [`RunAgentClientConfig::local`],
+    /// which skips the registry lookup.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::RunAgentClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::local("agent-id", "generic", "127.0.0.1", 8450).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn local(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        host: impl Into<String>,
+        port: u16,
+    ) -> RunAgentResult<Self> {
+        Self::new(RunAgentClientConfig::local(
+            agent_id,
+            entrypoint_tag,
+            host,
+            port,
+        ))
+        .await
+    }
 
-        // Resolve base URL (config > env var > default)
-        let base_url = config
-            .base_url
-            .or_else(|| std::env::var(ENV_RUNAGENT_BASE_URL).ok())
-            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+    /// Create a client for a remote agent authenticated with `api_key`
+    ///
+    /// Shorthand for [`RunAgentClient::new`] with [`RunAgentClientConfig::remote`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::RunAgentClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::remote("agent-id", "generic", "rau_key").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn remote(
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> RunAgentResult<Self> {
+        Self::new(RunAgentClientConfig::remote(
+            agent_id,
+            entrypoint_tag,
+            api_key,
+        ))
+        .await
+    }
 
-        if !local {
-            tracing::info!("🌐 Connecting to remote agent at {}", base_url);
-            if api_key.is_some() {
-                tracing::debug!("🔑 API key provided");
-            } else {
-                tracing::warn!("⚠️  No API key provided - using default limits");
-            }
-        }
+    /// Create a new client, failing fast if the server is unreachable
+    ///
+    /// Performs a health check bounded by the connect timeout before fetching the
+    /// architecture, so an unreachable server surfaces as a `Connection` error
+    /// naming the URL instead of an architecture failure. With
+    /// [region discovery](RunAgentClientConfig::with_region_discovery), a remote
+    /// client also follows the regional endpoint the server announces.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::connect(
+    ///         RunAgentClientConfig::new("agent-id", "entrypoint")
+    ///             .with_local(true)
+    ///             .with_address("127.0.0.1", 8450)
+    ///     ).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn connect(config: RunAgentClientConfig) -> RunAgentResult<Self> {
+        with_connect_deadline(config.connect_timeout, Self::connect_within(config)).await
+    }
 
-        let serializer = CoreSerializer::new(10.0)?;
-        #[cfg(feature = "db")]
-        let db_service: Option<DatabaseService> = None;
-        #[cfg(not(feature = "db"))]
-        let db_service: Option<DatabaseService> = None;
+    async fn connect_within(config: RunAgentClientConfig) -> RunAgentResult<Self> {
+        use crate::constants::DEFAULT_CONNECT_TIMEOUT_SECONDS;
 
-        let (rest_client, socket_client) = if local {
-            let host = host.ok_or_else(|| {
-                RunAgentError::validation(
-                    "Host is required for local clients. Provide host/port in config or enable registry for database lookup.",
-                )
-            })?;
-            let port = port.ok_or_else(|| {
-                RunAgentError::validation(
-                    "Port is required for local clients. Provide host/port in config or enable registry for database lookup.",
-                )
-            })?;
+        let mut client = Self::build(config).await?;
 
-            tracing::info!("🔌 Using address: {}:{}", host, port);
+        let base_url = client.rest_client.base_url().to_string();
+        let timeout = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECONDS);
+        match tokio::time::timeout(timeout, client.follow_region()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                return Err(RunAgentError::connection(format!(
+                    "Cannot reach server at {}: {}",
+                    base_url, e
+                )));
+            }
+            Err(_) => {
+                return Err(RunAgentError::connection(format!(
+                    "Cannot reach server at {}: no response within {}s",
+                    base_url, DEFAULT_CONNECT_TIMEOUT_SECONDS
+                )));
+            }
+        }
 
-            let agent_base_url = format!("http://{}:{}", host, port);
-            let agent_socket_url = format!("ws://{}:{}", host, port);
+        client.initialize_architecture().await?;
+        Ok(client)
+    }
 
-            let rest_client = RestClient::new(&agent_base_url, None, Some("/api/v1"))?;
-            let socket_client = SocketClient::new(&agent_socket_url, None, Some("/api/v1"))?;
+    /// Follow a regional endpoint for remote clients with region discovery,
+    /// ignoring failures
+    ///
+    /// Region discovery is best-effort; the architecture fetch that follows reports
+    /// an unreachable server.
+    pub(crate) async fn discover_region(&mut self) {
+        use crate::constants::DEFAULT_CONNECT_TIMEOUT_SECONDS;
+
+        if self.local || !self.region_discovery {
+            return;
+        }
+        let timeout = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECONDS);
+        match tokio::time::timeout(timeout, self.follow_region()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::debug!("Region discovery failed: {}", e),
+            Err(_) => tracing::debug!("Region discovery timed out"),
+        }
+    }
+
+    /// Health-check the server and switch to the regional endpoint it announces
+    ///
+    /// Both the REST and WebSocket base URLs are re-derived from the regional URL,
+    /// which is kept for the client's lifetime. Local clients and clients without
+    /// region discovery never switch.
+    async fn follow_region(&mut self) -> RunAgentResult<()> {
+        if !self.region_discovery {
+            return self.rest_client.health_check().await.map(|_| ());
+        }
+        let region = self.rest_client.resolve_region().await?;
+        if let (false, Some(region_url)) = (self.local, region) {
+            tracing::info!("🌍 Switching to regional endpoint {}", region_url);
+            self.switch_region(region_url)?;
+        }
+        Ok(())
+    }
+
+    /// Re-derive the REST and WebSocket transports from a regional base URL
+    fn switch_region(&mut self, region_url: String) -> RunAgentResult<()> {
+        let api_key = self.rest_client.api_key().map(|k| k.to_string());
+        let http_client = self.rest_client.http_client().clone();
+        let (rest_client, socket_client) = Self::create_remote_clients(
+            Some(&region_url),
+            api_key,
+            Some(http_client),
+            self.rest_client.api_prefix(),
+            None,
+        )?;
+        let rest_client = rest_client
+            .with_user_agent(self.rest_client.user_agent())
+            .with_region_hosts(self.rest_client.region_hosts().to_vec())
+            .with_request_compression(self.rest_client.request_compression())
+            .with_max_request_bytes(self.rest_client.max_request_bytes());
+        #[cfg(feature = "msgpack")]
+        let rest_client = rest_client.with_msgpack(self.rest_client.msgpack());
+        let rest_client = match self.rest_client.token_provider() {
+            Some(provider) => rest_client.with_token_provider(provider.clone()),
+            None => rest_client,
+        };
+        let rest_client = match self.rest_client.transport() {
+            Some(transport) => rest_client.with_transport(transport.clone()),
+            None => rest_client,
+        };
+        self.rest_client = self
+            .rest_client
+            .interceptors()
+            .iter()
+            .cloned()
+            .fold(rest_client, RestClient::with_interceptor);
+        self.socket_client = socket_client.with_settings_of(&self.socket_client);
+        self.region_base_url = Some(region_url);
+        Ok(())
+    }
+
+    /// Identifies the server the REST transport currently targets
+    pub(crate) fn endpoint_key(&self) -> String {
+        match self.rest_client.unix_socket() {
+            Some(path) => format!("unix:{}", path.display()),
+            None => self.rest_client.base_url().to_string(),
+        }
+    }
+
+    /// Architecture and regional endpoint resolved during setup, for reuse
+    pub(crate) fn setup_state(&self) -> Option<(Value, Option<String>)> {
+        let architecture = self.agent_architecture.clone()?;
+        Some((architecture, self.region_base_url.clone()))
+    }
+
+    /// Finish setup from a previously resolved architecture and region
+    pub(crate) fn restore_setup_state(
+        &mut self,
+        architecture: Value,
+        region_base_url: Option<String>,
+    ) -> RunAgentResult<()> {
+        if let (false, Some(region_url)) = (self.local, region_base_url) {
+            self.switch_region(region_url)?;
+        }
+        self.agent_architecture = Some(architecture);
+        self.validate_entrypoint()
+    }
+
+    /// [`build`](Self::build) around a shared HTTP client that already carries the
+    /// config's TLS settings, which then only configure WebSocket streams
+    pub(crate) async fn build_pooled(
+        mut config: RunAgentClientConfig,
+        http_client: reqwest::Client,
+    ) -> RunAgentResult<Self> {
+        let tls = config.tls.take();
+        config.http_client = Some(http_client);
+        let mut client = Self::build(config).await?;
+        if let (false, Some(tls)) = (client.local, tls) {
+            client.socket_client = client.socket_client.clone().with_tls(&tls)?;
+        }
+        Ok(client)
+    }
+
+    /// Resolve configuration and construct transports without any network calls
+    pub(crate) async fn build(config: RunAgentClientConfig) -> RunAgentResult<Self> {
+        use crate::constants::{DEFAULT_MAX_PAYLOAD_MB, ENV_RUNAGENT_API_KEY};
+
+        let resolve_by_registry = config.agent_id.is_empty()
+            && (config.agent_name.is_some() || config.framework.is_some());
+        #[cfg(feature = "db")]
+        let config = if resolve_by_registry {
+            let db_service = DatabaseService::new(None).await?;
+            let agent_id = resolve_agent_id(
+                &db_service,
+                config.agent_name.as_deref(),
+                config.framework.as_deref(),
+            )
+            .await?;
+            RunAgentClientConfig {
+                agent_id,
+                local: Some(config.local.unwrap_or(true)),
+                ..config
+            }
+        } else {
+            config
+        };
+        #[cfg(not(feature = "db"))]
+        if resolve_by_registry {
+            return Err(RunAgentError::config(
+                "Resolving agents by name or framework requires the `db` feature",
+            ));
+        }
+
+        if let Some(ref path) = config.payload_path {
+            if !path.is_empty() && !path.starts_with('/') {
+                return Err(RunAgentError::validation(format!(
+                    "Invalid payload_path `{}`: expected a JSON pointer such as `/result/output`",
+                    path
+                )));
+            }
+        }
+
+        let local = config.local.unwrap_or(false) || config.unix_socket.is_some();
+        let enable_registry = config.enable_registry.unwrap_or(local);
+
+        // Resolve host/port for local agents
+        let (host, port) = if config.unix_socket.is_some() {
+            (None, None)
+        } else if local {
+            // If host/port provided, use them
+            if let (Some(h), Some(p)) = (&config.host, &config.port) {
+                (Some(h.clone()), Some(*p))
+            } else if enable_registry {
+                // Try database lookup if enabled
+                #[cfg(feature = "db")]
+                {
+                    let db_service = DatabaseService::new(None).await?;
+                    if let Some(agent_info) = db_service.get_agent(&config.agent_id).await? {
+                        tracing::info!(
+                            "🔍 Found agent in database: {}:{}",
+                            agent_info.host,
+                            agent_info.port
+                        );
+                        (Some(agent_info.host), Some(agent_info.port as u16))
+                    } else {
+                        (config.host.clone(), config.port)
+                    }
+                }
+                #[cfg(not(feature = "db"))]
+                {
+                    (config.host.clone(), config.port)
+                }
+            } else {
+                (config.host.clone(), config.port)
+            }
+        } else {
+            (None, None)
+        };
+
+        // Resolve API key and base URL (config > env var > profile > config file > default),
+        // with the profile from `with_profile` or else `RUNAGENT_PROFILE`
+        let user_config = match config.profile {
+            Some(ref profile) => Config::load_profile(profile)?,
+            None => Config::load()?,
+        };
+        let api_key = config
+            .api_key
+            .or(user_config.api_key)
+            .filter(|key| !key.is_empty());
+        let base_url = config.base_url.unwrap_or(user_config.base_url);
+
+        if !local {
+            tracing::info!("🌐 Connecting to remote agent at {}", base_url);
+            if api_key.is_some() {
+                tracing::debug!("🔑 API key provided");
+            } else if config.token_provider.is_some() {
+                tracing::debug!("🔑 Token provider configured");
+            } else if config.require_api_key.unwrap_or(false) {
+                return Err(RunAgentError::config(format!(
+                    "API key required for remote agent {}: set api_key in the config or the {} environment variable",
+                    config.agent_id, ENV_RUNAGENT_API_KEY
+                )));
+            } else {
+                tracing::warn!("⚠️  No API key provided - using default limits");
+            }
+        }
+
+        let max_response_mb = config.max_response_mb.unwrap_or(DEFAULT_MAX_PAYLOAD_MB);
+        let serializer = CoreSerializer::new(max_response_mb)?
+            .with_envelope_unwrap(config.unwrap_envelopes.unwrap_or(true));
+        #[cfg(feature = "db")]
+        let db_service: Option<DatabaseService> = None;
+
+        let compress_requests =
+            !local && config.unix_socket.is_none() && config.compression.unwrap_or(false);
+        let msgpack = config.msgpack.unwrap_or(false);
+        if msgpack && !cfg!(feature = "msgpack") {
+            return Err(RunAgentError::config(
+                "MessagePack responses require the `msgpack` feature of the runagent crate",
+            ));
+        }
+        let api_prefix = normalize_api_prefix(config.api_prefix.as_deref());
+        let (rest_client, socket_client) = if let Some(path) = config.unix_socket {
+            Self::create_unix_clients(path, &api_prefix)?
+        } else if local {
+            let (Some(host), Some(port)) = (host, port) else {
+                return Err(missing_local_address(&config.agent_id, enable_registry));
+            };
+
+            tracing::info!("🔌 Using address: {}:{}", host, port);
+
+            let agent_base_url = format!("http://{}:{}", host, port);
+            let agent_socket_url = format!("ws://{}:{}", host, port);
+
+            let rest_client = match config.http_client {
+                Some(http_client) => RestClient::with_http_client(
+                    http_client,
+                    &agent_base_url,
+                    None,
+                    Some(&api_prefix),
+                ),
+                None => RestClient::new(&agent_base_url, None, Some(&api_prefix))?,
+            };
+            let socket_client = SocketClient::new(&agent_socket_url, None, Some(&api_prefix))?;
 
             (rest_client, socket_client)
         } else {
-            Self::create_remote_clients(Some(&base_url), api_key)?
+            Self::create_remote_clients(
+                Some(&base_url),
+                api_key,
+                config.http_client,
+                &api_prefix,
+                config.tls.as_ref(),
+            )?
+        };
+
+        let rest_client = config
+            .interceptors
+            .into_iter()
+            .fold(rest_client, RestClient::with_interceptor)
+            .with_request_compression(compress_requests)
+            .with_region_hosts(config.region_hosts);
+        let rest_client = match config.max_request_mb {
+            Some(max_request_mb) => rest_client.with_max_request_mb(max_request_mb),
+            None => rest_client,
         };
+        let rest_client = match config.transport {
+            Some(transport) => rest_client.with_transport(transport),
+            None => rest_client,
+        };
+        #[cfg(feature = "msgpack")]
+        let rest_client = rest_client.with_msgpack(msgpack);
+        let (rest_client, socket_client) = match config.token_provider {
+            Some(provider) => (
+                rest_client.with_token_provider(provider.clone()),
+                socket_client.with_token_provider(provider),
+            ),
+            None => (rest_client, socket_client),
+        };
+        let (rest_client, socket_client) = match config.user_agent_suffix.as_deref() {
+            Some(suffix) => (
+                rest_client.with_user_agent_suffix(suffix),
+                socket_client.with_user_agent_suffix(suffix),
+            ),
+            None => (rest_client, socket_client),
+        };
+        let socket_client = socket_client
+            .with_server_ping_response(config.respond_to_server_pings.unwrap_or(true))
+            .with_config(config.stream_reconnect.unwrap_or_default())
+            .with_serializer(serializer.clone());
 
-        let mut client = Self {
+        Ok(Self {
             agent_id: config.agent_id,
             entrypoint_tag: config.entrypoint_tag,
             local,
@@ -317,17 +1248,25 @@ impl RunAgentClient {
             extra_params: config.extra_params,
             user_id: config.user_id,
             persistent_memory: config.persistent_memory.unwrap_or(false),
+            payload_path: config.payload_path,
+            retry_policy: config.retry_policy.unwrap_or_else(RetryPolicy::disabled),
+            region_base_url: None,
+            region_discovery: config.region_discovery.unwrap_or(false),
+            stream_fallback_to_run: config.stream_fallback_to_run.unwrap_or(false),
+            detect_generator_misuse: config.detect_generator_misuse.unwrap_or(true),
+            validate_input: config.validate_input.unwrap_or(false),
+            stream_transport: config.stream_transport.unwrap_or_default(),
+            chunk_timeout: config.chunk_timeout,
+            default_kwargs: config.default_kwargs.unwrap_or_default(),
+            attachment_encoding: config.attachment_encoding.unwrap_or_default(),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
 
             #[cfg(feature = "db")]
             db_service,
-        };
-
-        client.initialize_architecture().await?;
-
-        Ok(client)
+        })
     }
 
-    async fn initialize_architecture(&mut self) -> RunAgentResult<()> {
+    pub(crate) async fn initialize_architecture(&mut self) -> RunAgentResult<()> {
         let architecture = self.get_agent_architecture_internal().await?;
         self.agent_architecture = Some(architecture);
         self.validate_entrypoint()?;
@@ -362,16 +1301,46 @@ impl RunAgentClient {
                         self.agent_id,
                         available
                     );
-                    return Err(RunAgentError::validation(format!(
-                        "Entrypoint `{}` not found in agent {}",
-                        self.entrypoint_tag, self.agent_id
-                    )));
+                    return Err(RunAgentError::execution(
+                        "ENTRYPOINT_NOT_FOUND".to_string(),
+                        format!(
+                            "Entrypoint `{}` not found in agent {}",
+                            self.entrypoint_tag, self.agent_id
+                        ),
+                        Some(format!("Available entrypoints: {}", available.join(", "))),
+                        None,
+                    ));
                 }
             }
         }
         Ok(())
     }
 
+    /// Get a client for another entrypoint of the same agent
+    ///
+    /// Reuses this client's transports, settings and fetched architecture, so no
+    /// network calls are made. The tag is checked against the architecture.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let chat = RunAgentClient::new(RunAgentClientConfig::new("agent-id", "chat")).await?;
+    ///     let chat_stream = chat.with_entrypoint("chat_stream")?;
+    ///     let history = chat.with_entrypoint("get_history")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_entrypoint(&self, tag: &str) -> RunAgentResult<RunAgentClient> {
+        let mut client = self.clone();
+        client.entrypoint_tag = tag.to_string();
+        client.validate_entrypoint()?;
+        Ok(client)
+    }
+
     /// Run the agent with keyword arguments only
     pub async fn run(&self, input_kwargs: &[(&str, Value)]) -> RunAgentResult<Value> {
         self.run_with_args(&[], input_kwargs).await
@@ -383,197 +1352,1253 @@ impl RunAgentClient {
         input_args: &[Value],
         input_kwargs: &[(&str, Value)],
     ) -> RunAgentResult<Value> {
-        if self.entrypoint_tag.ends_with("_stream") {
-            return Err(RunAgentError::validation(
-                "Use run_stream for streaming entrypoints".to_string(),
-            ));
-        }
-
-        let input_kwargs_map: HashMap<String, Value> = input_kwargs
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.clone()))
-            .collect();
+        self.reject_stream_entrypoint()?;
 
-        let response = self
-            .rest_client
-            .run_agent(
-                &self.agent_id,
-                &self.entrypoint_tag,
-                input_args,
-                &input_kwargs_map,
-                self.user_id.as_deref(),
-                self.persistent_memory,
-            )
-            .await?;
+        self.run_entrypoint(&self.entrypoint_tag, input_args, input_kwargs, None, None)
+            .await
+            .map(|(value, _)| value)
+    }
 
-        if response
-            .get("success")
-            .and_then(|s| s.as_bool())
-            .unwrap_or(false)
-        {
-            // Process response data
-            let mut payload: Option<Value> = None;
-
-            if let Some(data) = response.get("data") {
-                // Case 1: data is a string (simplified payload - could be JSON string with {type, payload})
-                if data.as_str().is_some() {
-                    // Check for generator object BEFORE processing (case-insensitive)
-                    if let Some(data_str) = data.as_str() {
-                        let lower_str = data_str.to_lowercase();
-                        if lower_str.contains("generator object")
-                            || lower_str.contains("<generator")
-                        {
-                            let streaming_tag = format!("{}_stream", self.entrypoint_tag);
-                            return Err(RunAgentError::validation(format!(
-                                "Agent returned a generator object instead of content. This entrypoint appears to be a streaming function.\n\
-                                Try using the streaming endpoint: `{}`\n\
-                                Or use `run_stream()` method instead of `run()`.",
-                                streaming_tag
-                            )));
-                        }
-                    }
-                    // Use common deserializer preparation logic
-                    let prepared = self.serializer.prepare_for_deserialization(data.clone());
-                    payload = Some(prepared);
-                }
-                // Case 2: data has result_data.data (legacy detailed execution payload)
-                else if let Some(result_data) = data.get("result_data") {
-                    if let Some(output_data) = result_data.get("data") {
-                        // Check for generator object in nested data (case-insensitive)
-                        if let Some(output_str) = output_data.as_str() {
-                            let lower_str = output_str.to_lowercase();
-                            if lower_str.contains("generator object")
-                                || lower_str.contains("<generator")
-                            {
-                                let streaming_tag = format!("{}_stream", self.entrypoint_tag);
-                                return Err(RunAgentError::validation(format!(
-                                    "Agent returned a generator object instead of content. This entrypoint appears to be a streaming function.\n\
-                                    Try using the streaming endpoint: `{}`\n\
-                                    Or use `run_stream()` method instead of `run()`.",
-                                    streaming_tag
-                                )));
-                            }
-                        }
-                        payload = Some(output_data.clone());
-                    }
-                }
-                // Case 3: data is an object (could be {type, payload} structure)
-                else if data.is_object() {
-                    payload = Some(data.clone());
-                }
-            }
-            // Case 4: Fallback to output_data (backward compatibility)
-            else if let Some(output_data) = response.get("output_data") {
-                // Check for generator object in output_data (case-insensitive)
-                if let Some(output_str) = output_data.as_str() {
-                    let lower_str = output_str.to_lowercase();
-                    if lower_str.contains("generator object") || lower_str.contains("<generator") {
-                        let streaming_tag = format!("{}_stream", self.entrypoint_tag);
-                        return Err(RunAgentError::validation(format!(
-                            "Agent returned a generator object instead of content. This entrypoint appears to be a streaming function.\n\
-                            Try using the streaming endpoint: `{}`\n\
-                            Or use `run_stream()` method instead of `run()`.",
-                            streaming_tag
-                        )));
-                    }
-                }
-                payload = Some(output_data.clone());
-            }
+    /// Run the agent, telling a `null` payload apart from a missing one
+    ///
+    /// `run` returns `Value::Null` in both cases. See [`RunOutcome`] for how
+    /// response shapes map to outcomes.
+    pub async fn run_outcome(&self, input_kwargs: &[(&str, Value)]) -> RunAgentResult<RunOutcome> {
+        self.reject_stream_entrypoint()?;
 
-            // Deserialize the payload using serializer (handles {type, payload} structure)
-            if let Some(payload_val) = payload {
-                // Check for generator object warning (case-insensitive, after deserialization)
-                if let Some(content_str) = payload_val.as_str() {
-                    let lower_str = content_str.to_lowercase();
-                    if lower_str.contains("generator object") || lower_str.contains("<generator") {
-                        // Check if there's a streaming version of this entrypoint
-                        let streaming_tag = format!("{}_stream", self.entrypoint_tag);
-                        return Err(RunAgentError::validation(format!(
-                            "Agent returned a generator object instead of content. This entrypoint appears to be a streaming function.\n\
-                            Try using the streaming endpoint: `{}`\n\
-                            Or use `run_stream()` method instead of `run()`.",
-                            streaming_tag
-                        )));
-                    }
-                }
-                // Deserialize the payload - this should extract payload from {type, payload} structure
-                let deserialized = self.serializer.deserialize_object(payload_val)?;
-                return Ok(deserialized);
-            }
-            Ok(Value::Null)
-        } else {
-            // Handle new error format with ErrorDetail object (matching Python SDK)
-            if let Some(error_info) = response.get("error") {
-                if let Some(error_obj) = error_info.as_object() {
-                    if let (Some(message), Some(code)) = (
-                        error_obj.get("message").and_then(|m| m.as_str()),
-                        error_obj.get("code").and_then(|c| c.as_str()),
-                    ) {
-                        return Err(RunAgentError::server(format!("[{}] {}", code, message)));
-                    }
-                }
-                // Fallback to old format
-                if let Some(error_msg) = error_info.as_str() {
-                    return Err(RunAgentError::server(error_msg));
-                }
-            }
-            Err(RunAgentError::server("Unknown error"))
-        }
+        self.run_entrypoint_outcome(&self.entrypoint_tag, &[], input_kwargs, &[], None, None)
+            .await
+            .map(|(outcome, _)| outcome)
     }
 
-    /// Run the agent and return a stream of responses
-    pub async fn run_stream(
+    /// Run the agent with binary attachments, such as a PDF or an image
+    ///
+    /// With the default [`AttachmentEncoding::Base64`], the attachments are sent
+    /// as base64 objects in the `attachments` kwarg, which the call's kwargs must
+    /// not set themselves. With [`AttachmentEncoding::Multipart`], they are file
+    /// parts of a `multipart/form-data` request, for servers that accept one.
+    /// Either way the whole request counts toward
+    /// [`RunAgentClientConfig::with_max_request_mb`]; base64 grows each attachment
+    /// by a third. Files the agent returns in the same base64 form decode with
+    /// [`Attachment::from_value`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::types::Attachment;
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "summarize").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let report = Attachment::from_file("report.pdf", "application/pdf")?;
+    ///     let summary = client
+    ///         .run_with_attachments(&[("question", json!("Key findings?"))], &[report])
+    ///         .await?;
+    ///     println!("{}", summary);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_with_attachments(
         &self,
         input_kwargs: &[(&str, Value)],
-    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
-        self.run_stream_with_args(&[], input_kwargs).await
+        attachments: &[Attachment],
+    ) -> RunAgentResult<Value> {
+        self.reject_stream_entrypoint()?;
+
+        self.run_entrypoint_outcome(
+            &self.entrypoint_tag,
+            &[],
+            input_kwargs,
+            attachments,
+            None,
+            None,
+        )
+        .await
+        .map(|(outcome, _)| outcome.into_value())
     }
 
-    /// Run the agent with streaming and both positional and keyword arguments
-    pub async fn run_stream_with_args(
+    /// Run the agent and deserialize the payload into `T`
+    ///
+    /// Use [`AgentResponse`](crate::types::AgentResponse) for common-field accessors,
+    /// or your own `Deserialize` type to catch schema mismatches at the boundary.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Chat {
+    ///     response: String,
+    ///     thread_id: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "chat").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let chat: Chat = client
+    ///         .run_typed(&[("message", json!("Hi")), ("thread_id", json!("t1"))])
+    ///         .await?;
+    ///     println!("[{}] {}", chat.thread_id, chat.response);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_typed<T: DeserializeOwned>(
         &self,
-        input_args: &[Value],
         input_kwargs: &[(&str, Value)],
-    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
-        if !self.entrypoint_tag.ends_with("_stream") {
-            return Err(RunAgentError::validation(
-                "Use run() for non-stream entrypoints".to_string(),
-            ));
-        }
+    ) -> RunAgentResult<T> {
+        AgentResponse::from(self.run(input_kwargs).await?).into()
+    }
 
-        let input_kwargs_map: HashMap<String, Value> = input_kwargs
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.clone()))
-            .collect();
+    /// Run the agent and return the payload with the run's timing and token usage
+    ///
+    /// Metadata is read from the response envelope (see
+    /// [`ExecutionMetadata::from_response`]); fields the agent did not report are
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "entrypoint").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let (result, metadata) = client.run_with_metadata(&[("message", json!("Hi"))]).await?;
+    ///     println!("{} ({:?} tokens in)", result, metadata.input_tokens);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_with_metadata(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<(Value, ExecutionMetadata)> {
+        self.reject_stream_entrypoint()?;
 
-        self.socket_client
-            .run_stream(
-                &self.agent_id,
-                &self.entrypoint_tag,
-                input_args,
-                &input_kwargs_map,
-                self.user_id.as_deref(),
-                self.persistent_memory,
-            )
+        self.run_entrypoint(&self.entrypoint_tag, &[], input_kwargs, None, None)
             .await
     }
 
-    /// Get the agent's architecture information
-    pub async fn get_agent_architecture(&self) -> RunAgentResult<Value> {
-        self.rest_client
-            .get_agent_architecture(&self.agent_id)
-            .await
-    }
+    /// Run the agent with a per-call timeout
+    ///
+    /// Overrides the client's default request timeout for this call and sends it
+    /// to the server as `timeout_seconds` (rounded up to whole seconds). When the
+    /// deadline elapses the call fails with [`RunAgentError::Timeout`]. With a
+    /// retry policy, the timeout applies to each attempt.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig, RunAgentError};
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "entrypoint").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     match client
+    ///         .run_with_timeout(&[("message", json!("Hi"))], Duration::from_secs(5))
+    ///         .await
+    ///     {
+    ///         Ok(result) => println!("{}", result),
+    ///         Err(RunAgentError::Timeout { elapsed }) => eprintln!("gave up after {:?}", elapsed),
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_with_timeout(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        timeout: Duration,
+    ) -> RunAgentResult<Value> {
+        self.reject_stream_entrypoint()?;
 
-    /// Check if the agent is available
-    pub async fn health_check(&self) -> RunAgentResult<bool> {
-        match self.rest_client.health_check().await {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
-        }
+        self.run_entrypoint(&self.entrypoint_tag, &[], input_kwargs, Some(timeout), None)
+            .await
+            .map(|(value, _)| value)
     }
 
-    /// Get agent information
+    /// Run the agent with an idempotency key
+    ///
+    /// The key is sent as `idempotency_key` in the run body and as the
+    /// `Idempotency-Key` header, so servers that support it can run a repeated
+    /// request only once. Retries under the client's retry policy reuse the key.
+    ///
+    /// Within this process, concurrent calls with the same key on this client or
+    /// its clones share a single request: later callers wait for the first
+    /// call's result instead of sending their own. That run continues even if
+    /// every caller stops waiting. Once it finishes the key is released, so a
+    /// later call runs again unless the server deduplicates it. Beyond this,
+    /// at-most-once execution depends entirely on the server honouring the key.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "generate_report").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let report = client
+    ///         .run_idempotent("report-2024-06-01", &[("date", json!("2024-06-01"))])
+    ///         .await?;
+    ///     println!("{}", report);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_idempotent(
+        &self,
+        key: &str,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Value> {
+        self.reject_stream_entrypoint()?;
+
+        let run = self
+            .lock_in_flight()
+            .entry(key.to_string())
+            .or_insert_with(|| self.start_idempotent_run(key, input_kwargs))
+            .clone();
+        run.await.map_err(|e| e.duplicate())
+    }
+
+    /// Spawn the run for a new idempotency key, releasing the key when it finishes
+    fn start_idempotent_run(&self, key: &str, input_kwargs: &[(&str, Value)]) -> InFlightRun {
+        let client = self.clone();
+        let key = key.to_string();
+        let input_kwargs: Vec<(String, Value)> = input_kwargs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+
+        let handle = tokio::spawn(async move {
+            let input_kwargs: Vec<(&str, Value)> = input_kwargs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.clone()))
+                .collect();
+            let result = client
+                .run_entrypoint(&client.entrypoint_tag, &[], &input_kwargs, None, Some(&key))
+                .await
+                .map(|(value, _)| value)
+                .map_err(Arc::new);
+            client.lock_in_flight().remove(&key);
+            result
+        });
+        async move {
+            handle.await.unwrap_or_else(|e| {
+                Err(Arc::new(RunAgentError::generic(format!(
+                    "Idempotent run task failed: {}",
+                    e
+                ))))
+            })
+        }
+        .boxed()
+        .shared()
+    }
+
+    fn lock_in_flight(&self) -> std::sync::MutexGuard<'_, HashMap<String, InFlightRun>> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Fail fast when a non-streaming run targets a `_stream` entrypoint
+    fn reject_stream_entrypoint(&self) -> RunAgentResult<()> {
+        if self.entrypoint_tag.ends_with("_stream") {
+            return Err(RunAgentError::execution(
+                "STREAM_ENTRYPOINT",
+                "Use run_stream for streaming entrypoints",
+                Some(format!(
+                    "Entrypoint `{}` streams its output; call run_stream() instead",
+                    self.entrypoint_tag
+                )),
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run the agent for many inputs concurrently
+    ///
+    /// At most `concurrency` runs are in flight at once (`0` is treated as `1`).
+    /// Results are returned in input order, and a failed run only fails its own slot.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "score_lead").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let leads = ["Acme", "Globex", "Initech"];
+    ///     let inputs = leads
+    ///         .iter()
+    ///         .map(|lead| vec![("company", json!(lead))])
+    ///         .collect();
+    ///
+    ///     for (lead, result) in leads.iter().zip(client.run_batch(inputs, 4).await) {
+    ///         match result {
+    ///             Ok(score) => println!("{}: {}", lead, score),
+    ///             Err(e) => eprintln!("{}: {}", lead, e),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_batch(
+        &self,
+        inputs: Vec<Vec<(&str, Value)>>,
+        concurrency: usize,
+    ) -> Vec<RunAgentResult<Value>> {
+        let mut results: Vec<(usize, RunAgentResult<Value>)> = stream::iter(inputs)
+            .enumerate()
+            .map(|(index, input_kwargs)| async move { (index, self.run(&input_kwargs).await) })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        results.sort_unstable_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Run a non-streaming entrypoint by tag and extract its payload
+    async fn run_entrypoint(
+        &self,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &[(&str, Value)],
+        timeout: Option<Duration>,
+        idempotency_key: Option<&str>,
+    ) -> RunAgentResult<(Value, ExecutionMetadata)> {
+        self.run_entrypoint_outcome(
+            entrypoint_tag,
+            input_args,
+            input_kwargs,
+            &[],
+            timeout,
+            idempotency_key,
+        )
+        .await
+        .map(|(outcome, metadata)| (outcome.into_value(), metadata))
+    }
+
+    /// Run a non-streaming entrypoint by tag and classify its payload
+    #[tracing::instrument(
+        name = "runagent.run",
+        skip_all,
+        fields(agent_id = %self.agent_id, entrypoint_tag = %entrypoint_tag, local = self.local)
+    )]
+    async fn run_entrypoint_outcome(
+        &self,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &[(&str, Value)],
+        attachments: &[Attachment],
+        timeout: Option<Duration>,
+        idempotency_key: Option<&str>,
+    ) -> RunAgentResult<(RunOutcome, ExecutionMetadata)> {
+        let started = Instant::now();
+        let result = self
+            .execute_entrypoint(
+                entrypoint_tag,
+                input_args,
+                input_kwargs,
+                attachments,
+                timeout,
+                idempotency_key,
+            )
+            .await;
+        metrics::record_run(started.elapsed(), &result);
+        result
+    }
+
+    async fn execute_entrypoint(
+        &self,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &[(&str, Value)],
+        attachments: &[Attachment],
+        timeout: Option<Duration>,
+        idempotency_key: Option<&str>,
+    ) -> RunAgentResult<(RunOutcome, ExecutionMetadata)> {
+        let mut input_kwargs_map = self.merged_kwargs(entrypoint_tag, input_kwargs)?;
+        let multipart =
+            !attachments.is_empty() && self.attachment_encoding == AttachmentEncoding::Multipart;
+        if !attachments.is_empty() && !multipart {
+            if input_kwargs_map.contains_key(ATTACHMENTS_KWARG) {
+                return Err(RunAgentError::validation(format!(
+                    "The `{}` kwarg is set by run_with_attachments; pass attachments there only",
+                    ATTACHMENTS_KWARG
+                )));
+            }
+            let encoded = attachments.iter().map(Attachment::to_value).collect();
+            input_kwargs_map.insert(ATTACHMENTS_KWARG.to_string(), Value::Array(encoded));
+        }
+
+        let mut request = RestClient::run_request(
+            entrypoint_tag,
+            input_args,
+            &input_kwargs_map,
+            self.user_id.as_deref(),
+            self.persistent_memory,
+        );
+        if let Some(timeout) = timeout {
+            request["timeout_seconds"] = Value::from(timeout.as_secs_f64().ceil().max(1.0) as u64);
+        }
+        if let Some(key) = idempotency_key {
+            request["idempotency_key"] = Value::from(key);
+        }
+
+        // Shared across retries; large bodies are streamed rather than copied
+        let request = Arc::new(request);
+        let multipart_body = if multipart {
+            Some(MultipartBody::new(&request, attachments)?)
+        } else {
+            None
+        };
+        let response = self
+            .retry_policy
+            .retry(|| async {
+                match &multipart_body {
+                    Some(body) => {
+                        self.rest_client
+                            .send_multipart_run_request(&self.agent_id, &request, body, timeout)
+                            .await
+                    }
+                    None => {
+                        self.rest_client
+                            .send_shared_run_request(
+                                &self.agent_id,
+                                entrypoint_tag,
+                                &request,
+                                timeout,
+                            )
+                            .await
+                    }
+                }
+            })
+            .await?;
+
+        let envelope = ResponseEnvelope::parse(&response);
+        if let ResponseEnvelope::Failure(detail) = envelope {
+            return Err(detail.into());
+        }
+        let metadata = ExecutionMetadata::from_response(&response);
+
+        // A user-configured payload location takes precedence over the envelope
+        let payload = match payload_at_path(&response, self.payload_path.as_deref()) {
+            Some(custom) => Some(
+                self.serializer
+                    .try_prepare_for_deserialization(custom.clone())?,
+            ),
+            None => match envelope {
+                ResponseEnvelope::Serialized(data) => {
+                    self.check_generator_misuse(&data, entrypoint_tag)?;
+                    Some(self.serializer.try_prepare_for_deserialization(data)?)
+                }
+                envelope => envelope.into_payload(),
+            },
+        };
+
+        // Deserialize the payload using serializer (handles {type, payload} structure)
+        if let Some(payload_val) = payload {
+            self.check_generator_misuse(&payload_val, entrypoint_tag)?;
+            let deserialized = self.serializer.deserialize_object(payload_val)?;
+            return Ok((RunOutcome::from_payload(Some(deserialized)), metadata));
+        }
+        Ok((RunOutcome::NoPayload, metadata))
+    }
+
+    /// Fail when a run returned the repr of a Python generator instead of content
+    ///
+    /// This happens when a streaming entrypoint is called with `run`. Disabled by
+    /// [`RunAgentClientConfig::with_detect_generator_misuse`].
+    fn check_generator_misuse(&self, value: &Value, entrypoint_tag: &str) -> RunAgentResult<()> {
+        if !self.detect_generator_misuse || !value.as_str().is_some_and(is_generator_repr) {
+            return Ok(());
+        }
+        let streaming_tag = format!("{}_stream", entrypoint_tag);
+        Err(RunAgentError::validation(format!(
+            "Agent returned a generator object instead of content. This entrypoint appears to be a streaming function.\n\
+            Try using the streaming endpoint: `{}`\n\
+            Or use `run_stream()` method instead of `run()`.",
+            streaming_tag
+        )))
+    }
+
+    /// Run the agent and return a stream of responses
+    pub async fn run_stream(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        self.run_stream_with_args(&[], input_kwargs).await
+    }
+
+    /// Run the agent with streaming and both positional and keyword arguments
+    pub async fn run_stream_with_args(
+        &self,
+        input_args: &[Value],
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        self.open_stream(input_args, input_kwargs, None, false)
+            .await
+    }
+
+    /// Run the agent with streaming under a per-stream resilience policy
+    ///
+    /// The policy's reconnect and idle-timeout settings replace the client's for
+    /// this stream only; see [`StreamPolicy`]. With a reconnect policy, a dropped
+    /// connection is resumed as described in [`SocketClient::run_stream`], which
+    /// applies to the WebSocket transport.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig, SocketClientConfig, StreamPolicy};
+    /// use futures::StreamExt;
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "generic_stream").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let policy = StreamPolicy::new()
+    ///         .with_reconnect(SocketClientConfig::default().with_max_reconnects(3))
+    ///         .with_idle_timeout(Duration::from_secs(30));
+    ///     let mut stream = client
+    ///         .run_stream_with_policy(&[("message", json!("Hi"))], &policy)
+    ///         .await?;
+    ///     while let Some(chunk) = stream.next().await {
+    ///         println!("{}", chunk?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_stream_with_policy(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        policy: &StreamPolicy,
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        let mut client = self.clone();
+        if let Some(reconnect) = &policy.reconnect {
+            client.socket_client = client.socket_client.with_config(reconnect.clone());
+        }
+        if let Some(idle_timeout) = policy.idle_timeout {
+            client.chunk_timeout = Some(idle_timeout);
+        }
+        client.open_stream(&[], input_kwargs, None, false).await
+    }
+
+    /// Run the agent with streaming, delivering errors as in-band items
+    ///
+    /// Instead of ending on the first failure, the stream yields
+    /// `{"type": "error", "message": ..., "category": ..., "recoverable": bool}`
+    /// items and never an `Err`. Server error frames are recoverable unless they
+    /// carry `"recoverable": false`, and a chunk that fails to deserialize is
+    /// skipped with a recoverable error; the stream goes on after either. It ends
+    /// on a terminal event or after an unrecoverable error, such as a dropped
+    /// connection that could not be resumed. Failing to open the stream is still
+    /// returned as an `Err`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use futures::StreamExt;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "review_stream").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let mut stream = client.run_stream_lenient(&[("diff", json!("..."))]).await?;
+    ///     while let Some(chunk) = stream.next().await {
+    ///         let chunk = chunk?;
+    ///         if chunk["type"] == "error" {
+    ///             eprintln!("agent error: {}", chunk["message"]);
+    ///         } else {
+    ///             println!("{}", chunk);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_stream_lenient(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        self.open_stream(&[], input_kwargs, None, true).await
+    }
+
+    /// Run the agent with streaming, stopping when `token` is cancelled
+    ///
+    /// On cancellation a close frame is sent to the server, any partially received
+    /// chunk is dropped, and `{"type": "cancelled"}` is yielded as the final item so
+    /// consumers can tell an aborted stream from a natural end.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{CancellationToken, RunAgentClient, RunAgentClientConfig};
+    /// use futures::StreamExt;
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "generic_stream").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let token = CancellationToken::new();
+    ///     let canceller = token.clone();
+    ///     tokio::spawn(async move {
+    ///         tokio::time::sleep(Duration::from_secs(10)).await;
+    ///         canceller.cancel();
+    ///     });
+    ///
+    ///     let mut stream = client
+    ///         .run_stream_with_cancel(&[("message", json!("Write a long story"))], token)
+    ///         .await?;
+    ///     while let Some(chunk) = stream.next().await {
+    ///         println!("{}", chunk?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_stream_with_cancel(
+        &self,
+        input_kwargs: &[(&str, Value)],
+        token: CancellationToken,
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        self.open_stream(&[], input_kwargs, Some(token), false)
+            .await
+    }
+
+    async fn open_stream(
+        &self,
+        input_args: &[Value],
+        input_kwargs: &[(&str, Value)],
+        cancel: Option<CancellationToken>,
+        lenient: bool,
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        if !self.entrypoint_tag.ends_with("_stream") {
+            return Err(RunAgentError::execution(
+                "NON_STREAM_ENTRYPOINT",
+                "Use run() for non-stream entrypoints",
+                Some("Streaming entrypoint tags end with `_stream`".to_string()),
+                None,
+            ));
+        }
+
+        let input_kwargs_map = self.merged_kwargs(&self.entrypoint_tag, input_kwargs)?;
+
+        let span = tracing::info_span!(
+            "runagent.run_stream",
+            agent_id = %self.agent_id,
+            entrypoint_tag = %self.entrypoint_tag,
+            local = self.local,
+            chunks = tracing::field::Empty,
+        );
+        let result = match self.stream_transport {
+            StreamTransport::Ws => self
+                .socket_client
+                .start_stream(
+                    &self.agent_id,
+                    &self.entrypoint_tag,
+                    input_args,
+                    &input_kwargs_map,
+                    self.user_id.as_deref(),
+                    self.persistent_memory,
+                )
+                .instrument(span.clone())
+                .await
+                .map(|session| self.socket_client.chunk_stream(session, cancel, lenient)),
+            StreamTransport::Sse => {
+                let sse_client =
+                    SseClient::with_serializer(self.rest_client.clone(), self.serializer.clone());
+                sse_client
+                    .start_stream(
+                        &self.agent_id,
+                        &self.entrypoint_tag,
+                        input_args,
+                        &input_kwargs_map,
+                        self.user_id.as_deref(),
+                        self.persistent_memory,
+                    )
+                    .instrument(span.clone())
+                    .await
+                    .map(|response| sse_client.chunk_stream(response, cancel, lenient))
+            }
+        };
+        metrics::record_stream_start(&result);
+
+        match result {
+            Ok(stream) => {
+                let stream = match self.chunk_timeout {
+                    Some(chunk_timeout) => idle_timeout_stream(stream, chunk_timeout),
+                    None => stream,
+                };
+                let stream = traced_stream(stream, span);
+                if lenient {
+                    // Any remaining error ends the stream, so it is unrecoverable
+                    return Ok(Box::pin(
+                        stream.map(|item| item.or_else(|e| Ok(error_item(&e, false)))),
+                    ));
+                }
+                Ok(stream)
+            }
+            Err(RunAgentError::Connection { message }) if self.stream_fallback_to_run => {
+                let Some(run_tag) = self.fallback_entrypoint() else {
+                    return Err(RunAgentError::Connection { message });
+                };
+                tracing::warn!(
+                    "⚠️  {} stream unavailable ({}); falling back from `{}` to non-streaming `{}`",
+                    self.stream_transport,
+                    message,
+                    self.entrypoint_tag,
+                    run_tag
+                );
+                let (value, _) = self
+                    .run_entrypoint(run_tag, input_args, input_kwargs, None, None)
+                    .await?;
+                Ok(Box::pin(futures::stream::iter([Ok(value)])))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Call kwargs over the configured defaults, checked against the entrypoint's schema
+    fn merged_kwargs(
+        &self,
+        entrypoint_tag: &str,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<HashMap<String, Value>> {
+        let mut merged = self.default_kwargs.clone();
+        merged.extend(input_kwargs.iter().map(|(k, v)| (k.to_string(), v.clone())));
+        if self.default_kwargs.is_empty() {
+            self.validate_kwargs_for(entrypoint_tag, input_kwargs)?;
+        } else if self.validate_input {
+            let merged_pairs: Vec<(&str, Value)> = merged
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.clone()))
+                .collect();
+            self.validate_kwargs_for(entrypoint_tag, &merged_pairs)?;
+        }
+        Ok(merged)
+    }
+
+    /// Check kwargs against the entrypoint's `input_schema`, when enabled and present
+    fn validate_kwargs_for(
+        &self,
+        entrypoint_tag: &str,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<()> {
+        if !self.validate_input {
+            return Ok(());
+        }
+        let schema = self
+            .agent_architecture
+            .as_ref()
+            .and_then(|architecture| architecture.get("entrypoints"))
+            .and_then(|entrypoints| entrypoints.as_array())
+            .and_then(|entrypoints| {
+                entrypoints
+                    .iter()
+                    .find(|ep| ep.get("tag").and_then(|t| t.as_str()) == Some(entrypoint_tag))
+            })
+            .and_then(|ep| ep.get("input_schema"));
+        match schema {
+            Some(schema) => validate_kwargs(schema, input_kwargs),
+            None => Ok(()),
+        }
+    }
+
+    /// Non-streaming sibling of the streaming entrypoint, if the agent exposes it
+    fn fallback_entrypoint(&self) -> Option<&str> {
+        let run_tag = self.entrypoint_tag.strip_suffix("_stream")?;
+        self.has_entrypoint(run_tag).then_some(run_tag)
+    }
+
+    /// Whether the agent architecture lists `tag`; `None` if no architecture is known
+    pub(crate) fn lists_entrypoint(&self, tag: &str) -> Option<bool> {
+        let entrypoints = self
+            .agent_architecture
+            .as_ref()?
+            .get("entrypoints")?
+            .as_array()?;
+        Some(
+            entrypoints
+                .iter()
+                .any(|ep| ep.get("tag").and_then(|t| t.as_str()) == Some(tag)),
+        )
+    }
+
+    /// Run the agent and return a typed stream modelling the response lifecycle
+    ///
+    /// Chunks are classified into [`StreamItem`](crate::StreamItem)s; use
+    /// [`RunStream::into_raw`] or [`RunAgentClient::run_stream`] for raw chunks.
+    pub async fn run_stream_lifecycle(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<RunStream> {
+        Ok(RunStream::new(self.run_stream(input_kwargs).await?))
+    }
+
+    /// Run the agent, streaming chunks while also delivering the final chunk
+    ///
+    /// The receiver resolves once the stream ends, to the `complete` chunk (e.g. a
+    /// LangGraph thread's final state) or the `stream_end` chunk. See
+    /// [`AgentStream::with_final`] for the exact rules.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use futures::StreamExt;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "chat_stream").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let (mut stream, final_chunk) = client
+    ///         .run_stream_with_final(&[("message", json!("Hi"))])
+    ///         .await?;
+    ///     while let Some(chunk) = stream.next().await {
+    ///         println!("{}", chunk?);
+    ///     }
+    ///     if let Ok(Ok(state)) = final_chunk.await {
+    ///         println!("final state: {}", state);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_stream_with_final(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<(AgentStream, oneshot::Receiver<RunAgentResult<Value>>)> {
+        Ok(AgentStream::new(self.run_stream(input_kwargs).await?).with_final())
+    }
+
+    /// Run the agent and tag each chunk with its sequence number and arrival time
+    ///
+    /// Sequence numbers are assigned by the client in arrival order, so gaps in
+    /// agent-side numbering and inter-chunk latency can be checked against them.
+    /// See [`SequencedChunk`](crate::SequencedChunk).
+    pub async fn run_stream_items(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<AgentStream<SequencedChunk>> {
+        Ok(AgentStream::new(self.run_stream(input_kwargs).await?).sequenced())
+    }
+
+    /// Run the agent and deserialize every chunk into `T`
+    ///
+    /// Suited to agents whose chunks share one shape; a chunk that does not match
+    /// `T` yields a validation error. To filter chunks first, wrap
+    /// [`RunAgentClient::run_stream`] in an [`AgentStream`], e.g.
+    /// `AgentStream::new(stream).filter_content().deserialize::<T>()`.
+    pub async fn run_stream_typed<T>(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<AgentStream<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        Ok(AgentStream::new(self.run_stream(input_kwargs).await?).deserialize())
+    }
+
+    /// Run the agent with streaming and collect every chunk
+    ///
+    /// Fails with the first chunk error; chunks received before it are discarded.
+    pub async fn run_stream_collect(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<Vec<Value>> {
+        self.run_stream(input_kwargs).await?.try_collect().await
+    }
+
+    /// Run the agent with streaming and concatenate the text of its chunks
+    ///
+    /// Text is taken from bare string chunks and string `content` fields (see
+    /// [`chunk_text`](crate::client::stream::chunk_text)); other chunks are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "chat_stream").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let text = client
+    ///         .run_stream_concat_text(&[("message", json!("Write a haiku"))])
+    ///         .await?;
+    ///     println!("{}", text);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_stream_concat_text(
+        &self,
+        input_kwargs: &[(&str, Value)],
+    ) -> RunAgentResult<String> {
+        let chunks = self.run_stream_collect(input_kwargs).await?;
+        Ok(chunks.iter().filter_map(chunk_text).collect())
+    }
+
+    /// POST exactly `body` to this agent's run URL and return the raw response
+    ///
+    /// Escape hatch for reproducing server bugs or trying server features the SDK
+    /// does not support yet: no request fields are added, and the response is
+    /// returned without `success` checks or payload extraction. HTTP errors are
+    /// still reported as errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "entrypoint").with_api_key("key"),
+    ///     )
+    ///     .await?;
+    ///
+    ///     let response = client
+    ///         .run_raw_body(json!({
+    ///             "entrypoint_tag": "entrypoint",
+    ///             "input_args": [],
+    ///             "input_kwargs": {"message": "Hello"},
+    ///             "experimental_flag": true
+    ///         }))
+    ///         .await?;
+    ///     println!("{}", response);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_raw_body(&self, body: Value) -> RunAgentResult<Value> {
+        self.rest_client.run_agent_raw(&self.agent_id, &body).await
+    }
+
+    /// Open a raw bidirectional stream session for this client's agent
+    ///
+    /// For interactive protocols where the client sends messages mid-stream. The
+    /// caller sends the start request itself; see [`SocketClient::open_session`]
+    /// for the message contract.
+    pub async fn open_session(&self) -> RunAgentResult<StreamSession> {
+        self.socket_client
+            .open_session(&self.agent_id, &self.entrypoint_tag)
+            .await
+    }
+
+    /// Get the agent's architecture information
+    pub async fn get_agent_architecture(&self) -> RunAgentResult<Value> {
+        self.rest_client
+            .get_agent_architecture(&self.agent_id)
+            .await
+    }
+
+    /// List the agents deployed by the authenticated account
+    ///
+    /// Lets tools offer a picker instead of a hardcoded agent ID. Every page is
+    /// fetched; see [`RestClient::list_agents`].
+    pub async fn list_remote_agents(&self) -> RunAgentResult<Vec<AgentSummary>> {
+        self.rest_client.list_agents().await
+    }
+
+    /// Check if the agent is available
+    pub async fn health_check(&self) -> RunAgentResult<bool> {
+        match self.rest_client.health_check().await {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Check the client's setup and explain what is wrong, if anything
+    ///
+    /// Runs, in order: server reachability, authentication (remote agents),
+    /// whether the agent exists and exposes this client's entrypoint, and whether
+    /// the agent is in the local registry (local agents with the `db` feature).
+    /// Checks that cannot apply, or that an earlier failure makes pointless, are
+    /// skipped. Failures carry a suggestion. The entrypoint check fetches the
+    /// architecture again rather than trusting the cached copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let config = RunAgentClientConfig::new("agent-id", "entrypoint").with_api_key("key");
+    ///     if let Err(e) = RunAgentClient::new(config.clone()).await {
+    ///         eprintln!("{}", e);
+    ///         eprint!("{}", RunAgentClient::diagnose_config(config).await?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn diagnose(&self) -> DiagnosticReport {
+        use crate::constants::DEFAULT_CONNECT_TIMEOUT_SECONDS;
+
+        let base_url = self.rest_client.base_url().to_string();
+        let mut checks = Vec::new();
+
+        let timeout = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECONDS);
+        let unreachable = match tokio::time::timeout(timeout, self.rest_client.health_check()).await
+        {
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => Some(format!("Health check against {} failed: {}", base_url, e)),
+            Err(_) => Some(format!(
+                "No response from {} within {}s",
+                base_url, DEFAULT_CONNECT_TIMEOUT_SECONDS
+            )),
+        };
+        let reachable = unreachable.is_none();
+        checks.push(match unreachable {
+            None => DiagnosticCheck::passed(
+                DiagnosticKind::Reachability,
+                format!("Server at {} is reachable", base_url),
+            ),
+            Some(message) if self.local => DiagnosticCheck::failed(
+                DiagnosticKind::Reachability,
+                message,
+                "Start the agent with `runagent serve <path>` and check the configured host and port",
+            ),
+            Some(message) => DiagnosticCheck::failed(
+                DiagnosticKind::Reachability,
+                message,
+                "Check the base URL and your network connection",
+            ),
+        });
+
+        checks.push(if self.local {
+            DiagnosticCheck::skipped(
+                DiagnosticKind::Authentication,
+                "Local agents do not need an API key",
+            )
+        } else if !reachable {
+            DiagnosticCheck::skipped(DiagnosticKind::Authentication, "Server is not reachable")
+        } else if !self.rest_client.has_credentials() {
+            DiagnosticCheck::failed(
+                DiagnosticKind::Authentication,
+                "No API key provided",
+                format!(
+                    "Set the {} environment variable or call `.with_api_key(..)` on the config",
+                    crate::constants::ENV_RUNAGENT_API_KEY
+                ),
+            )
+        } else {
+            let authenticated = self
+                .rest_client
+                .validate_api_connection()
+                .await
+                .ok()
+                .and_then(|result| result.get("api_authenticated").and_then(Value::as_bool))
+                .unwrap_or(false);
+            if authenticated {
+                DiagnosticCheck::passed(
+                    DiagnosticKind::Authentication,
+                    format!("API key accepted by {}", base_url),
+                )
+            } else {
+                DiagnosticCheck::failed(
+                    DiagnosticKind::Authentication,
+                    format!("API key was not accepted by {}", base_url),
+                    "Check that the API key is valid, not expired and meant for this server",
+                )
+            }
+        });
+
+        checks.push(if !reachable {
+            DiagnosticCheck::skipped(DiagnosticKind::Entrypoint, "Server is not reachable")
+        } else {
+            match self.get_agent_architecture_internal().await {
+                Ok(architecture) => {
+                    let tags: Vec<&str> = architecture
+                        .get("entrypoints")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|ep| ep.get("tag").and_then(Value::as_str))
+                        .collect();
+                    if tags.contains(&self.entrypoint_tag.as_str()) {
+                        DiagnosticCheck::passed(
+                            DiagnosticKind::Entrypoint,
+                            format!(
+                                "Agent {} exposes entrypoint `{}`",
+                                self.agent_id, self.entrypoint_tag
+                            ),
+                        )
+                    } else {
+                        DiagnosticCheck::failed(
+                            DiagnosticKind::Entrypoint,
+                            format!(
+                                "Entrypoint `{}` not found in agent {}",
+                                self.entrypoint_tag, self.agent_id
+                            ),
+                            format!("Available entrypoints: {}", tags.join(", ")),
+                        )
+                    }
+                }
+                Err(e) => {
+                    let suggestion = match &e {
+                        RunAgentError::Execution {
+                            suggestion: Some(suggestion),
+                            ..
+                        } => suggestion.clone(),
+                        _ => "Check that the agent ID is correct and the agent is deployed"
+                            .to_string(),
+                    };
+                    DiagnosticCheck::failed(DiagnosticKind::Entrypoint, e.to_string(), suggestion)
+                }
+            }
+        });
+
+        checks.push(self.registry_check().await);
+        DiagnosticReport { checks }
+    }
+
+    /// Diagnose a configuration that [`RunAgentClient::new`] rejects
+    ///
+    /// Builds the client without network calls, then runs
+    /// [`RunAgentClient::diagnose`]. Fails only for configuration errors, such as
+    /// a local agent without an address.
+    pub async fn diagnose_config(config: RunAgentClientConfig) -> RunAgentResult<DiagnosticReport> {
+        Ok(Self::build(config).await?.diagnose().await)
+    }
+
+    /// Whether a local agent is in the local registry
+    async fn registry_check(&self) -> DiagnosticCheck {
+        if !self.local {
+            return DiagnosticCheck::skipped(
+                DiagnosticKind::Registry,
+                "Remote agents are not in the local registry",
+            );
+        }
+
+        #[cfg(feature = "db")]
+        {
+            let agent = match DatabaseService::new(None).await {
+                Ok(db_service) => db_service.get_agent(&self.agent_id).await,
+                Err(e) => Err(e),
+            };
+            match agent {
+                Ok(Some(agent)) => DiagnosticCheck::passed(
+                    DiagnosticKind::Registry,
+                    format!(
+                        "Agent {} is registered at {}:{}",
+                        self.agent_id, agent.host, agent.port
+                    ),
+                ),
+                Ok(None) => DiagnosticCheck::failed(
+                    DiagnosticKind::Registry,
+                    format!("Agent {} is not in the local registry", self.agent_id),
+                    "Deploy it with `runagent serve <path>`, or pass its address with `.with_address(host, port)`",
+                ),
+                Err(e) => DiagnosticCheck::failed(
+                    DiagnosticKind::Registry,
+                    format!("Cannot read the local registry: {}", e),
+                    "Check that the RunAgent cache directory (~/.runagent or RUNAGENT_CACHE_DIR) is readable",
+                ),
+            }
+        }
+        #[cfg(not(feature = "db"))]
+        {
+            DiagnosticCheck::skipped(DiagnosticKind::Registry, "Built without the `db` feature")
+        }
+    }
+
+    /// Poll [`RunAgentClient::health_check`] until the agent responds
+    ///
+    /// Checks immediately, then every `poll_interval` until a check succeeds. Fails
+    /// with [`RunAgentError::Timeout`] once `timeout` has elapsed; a check still in
+    /// flight at the deadline is abandoned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use runagent::{RunAgentClient, RunAgentClientConfig};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> runagent::RunAgentResult<()> {
+    ///     let client = RunAgentClient::new(
+    ///         RunAgentClientConfig::new("agent-id", "generic")
+    ///             .with_local(true)
+    ///             .with_address("127.0.0.1", 8450),
+    ///     )
+    ///     .await?;
+    ///
+    ///     client
+    ///         .wait_until_ready(Duration::from_secs(30), Duration::from_millis(500))
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wait_until_ready(
+        &self,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> RunAgentResult<()> {
+        poll_until_ready(&SystemClock, timeout, poll_interval, || async {
+            self.health_check().await.unwrap_or(false)
+        })
+        .await
+    }
+
+    /// Get agent information
     pub fn agent_id(&self) -> &str {
         &self.agent_id
     }
@@ -583,6 +2608,57 @@ impl RunAgentClient {
         &self.entrypoint_tag
     }
 
+    /// Entrypoints listed in the cached agent architecture
+    ///
+    /// Empty until the architecture has been fetched, which [`RunAgentClient::new`]
+    /// does. Fields the architecture leaves out are empty strings.
+    pub fn entrypoints(&self) -> Vec<EntryPoint> {
+        let Some(entrypoints) = self
+            .agent_architecture
+            .as_ref()
+            .and_then(|architecture| architecture.get("entrypoints"))
+            .and_then(Value::as_array)
+        else {
+            return Vec::new();
+        };
+        let field = |ep: &Value, key: &str| {
+            ep.get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        entrypoints
+            .iter()
+            .filter(|ep| ep.get("tag").and_then(Value::as_str).is_some())
+            .map(|ep| EntryPoint {
+                file: field(ep, "file"),
+                module: field(ep, "module"),
+                tag: field(ep, "tag"),
+            })
+            .collect()
+    }
+
+    /// Whether the cached agent architecture lists the entrypoint `tag`
+    pub fn has_entrypoint(&self, tag: &str) -> bool {
+        self.lists_entrypoint(tag).unwrap_or(false)
+    }
+
+    /// Entrypoints that stream, by the `_stream` tag suffix convention
+    pub fn streaming_entrypoints(&self) -> Vec<EntryPoint> {
+        self.entrypoints()
+            .into_iter()
+            .filter(|ep| ep.tag.ends_with("_stream"))
+            .collect()
+    }
+
+    /// Entrypoints that return a single response, i.e. without the `_stream` suffix
+    pub fn non_streaming_entrypoints(&self) -> Vec<EntryPoint> {
+        self.entrypoints()
+            .into_iter()
+            .filter(|ep| !ep.tag.ends_with("_stream"))
+            .collect()
+    }
+
     /// Get any extra params supplied during initialization
     pub fn extra_params(&self) -> Option<&HashMap<String, Value>> {
         self.extra_params.as_ref()
@@ -598,6 +2674,11 @@ impl RunAgentClient {
         self.persistent_memory
     }
 
+    /// Get the regional base URL this client switched to, if the server announced one
+    pub fn region_base_url(&self) -> Option<&str> {
+        self.region_base_url.as_deref()
+    }
+
     /// Check if using local deployment
     pub fn is_local(&self) -> bool {
         self.local
@@ -605,12 +2686,57 @@ impl RunAgentClient {
 }
 
 impl RunAgentClient {
+    /// Transports for a local agent server listening on a Unix domain socket
+    #[cfg(all(unix, feature = "unix-socket"))]
+    fn create_unix_clients(
+        path: PathBuf,
+        api_prefix: &str,
+    ) -> RunAgentResult<(RestClient, SocketClient)> {
+        tracing::info!("🔌 Using Unix socket: {}", path.display());
+        let rest_client =
+            RestClient::new("http://localhost", None, Some(api_prefix))?.with_unix_socket(&path);
+        let socket_client =
+            SocketClient::new("ws://localhost", None, Some(api_prefix))?.with_unix_socket(path);
+        Ok((rest_client, socket_client))
+    }
+
+    #[cfg(not(all(unix, feature = "unix-socket")))]
+    fn create_unix_clients(
+        path: PathBuf,
+        _api_prefix: &str,
+    ) -> RunAgentResult<(RestClient, SocketClient)> {
+        Err(RunAgentError::config(format!(
+            "Unix socket {} requested, but Unix domain sockets need the `unix-socket` feature of the runagent crate on a Unix platform",
+            path.display()
+        )))
+    }
+
     fn create_remote_clients(
         base_url_override: Option<&str>,
         api_key_override: Option<String>,
+        http_client: Option<reqwest::Client>,
+        api_prefix: &str,
+        tls: Option<&TlsConfig>,
     ) -> RunAgentResult<(RestClient, SocketClient)> {
+        let http_client = match (http_client, tls) {
+            (Some(_), Some(_)) => {
+                return Err(RunAgentError::config(
+                    "TLS settings cannot be applied to a shared HTTP client; configure the client certificate on it instead",
+                ))
+            }
+            (None, Some(tls)) => Some(tls.http_client()?),
+            (http_client, None) => http_client,
+        };
         if let Some(base_url) = base_url_override {
-            let rest_client = RestClient::new(base_url, api_key_override.clone(), Some("/api/v1"))?;
+            let rest_client = match http_client {
+                Some(http_client) => RestClient::with_http_client(
+                    http_client,
+                    base_url,
+                    api_key_override.clone(),
+                    Some(api_prefix),
+                ),
+                None => RestClient::new(base_url, api_key_override.clone(), Some(api_prefix))?,
+            };
             let socket_base = if base_url.starts_with("https://") {
                 base_url.replace("https://", "wss://")
             } else if base_url.starts_with("http://") {
@@ -618,7 +2744,12 @@ impl RunAgentClient {
             } else {
                 format!("wss://{}", base_url)
             };
-            let socket_client = SocketClient::new(&socket_base, api_key_override, Some("/api/v1"))?;
+            let socket_client =
+                SocketClient::new(&socket_base, api_key_override, Some(api_prefix))?;
+            let socket_client = match tls {
+                Some(tls) => socket_client.with_tls(tls)?,
+                None => socket_client,
+            };
             Ok((rest_client, socket_client))
         } else {
             let rest_client = RestClient::default()?;
@@ -627,3 +2758,1125 @@ impl RunAgentClient {
         }
     }
 }
+
+/// API prefix with one leading slash and no trailing one; `/api/v1` when unset
+fn normalize_api_prefix(prefix: Option<&str>) -> String {
+    match prefix.map(|prefix| prefix.trim_matches('/')) {
+        None => crate::constants::DEFAULT_API_PREFIX.to_string(),
+        Some("") => String::new(),
+        Some(prefix) => format!("/{}", prefix),
+    }
+}
+
+/// Run `check` every `poll_interval` until it passes or `timeout` elapses
+async fn poll_until_ready<F, Fut>(
+    clock: &dyn Clock,
+    timeout: Duration,
+    poll_interval: Duration,
+    mut check: F,
+) -> RunAgentResult<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let start = clock.now();
+    let deadline = start + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(clock.now());
+        if let Ok(true) = tokio::time::timeout(remaining, check()).await {
+            return Ok(());
+        }
+
+        let now = clock.now();
+        if now >= deadline {
+            return Err(RunAgentError::timeout(now - start));
+        }
+        clock.sleep(poll_interval.min(deadline - now)).await;
+    }
+}
+
+/// Keep `span` open for the life of the stream, recording how many chunks it yielded
+fn traced_stream(
+    stream: Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>,
+    span: tracing::Span,
+) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>> {
+    let mut chunks = 0u64;
+    Box::pin(stream.inspect(move |item| {
+        metrics::record_stream_chunk(item);
+        chunks += 1;
+        span.record("chunks", chunks);
+    }))
+}
+
+/// Error for a local client whose host and port could not be resolved
+///
+/// Without the `db` feature there is no registry to look the address up in, so
+/// the error says how to either pass the address or enable the feature.
+fn missing_local_address(agent_id: &str, enable_registry: bool) -> RunAgentError {
+    let with_address = format!(
+        "RunAgentClientConfig::new(\"{}\", entrypoint).with_local(true).with_address(\"127.0.0.1\", 8450)",
+        agent_id
+    );
+    if !cfg!(feature = "db") {
+        return RunAgentError::config(format!(
+            "No host/port for local agent {}, and the local agent registry is unavailable because \
+             the runagent crate was built without the `db` feature. Either pass the address with \
+             `{}` or enable the feature: runagent = {{ version = \"0.1\", features = [\"db\"] }}",
+            agent_id, with_address
+        ));
+    }
+    let registry = if enable_registry {
+        format!("agent {} is not in the local agent registry", agent_id)
+    } else {
+        "registry lookup is disabled (enable it with `.with_enable_registry(true)`)".to_string()
+    };
+    RunAgentError::validation(format!(
+        "Host and port are required for local clients, but {}. Pass the address with `{}`",
+        registry, with_address
+    ))
+}
+
+/// Find the single local agent matching a deployment directory name and/or framework
+///
+/// Agents registered without a framework are matched by the one
+/// [`detect_framework`] finds in their directory.
+#[cfg(feature = "db")]
+async fn resolve_agent_id(
+    db_service: &DatabaseService,
+    name: Option<&str>,
+    framework: Option<&str>,
+) -> RunAgentResult<String> {
+    let matches: Vec<String> = db_service
+        .list_agents()
+        .await?
+        .into_iter()
+        .filter(|agent| {
+            name.is_none_or(|name| {
+                std::path::Path::new(&agent.agent_path)
+                    .file_name()
+                    .is_some_and(|dir| dir == name)
+            })
+        })
+        .filter(|agent| {
+            framework.is_none_or(|framework| {
+                let detected = agent
+                    .framework
+                    .clone()
+                    .or_else(|| detect_framework(&agent.agent_path));
+                detected.as_deref() == Some(framework)
+            })
+        })
+        .map(|agent| agent.agent_id)
+        .collect();
+
+    let criteria = [("name", name), ("framework", framework)]
+        .iter()
+        .filter_map(|(field, value)| value.map(|value| format!("{} `{}`", field, value)))
+        .collect::<Vec<_>>()
+        .join(" and ");
+    match matches.as_slice() {
+        [agent_id] => {
+            tracing::info!("🔍 Resolved agent {} by {}", agent_id, criteria);
+            Ok(agent_id.clone())
+        }
+        [] => Err(RunAgentError::validation(format!(
+            "No local agent found with {}",
+            criteria
+        ))),
+        _ => Err(RunAgentError::validation(format!(
+            "{} local agents found with {} ({}); set agent_id to choose one",
+            matches.len(),
+            criteria,
+            matches.join(", ")
+        ))),
+    }
+}
+
+/// End `stream` with a timeout error when no item follows the previous one within `timeout`
+///
+/// Dropping the inner stream closes its connection.
+fn idle_timeout_stream(
+    mut stream: Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>,
+    timeout: Duration,
+) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>> {
+    Box::pin(async_stream::stream! {
+        loop {
+            match tokio::time::timeout(timeout, stream.next()).await {
+                Ok(Some(item)) => yield item,
+                Ok(None) => break,
+                Err(_) => {
+                    yield Err(RunAgentError::timeout(timeout));
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Whether `text` contains a Python generator repr such as
+/// `<generator object agent_stream at 0x7f3a...>`
+///
+/// Only the full repr shape counts, so prose mentioning generators does not.
+fn is_generator_repr(text: &str) -> bool {
+    ["<generator object ", "<async_generator object "]
+        .iter()
+        .any(|prefix| {
+            text.match_indices(prefix).any(|(start, _)| {
+                text[start + prefix.len()..]
+                    .split_once(" at 0x")
+                    .is_some_and(|(name, address)| {
+                        !name.is_empty()
+                            && !name.contains(char::is_whitespace)
+                            && address.starts_with(|c: char| c.is_ascii_hexdigit())
+                    })
+            })
+        })
+}
+
+/// Run client setup under the config's connect timeout, if any
+pub(crate) async fn with_connect_deadline<T>(
+    connect_timeout: Option<Duration>,
+    setup: impl Future<Output = RunAgentResult<T>>,
+) -> RunAgentResult<T> {
+    match connect_timeout {
+        Some(connect_timeout) => tokio::time::timeout(connect_timeout, setup)
+            .await
+            .unwrap_or_else(|_| Err(RunAgentError::timeout(connect_timeout))),
+        None => setup.await,
+    }
+}
+
+/// Resolve a JSON pointer against a run response, returning `None` when unset or absent
+fn payload_at_path<'a>(response: &'a Value, payload_path: Option<&str>) -> Option<&'a Value> {
+    payload_path.and_then(|path| response.pointer(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::diagnostics::DiagnosticStatus;
+    use crate::types::ErrorCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_custom_payload_path() {
+        let response = json!({
+            "success": true,
+            "result": {
+                "output": {"type": "string", "payload": "\"Hello from custom server\""}
+            }
+        });
+
+        let custom = payload_at_path(&response, Some("/result/output")).unwrap();
+        let serializer = CoreSerializer::new(10.0).unwrap();
+        let prepared = serializer.prepare_for_deserialization(custom.clone());
+        let value = serializer.deserialize_object(prepared).unwrap();
+        assert_eq!(value, json!("Hello from custom server"));
+    }
+
+    #[test]
+    fn test_custom_payload_path_falls_back_when_missing() {
+        let response = json!({"success": true, "data": {"answer": 42}});
+        assert!(payload_at_path(&response, Some("/result/output")).is_none());
+        assert!(payload_at_path(&response, None).is_none());
+    }
+
+    #[test]
+    fn test_generator_repr_detection() {
+        assert!(is_generator_repr(
+            "<generator object agent_stream at 0x7f3a2c1b9e40>"
+        ));
+        assert!(is_generator_repr(
+            "result: <async_generator object Agent.run.<locals>.gen at 0x10a>"
+        ));
+        // Prose about generators is agent output, not a misused entrypoint
+        assert!(!is_generator_repr(
+            "The <generator object> in Python yields values lazily at 0x speed"
+        ));
+        assert!(!is_generator_repr("a generator object is created"));
+    }
+
+    #[tokio::test]
+    async fn test_api_prefix_flows_into_both_transports() {
+        use std::sync::Mutex;
+
+        /// Records request paths, answering every request with `{}`
+        struct Paths(Mutex<Vec<String>>);
+
+        #[async_trait::async_trait]
+        impl Transport for Paths {
+            async fn execute(
+                &self,
+                request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(request.url().path().to_string());
+                Ok(http::Response::new("{}").into())
+            }
+        }
+
+        let paths = Arc::new(Paths(Mutex::new(Vec::new())));
+        let config = RunAgentClientConfig::new("agent-id", "entrypoint")
+            .with_api_key("key")
+            .with_base_url("https://proxy.local")
+            .with_api_prefix("runagent/api/v2/")
+            .with_transport(paths.clone());
+        let client = RunAgentClient::build(config).await.unwrap();
+
+        assert!(client.health_check().await.unwrap());
+        assert_eq!(*paths.0.lock().unwrap(), vec!["/runagent/api/v2/health"]);
+        assert_eq!(client.socket_client.api_prefix(), "/runagent/api/v2");
+
+        assert_eq!(normalize_api_prefix(None), "/api/v1");
+        assert_eq!(normalize_api_prefix(Some("/")), "");
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)] // tungstenite's handshake callback signature
+    async fn test_user_agent_suffix_sent_by_both_transports() {
+        use futures::SinkExt;
+        use std::sync::Mutex;
+        use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request};
+        use tokio_tungstenite::tungstenite::Message;
+
+        /// Records the `User-Agent` of REST requests, answering with `{}`
+        struct UserAgents(Mutex<Vec<String>>);
+
+        #[async_trait::async_trait]
+        impl Transport for UserAgents {
+            async fn execute(
+                &self,
+                request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                let user_agent = &request.headers()[reqwest::header::USER_AGENT];
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(user_agent.to_str().unwrap().to_string());
+                Ok(http::Response::new("{}").into())
+            }
+        }
+
+        // Completes one stream, returning its handshake's `User-Agent`
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut user_agent = String::new();
+            let mut ws = tokio_tungstenite::accept_hdr_async(tcp, |request: &Request, response| {
+                user_agent = request.headers()["user-agent"]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                Ok::<_, ErrorResponse>(response)
+            })
+            .await
+            .unwrap();
+            ws.next().await; // start request
+            let done = json!({"type": "status", "status": "stream_completed"});
+            ws.send(Message::Text(done.to_string())).await.unwrap();
+            user_agent
+        });
+
+        let user_agents = Arc::new(UserAgents(Mutex::new(Vec::new())));
+        let config = RunAgentClientConfig::new("agent-id", "chat_stream")
+            .with_api_key("key")
+            .with_base_url(format!("http://{}", addr))
+            .with_transport(user_agents.clone())
+            .with_user_agent_suffix("lead-agent/1.0");
+        let client = RunAgentClient::build(config).await.unwrap();
+
+        let expected = format!("RunAgent-Rust-SDK/{} lead-agent/1.0", crate::VERSION);
+        assert!(client.health_check().await.unwrap());
+        assert_eq!(*user_agents.0.lock().unwrap(), vec![expected.clone()]);
+        let chunks: Vec<_> = client.run_stream(&[]).await.unwrap().collect().await;
+        assert!(chunks.is_empty());
+        assert_eq!(server.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_attachments_sent_per_encoding() {
+        use std::sync::Mutex;
+
+        /// Records content types and bodies, answering every run with `"ok"`
+        struct Bodies(Mutex<Vec<(String, Vec<u8>)>>);
+
+        #[async_trait::async_trait]
+        impl Transport for Bodies {
+            async fn execute(
+                &self,
+                request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                let content_type = request.headers()[reqwest::header::CONTENT_TYPE]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                let body = request.body().unwrap().as_bytes().unwrap().to_vec();
+                self.0.lock().unwrap().push((content_type, body));
+                Ok(http::Response::new(r#"{"success": true, "data": "ok"}"#).into())
+            }
+        }
+
+        let bodies = Arc::new(Bodies(Mutex::new(Vec::new())));
+        let config = RunAgentClientConfig::new("agent-id", "summarize")
+            .with_api_key("key")
+            .with_base_url("https://agents.local")
+            .with_transport(bodies.clone());
+        let client = RunAgentClient::build(config.clone()).await.unwrap();
+        let pdf = Attachment::new("report.pdf", "application/pdf", b"%PDF-1.7".to_vec());
+        let kwargs = [("question", json!("Key findings?"))];
+
+        let result = client
+            .run_with_attachments(&kwargs, std::slice::from_ref(&pdf))
+            .await
+            .unwrap();
+        assert_eq!(result, "ok");
+        let clash = client
+            .run_with_attachments(&[("attachments", json!([]))], std::slice::from_ref(&pdf))
+            .await
+            .unwrap_err();
+        assert_eq!(clash.category(), "validation");
+
+        let config = config.with_attachment_encoding(AttachmentEncoding::Multipart);
+        let client = RunAgentClient::build(config).await.unwrap();
+        client.run_with_attachments(&kwargs, &[pdf]).await.unwrap();
+
+        let bodies = bodies.0.lock().unwrap();
+        assert_eq!(bodies.len(), 2);
+        let (content_type, body) = &bodies[0];
+        assert_eq!(content_type, "application/json");
+        let request: Value = serde_json::from_slice(body).unwrap();
+        let sent = &request["input_kwargs"]["attachments"][0];
+        assert_eq!(sent["name"], "report.pdf");
+        assert_eq!(sent["data"], "JVBERi0xLjc=");
+
+        let (content_type, body) = &bodies[1];
+        assert!(content_type.starts_with("multipart/form-data; boundary="));
+        let body = String::from_utf8_lossy(body);
+        assert!(body.contains("\"question\":\"Key findings?\""));
+        assert!(body
+            .contains("filename=\"report.pdf\"\r\nContent-Type: application/pdf\r\n\r\n%PDF-1.7"));
+    }
+
+    #[tokio::test]
+    async fn test_require_api_key_fails_fast() {
+        // An empty key overrides RUNAGENT_API_KEY and the config file
+        let config = RunAgentClientConfig::new("agent-id", "entrypoint")
+            .with_base_url("http://localhost:8333")
+            .with_api_key("")
+            .with_require_api_key(true);
+        let err = RunAgentClient::build(config).await.err().unwrap();
+        assert_eq!(err.category(), "config");
+
+        let config = RunAgentClientConfig::new("agent-id", "entrypoint")
+            .with_base_url("http://localhost:8333")
+            .with_api_key("");
+        let client = RunAgentClient::build(config).await.unwrap();
+        assert!(!client.rest_client.has_credentials());
+    }
+
+    /// Serve run requests over HTTP, answering each with `respond(body)` after its delay
+    async fn mock_run_server<F>(respond: F) -> String
+    where
+        F: Fn(&Value) -> (Duration, Value) + Send + Sync + 'static,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let respond = std::sync::Arc::new(respond);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let respond = respond.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let body = loop {
+                        let read = socket.read(&mut buf).await.unwrap();
+                        if read == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..read]);
+                        let text = String::from_utf8_lossy(&request).to_string();
+                        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                            let length = head
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_lowercase()
+                                        .strip_prefix("content-length: ")
+                                        .map(|v| v.trim().to_string())
+                                })
+                                .and_then(|v| v.parse::<usize>().ok())
+                                .unwrap_or(0);
+                            if body.len() >= length {
+                                break body.to_string();
+                            }
+                        }
+                    };
+
+                    let request: Value = serde_json::from_str(&body).unwrap();
+                    let (delay, response) = respond(&request);
+                    tokio::time::sleep(delay).await;
+                    let response = response.to_string();
+                    let reply = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    );
+                    socket.write_all(reply.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_preserves_order_and_isolates_failures() {
+        // Answers each run with its `n` input, slower for smaller `n`; fails n == 2
+        let base_url = mock_run_server(|request| {
+            let n = request["input_kwargs"]["n"].as_u64().unwrap();
+            let response = if n == 2 {
+                json!({"success": false, "error": "boom"})
+            } else {
+                json!({"success": true, "data": {"n": n}})
+            };
+            (Duration::from_millis((5 - n) * 20), response)
+        })
+        .await;
+
+        let config = RunAgentClientConfig::new("agent-id", "score")
+            .with_api_key("key")
+            .with_base_url(base_url);
+        let client = RunAgentClient::build(config).await.unwrap();
+
+        let inputs = (0..5).map(|n| vec![("n", json!(n))]).collect();
+        let results = client.run_batch(inputs, 3).await;
+
+        assert_eq!(results.len(), 5);
+        for (n, result) in results.into_iter().enumerate() {
+            if n == 2 {
+                assert_eq!(result.unwrap_err().category(), "server");
+            } else {
+                assert_eq!(result.unwrap()["n"], json!(n));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_kwargs_merge_under_call_kwargs() {
+        let base_url = mock_run_server(|request| {
+            let response = json!({"success": true, "data": request["input_kwargs"]});
+            (Duration::ZERO, response)
+        })
+        .await;
+
+        let defaults = HashMap::from([
+            ("model".to_string(), json!("gpt-4o-mini")),
+            ("temperature".to_string(), json!(0.2)),
+        ]);
+        let config = RunAgentClientConfig::new("agent-id", "generic")
+            .with_api_key("key")
+            .with_base_url(base_url)
+            .with_defaults(defaults);
+        let client = RunAgentClient::build(config).await.unwrap();
+
+        let sent = client
+            .run(&[("message", json!("hi")), ("temperature", json!(0.9))])
+            .await
+            .unwrap();
+        assert_eq!(
+            sent,
+            json!({"model": "gpt-4o-mini", "temperature": 0.9, "message": "hi"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_outcome_distinguishes_null_from_missing() {
+        // Answers with the response shape named by the `shape` input
+        let base_url = mock_run_server(|request| {
+            let response = match request["input_kwargs"]["shape"].as_str().unwrap() {
+                "value" => {
+                    json!({"success": true, "data": {"type": "string", "payload": "\"hi\""}})
+                }
+                "none" => json!({"success": true, "data": {"type": "null", "payload": "null"}}),
+                "null_data" => json!({"success": true, "data": null}),
+                _ => json!({"success": true}),
+            };
+            (Duration::ZERO, response)
+        })
+        .await;
+
+        let config = RunAgentClientConfig::new("agent-id", "generic")
+            .with_api_key("key")
+            .with_base_url(base_url);
+        let client = RunAgentClient::build(config).await.unwrap();
+
+        let outcome = |shape: &'static str| {
+            let client = client.clone();
+            async move {
+                client
+                    .run_outcome(&[("shape", json!(shape))])
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(outcome("value").await, RunOutcome::Payload(json!("hi")));
+        assert_eq!(outcome("none").await, RunOutcome::EmptyNull);
+        assert_eq!(outcome("null_data").await, RunOutcome::EmptyNull);
+        assert_eq!(outcome("missing").await, RunOutcome::NoPayload);
+        // `run` keeps returning null for both
+        assert_eq!(
+            client.run(&[("shape", json!("missing"))]).await.unwrap(),
+            Value::Null
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_idempotent_shares_in_flight_runs() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let base_url = mock_run_server(move |request| {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            let data = json!({"n": n, "key": request["idempotency_key"]});
+            (
+                Duration::from_millis(50),
+                json!({"success": true, "data": data}),
+            )
+        })
+        .await;
+
+        let config = RunAgentClientConfig::new("agent-id", "report")
+            .with_api_key("key")
+            .with_base_url(base_url);
+        let client = RunAgentClient::build(config).await.unwrap();
+        let other = client.clone();
+
+        let kwargs = [("date", json!("today"))];
+        let (first, second) = tokio::join!(
+            client.run_idempotent("k1", &kwargs),
+            other.run_idempotent("k1", &kwargs),
+        );
+        assert_eq!(first.unwrap(), json!({"n": 0, "key": "k1"}));
+        assert_eq!(second.unwrap(), json!({"n": 0, "key": "k1"}));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // The key is released once the run finishes
+        let again = client.run_idempotent("k1", &[]).await.unwrap();
+        assert_eq!(again["n"], 1);
+        assert!(client.lock_in_flight().is_empty());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_run_metrics() {
+        use crate::utils::metrics::{ERRORS_TOTAL, RUNS_TOTAL, RUN_DURATION_SECONDS};
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        // The recorder is thread-local, so keep everything on one thread
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        ::metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let base_url = mock_run_server(|request| {
+                    let response = if request["input_kwargs"]["fail"] == json!(true) {
+                        json!({"success": false, "error": "boom"})
+                    } else {
+                        json!({"success": true, "data": "ok"})
+                    };
+                    (Duration::ZERO, response)
+                })
+                .await;
+                let config = RunAgentClientConfig::new("agent-id", "chat")
+                    .with_api_key("key")
+                    .with_base_url(base_url);
+                let client = RunAgentClient::build(config).await.unwrap();
+
+                client.run(&[]).await.unwrap();
+                client.run(&[("fail", json!(true))]).await.unwrap_err();
+            })
+        });
+
+        let values: HashMap<String, DebugValue> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| (key.key().name().to_string(), value))
+            .collect();
+        assert_eq!(values[RUNS_TOTAL], DebugValue::Counter(2));
+        assert_eq!(values[ERRORS_TOTAL], DebugValue::Counter(1));
+        assert!(matches!(&values[RUN_DURATION_SECONDS], DebugValue::Histogram(h) if h.len() == 2));
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_ready() {
+        use crate::utils::clock::TestClock;
+
+        let clock = TestClock::new();
+        let mut checks = 0;
+        let result = poll_until_ready(
+            &clock,
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+            || {
+                checks += 1;
+                std::future::ready(checks == 3)
+            },
+        )
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(2); 2]);
+
+        // The last sleep is cut short at the deadline
+        let clock = TestClock::new();
+        let err = poll_until_ready(
+            &clock,
+            Duration::from_secs(5),
+            Duration::from_secs(2),
+            || std::future::ready(false),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, RunAgentError::Timeout { elapsed } if elapsed == Duration::from_secs(5))
+        );
+        assert_eq!(
+            clock.sleeps(),
+            vec![
+                Duration::from_secs(2),
+                Duration::from_secs(2),
+                Duration::from_secs(1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_fallback_entrypoint() {
+        let config = RunAgentClientConfig::new("agent-id", "chat_stream")
+            .with_api_key("key")
+            .with_base_url("http://localhost:8333");
+        let mut client = RunAgentClient::build(config).await.unwrap();
+
+        client.agent_architecture = Some(json!({"entrypoints": [{"tag": "chat_stream"}]}));
+        assert_eq!(client.fallback_entrypoint(), None);
+
+        client.agent_architecture =
+            Some(json!({"entrypoints": [{"tag": "chat_stream"}, {"tag": "chat"}]}));
+        assert_eq!(client.fallback_entrypoint(), Some("chat"));
+    }
+
+    #[tokio::test]
+    async fn test_chunk_timeout_ends_stalled_stream() {
+        use futures::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Sends one chunk, then stalls longer than the chunk timeout
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.next().await; // start request
+            let chunk = json!({"type": "data", "content": "first"});
+            ws.send(Message::Text(chunk.to_string())).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            let chunk = json!({"type": "data", "content": "too late"});
+            let _ = ws.send(Message::Text(chunk.to_string())).await;
+        });
+
+        let config = RunAgentClientConfig::new("agent-id", "chat_stream")
+            .with_api_key("key")
+            .with_base_url(format!("http://{}", addr))
+            .with_chunk_timeout(Duration::from_millis(100));
+        let client = RunAgentClient::build(config).await.unwrap();
+        let mut stream = client
+            .run_stream(&[("message", json!("hi"))])
+            .await
+            .unwrap();
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "first");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(
+            matches!(err, RunAgentError::Timeout { elapsed } if elapsed == Duration::from_millis(100))
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lenient_stream_reports_errors_in_band() {
+        use futures::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // A recoverable error between two chunks, then a dropped connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.next().await; // start request
+            for frame in [
+                json!({"type": "data", "content": "first"}),
+                json!({"type": "error", "error": "tool call failed"}),
+                json!({"type": "data", "content": "second"}),
+            ] {
+                ws.send(Message::Text(frame.to_string())).await.unwrap();
+            }
+        });
+
+        let config = RunAgentClientConfig::new("agent-id", "review_stream")
+            .with_api_key("key")
+            .with_base_url(format!("http://{}", addr));
+        let client = RunAgentClient::build(config).await.unwrap();
+        let stream = client.run_stream_lenient(&[]).await.unwrap();
+        let items: Vec<Value> = stream.map(|item| item.unwrap()).collect().await;
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0], "first");
+        assert_eq!(items[1]["type"], "error");
+        assert_eq!(items[1]["category"], "server");
+        assert!(items[1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("tool call failed"));
+        assert_eq!(items[1]["recoverable"], true);
+        assert_eq!(items[2], "second");
+        assert_eq!(items[3]["category"], "connection");
+        assert_eq!(items[3]["recoverable"], false);
+    }
+
+    #[tokio::test]
+    async fn test_with_entrypoint_reuses_architecture() {
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_api_key("key")
+            .with_base_url("http://localhost:8333");
+        let mut client = RunAgentClient::build(config).await.unwrap();
+        client.agent_architecture =
+            Some(json!({"entrypoints": [{"tag": "chat"}, {"tag": "chat_stream"}]}));
+
+        let stream_client = client.with_entrypoint("chat_stream").unwrap();
+        assert_eq!(stream_client.entrypoint_tag(), "chat_stream");
+        assert_eq!(stream_client.agent_architecture, client.agent_architecture);
+        assert_eq!(client.entrypoint_tag(), "chat");
+
+        let err = client.with_entrypoint("missing").err().unwrap();
+        assert_eq!(err.error_code(), Some(ErrorCode::InvalidEntrypoint));
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_bounds_architecture_fetch() {
+        /// Answers health checks at once but stalls architecture requests
+        struct SlowArchitecture;
+
+        #[async_trait::async_trait]
+        impl Transport for SlowArchitecture {
+            async fn execute(
+                &self,
+                request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                if request.url().path().ends_with("/architecture") {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+                Ok(http::Response::new("{}").into())
+            }
+        }
+
+        let config = RunAgentClientConfig::remote("agent-id", "chat", "key")
+            .with_transport(Arc::new(SlowArchitecture))
+            .with_connect_timeout(Duration::from_millis(100));
+        let started = Instant::now();
+        let err = RunAgentClient::new(config).await.err().unwrap();
+
+        assert!(
+            matches!(err, RunAgentError::Timeout { elapsed } if elapsed == Duration::from_millis(100))
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_region_hints_switch_only_to_trusted_hosts() {
+        /// Announces its region, if any, in health responses
+        struct Announces(Option<&'static str>);
+
+        #[async_trait::async_trait]
+        impl Transport for Announces {
+            async fn execute(
+                &self,
+                request: reqwest::Request,
+            ) -> RunAgentResult<reqwest::Response> {
+                let body = if request.url().path().ends_with("/health") {
+                    json!({"status": "ok", "region_url": self.0})
+                } else {
+                    json!({"success": true, "data": {"entrypoints": [{"tag": "chat"}]}})
+                };
+                Ok(http::Response::new(body.to_string()).into())
+            }
+        }
+
+        let config = |region: Option<&'static str>| {
+            RunAgentClientConfig::remote("agent-id", "chat", "key")
+                .with_base_url("https://backend.run-agent.ai")
+                .with_transport(Arc::new(Announces(region)))
+        };
+        let connect = |region: Option<&'static str>| {
+            let config = config(region).with_region_discovery(true);
+            async move { RunAgentClient::new(config).await.unwrap() }
+        };
+
+        let client = connect(Some("https://eu.backend.run-agent.ai")).await;
+        assert_eq!(
+            client.region_base_url(),
+            Some("https://eu.backend.run-agent.ai")
+        );
+        assert_eq!(
+            client.rest_client.base_url(),
+            "https://eu.backend.run-agent.ai"
+        );
+
+        // Region discovery is opt-in
+        let client = RunAgentClient::new(config(Some("https://eu.backend.run-agent.ai")))
+            .await
+            .unwrap();
+        assert_eq!(client.region_base_url(), None);
+
+        for untrusted in [
+            "https://attacker.example",
+            "https://eu.run-agent.ai",
+            "http://eu.backend.run-agent.ai",
+        ] {
+            let client = connect(Some(untrusted)).await;
+            assert_eq!(client.region_base_url(), None);
+            assert_eq!(
+                client.rest_client.base_url(),
+                "https://backend.run-agent.ai"
+            );
+        }
+
+        // Transport responses carry a placeholder URL, never a redirect target
+        let client = connect(None).await;
+        assert_eq!(client.region_base_url(), None);
+    }
+
+    #[tokio::test]
+    async fn test_typed_configs_skip_registry() {
+        let config = RunAgentClientConfig::local("agent-id", "chat", "127.0.0.1", 8451);
+        assert_eq!(config.local, Some(true));
+        assert_eq!(config.enable_registry, Some(false));
+        let client = RunAgentClient::build(config).await.unwrap();
+        assert!(client.is_local());
+        assert_eq!(client.rest_client.base_url(), "http://127.0.0.1:8451");
+
+        let config = RunAgentClientConfig::remote("agent-id", "chat", "key");
+        assert_eq!(config.local, Some(false));
+        assert_eq!(config.api_key.as_deref(), Some("key"));
+        assert_eq!(config.enable_registry, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_local_client_without_address() {
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_local(true)
+            .with_enable_registry(false);
+        let err = RunAgentClient::build(config).await.err().unwrap();
+        assert!(err
+            .to_string()
+            .contains(".with_address(\"127.0.0.1\", 8450)"));
+
+        let err = missing_local_address("agent-id", true);
+        if cfg!(feature = "db") {
+            assert_eq!(err.category(), "validation");
+            assert!(err.to_string().contains("not in the local agent registry"));
+        } else {
+            assert_eq!(err.category(), "config");
+            assert!(err.to_string().contains("features = [\"db\"]"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_reports_each_check() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Healthy server whose agent only has a `chat` entrypoint and rejects keys
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let read = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..read]).to_string();
+                let (status, body) = if request.contains("/architecture") {
+                    (
+                        "200 OK",
+                        json!({"success": true, "data": {"entrypoints": [{"tag": "chat"}]}}),
+                    )
+                } else if request.contains("/limits/agents") {
+                    ("401 Unauthorized", json!({"detail": "invalid key"}))
+                } else {
+                    ("200 OK", json!({"status": "ok"}))
+                };
+                let body = body.to_string();
+                let reply = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        let config = RunAgentClientConfig::new("agent-id", "summarize")
+            .with_api_key("bad-key")
+            .with_base_url(format!("http://{}", addr));
+        let report = RunAgentClient::diagnose_config(config).await.unwrap();
+        assert_eq!(
+            status_of(&report, DiagnosticKind::Reachability),
+            DiagnosticStatus::Passed
+        );
+        assert_eq!(
+            status_of(&report, DiagnosticKind::Authentication),
+            DiagnosticStatus::Failed
+        );
+        assert_eq!(
+            status_of(&report, DiagnosticKind::Registry),
+            DiagnosticStatus::Skipped
+        );
+        let entrypoint = report.get(DiagnosticKind::Entrypoint).unwrap();
+        assert_eq!(entrypoint.status, DiagnosticStatus::Failed);
+        assert_eq!(
+            entrypoint.suggestion.as_deref(),
+            Some("Available entrypoints: chat")
+        );
+        assert!(!report.is_ok());
+        assert_eq!(report.failures().count(), 2);
+        assert!(report
+            .to_string()
+            .contains("[FAIL] entrypoint: Entrypoint `summarize` not found"));
+
+        // Nothing listens here
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_api_key("key")
+            .with_base_url("http://127.0.0.1:9");
+        let report = RunAgentClient::diagnose_config(config).await.unwrap();
+        assert_eq!(
+            status_of(&report, DiagnosticKind::Reachability),
+            DiagnosticStatus::Failed
+        );
+        assert_eq!(
+            status_of(&report, DiagnosticKind::Entrypoint),
+            DiagnosticStatus::Skipped
+        );
+    }
+
+    fn status_of(report: &DiagnosticReport, kind: DiagnosticKind) -> DiagnosticStatus {
+        report.get(kind).unwrap().status
+    }
+
+    #[tokio::test]
+    async fn test_entrypoint_discovery() {
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_api_key("key")
+            .with_base_url("http://localhost:8333");
+        let mut client = RunAgentClient::build(config).await.unwrap();
+        assert!(client.entrypoints().is_empty());
+        assert!(!client.has_entrypoint("chat"));
+
+        client.agent_architecture = Some(json!({"entrypoints": [
+            {"tag": "chat", "file": "agent.py", "module": "chat"},
+            {"tag": "chat_stream", "file": "agent.py", "module": "chat_stream"},
+            {"tag": "summarize"},
+            {"file": "untagged.py"}
+        ]}));
+        let tags = |entrypoints: Vec<EntryPoint>| -> Vec<String> {
+            entrypoints.into_iter().map(|ep| ep.tag).collect()
+        };
+        assert_eq!(
+            tags(client.entrypoints()),
+            ["chat", "chat_stream", "summarize"]
+        );
+        assert_eq!(client.entrypoints()[0].file, "agent.py");
+        assert!(client.has_entrypoint("summarize"));
+        assert!(!client.has_entrypoint("missing"));
+        assert_eq!(tags(client.streaming_entrypoints()), ["chat_stream"]);
+        assert_eq!(
+            tags(client.non_streaming_entrypoints()),
+            ["chat", "summarize"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_input_before_sending() {
+        // Nothing listens here, so only a local validation failure can be returned
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_api_key("key")
+            .with_base_url("http://127.0.0.1:9")
+            .with_validate_input(true);
+        let mut client = RunAgentClient::build(config).await.unwrap();
+        let schema = json!({"properties": {"query": {"type": "string"}}, "required": ["query"]});
+        client.agent_architecture = Some(json!({"entrypoints": [
+            {"tag": "chat", "input_schema": schema},
+            {"tag": "chat_stream", "input_schema": schema},
+        ]}));
+
+        let err = client.run(&[("query", json!(42))]).await.unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert!(err.to_string().contains("query (expected string)"));
+
+        let stream_client = client.with_entrypoint("chat_stream").unwrap();
+        let err = stream_client.run_stream(&[]).await.err().unwrap();
+        assert!(err.to_string().contains("missing: query"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_profile_is_a_config_error() {
+        let config = RunAgentClientConfig::new("agent-id", "chat")
+            .with_profile("runagent-test-missing-profile")
+            .with_base_url("http://localhost:8333");
+        let err = RunAgentClient::build(config).await.err().unwrap();
+        assert_eq!(err.category(), "config");
+        assert!(err.to_string().contains("runagent-test-missing-profile"));
+    }
+
+    #[cfg(feature = "db")]
+    #[tokio::test]
+    async fn test_resolve_agent_id_by_name_and_framework() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("agents.db");
+        std::fs::File::create(&db_path).unwrap();
+        let db_service = DatabaseService::new(Some(db_path)).await.unwrap();
+        db_service
+            .import_agents(
+                &serde_json::json!({"agents": [
+                    {"agent_id": "id-1", "agent_path": "/agents/chatbot", "framework": "langgraph"},
+                    {"agent_id": "id-2", "agent_path": "/agents/sql", "framework": "langgraph"},
+                    {"agent_id": "id-3", "agent_path": "/agents/crew", "framework": "crewai"},
+                    {"agent_id": "id-4", "agent_path": dir.path().to_str().unwrap()}
+                ]}),
+                crate::db::MergeStrategy::Skip,
+            )
+            .await
+            .unwrap();
+
+        std::fs::write(dir.path().join("agent.py"), "import llama_index\n").unwrap();
+
+        let resolve = |name, framework| resolve_agent_id(&db_service, name, framework);
+        assert_eq!(resolve(None, Some("crewai")).await.unwrap(), "id-3");
+        assert_eq!(resolve(None, Some("llamaindex")).await.unwrap(), "id-4");
+        assert_eq!(
+            resolve(Some("sql"), Some("langgraph")).await.unwrap(),
+            "id-2"
+        );
+
+        let ambiguous = resolve(None, Some("langgraph")).await.unwrap_err();
+        assert_eq!(ambiguous.category(), "validation");
+        assert!(ambiguous.to_string().contains("id-1, id-2"));
+        let missing = resolve(Some("sql"), Some("crewai")).await.unwrap_err();
+        assert_eq!(missing.category(), "validation");
+    }
+}
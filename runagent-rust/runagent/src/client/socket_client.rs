@@ -1,21 +1,134 @@
 //! WebSocket client for streaming agent interactions
 
-use crate::types::{MessageType, RunAgentError, RunAgentResult, SafeMessage};
+use crate::client::rest_client::sdk_user_agent;
+use crate::client::tls::TlsConfig;
+use crate::client::token::TokenProvider;
+use crate::constants::{DEFAULT_MAX_PAYLOAD_MB, STREAM_TIMEOUT_SECONDS};
+use crate::types::{extract_error, MessageType, RunAgentError, RunAgentResult, SafeMessage};
 use crate::utils::config::Config;
 use crate::utils::serializer::CoreSerializer;
-use futures::{SinkExt, Stream, StreamExt};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, USER_AGENT};
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{connect_async_tls_with_config, Connector, WebSocketStream};
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+/// Reconnection and keepalive behaviour for streams
+///
+/// When a stream's connection drops before the server sent a final event, the
+/// client reconnects up to `max_reconnects` times per stream, waiting
+/// `reconnect_delay` before each attempt, and re-sends the start request.
+///
+/// With `ping_interval` set, a WebSocket ping frame is sent whenever a stream
+/// has been idle that long, so proxies keep the connection open while the agent
+/// thinks. A ping left unanswered for `pong_timeout` counts as a dropped
+/// connection, which triggers a reconnect when any are allowed.
+#[derive(Debug, Clone)]
+pub struct SocketClientConfig {
+    /// Reconnect attempts allowed per stream (default: 0, never reconnect)
+    pub max_reconnects: u32,
+    /// Wait before each reconnect attempt (default: 1 second)
+    pub reconnect_delay: Duration,
+    /// Idle time after which a keepalive ping is sent (default: none, no pings)
+    pub ping_interval: Option<Duration>,
+    /// Longest wait for the pong answering a keepalive ping (default: 10 seconds)
+    pub pong_timeout: Duration,
+}
+
+impl Default for SocketClientConfig {
+    fn default() -> Self {
+        Self {
+            max_reconnects: 0,
+            reconnect_delay: Duration::from_secs(1),
+            ping_interval: None,
+            pong_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl SocketClientConfig {
+    /// Allow up to `max_reconnects` reconnect attempts per stream
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Set the wait before each reconnect attempt
+    pub fn with_reconnect_delay(mut self, reconnect_delay: Duration) -> Self {
+        self.reconnect_delay = reconnect_delay;
+        self
+    }
+
+    /// Send a keepalive ping after `ping_interval` without incoming frames
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = Some(ping_interval);
+        self
+    }
+
+    /// Set the longest wait for a keepalive pong before the connection is dropped
+    pub fn with_pong_timeout(mut self, pong_timeout: Duration) -> Self {
+        self.pong_timeout = pong_timeout;
+        self
+    }
+}
+
+/// Resilience settings for a single streaming run
+///
+/// Passed to `run_stream_with_policy` to override the client's
+/// [`with_stream_reconnect`](crate::RunAgentClientConfig::with_stream_reconnect)
+/// and [`with_chunk_timeout`](crate::RunAgentClientConfig::with_chunk_timeout)
+/// settings for one stream. Unset fields keep the client's settings.
+#[derive(Debug, Clone, Default)]
+pub struct StreamPolicy {
+    /// Reconnection and keepalive behaviour
+    pub reconnect: Option<SocketClientConfig>,
+    /// Longest wait for the next chunk before the stream fails
+    pub idle_timeout: Option<Duration>,
+}
+
+impl StreamPolicy {
+    /// Create a policy that keeps the client's settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resume dropped connections as `reconnect` describes
+    pub fn with_reconnect(mut self, reconnect: SocketClientConfig) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+
+    /// Fail the stream after `idle_timeout` without a chunk
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+}
+
 /// WebSocket client for agent streaming
+#[derive(Clone)]
 pub struct SocketClient {
     base_socket_url: String,
     api_key: Option<String>,
     api_prefix: String,
     serializer: CoreSerializer,
+    respond_to_server_pings: bool,
+    config: SocketClientConfig,
+    unix_socket: Option<PathBuf>,
+    token_provider: Option<TokenProvider>,
+    tls: Option<Connector>,
+    user_agent: String,
 }
 
 impl SocketClient {
@@ -25,16 +138,122 @@ impl SocketClient {
         api_key: Option<String>,
         api_prefix: Option<&str>,
     ) -> RunAgentResult<Self> {
-        let serializer = CoreSerializer::new(10.0)?;
+        let serializer = CoreSerializer::new(DEFAULT_MAX_PAYLOAD_MB)?;
 
         Ok(Self {
             base_socket_url: base_socket_url.trim_end_matches('/').to_string(),
             api_key,
             api_prefix: api_prefix.unwrap_or("/api/v1").to_string(),
             serializer,
+            respond_to_server_pings: true,
+            config: SocketClientConfig::default(),
+            unix_socket: None,
+            token_provider: None,
+            tls: None,
+            user_agent: sdk_user_agent(None),
         })
     }
 
+    /// Identify the calling app after the SDK in the handshake's `User-Agent`
+    ///
+    /// See [`RestClient::with_user_agent_suffix`](crate::RestClient::with_user_agent_suffix).
+    pub fn with_user_agent_suffix(mut self, suffix: &str) -> Self {
+        self.user_agent = sdk_user_agent(Some(suffix));
+        self
+    }
+
+    /// `User-Agent` header sent with every WebSocket handshake
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Authenticate with tokens from `provider` instead of the static API key
+    ///
+    /// A token is fetched (or reused from the provider's cache) each time a stream
+    /// connects; reconnects of the same run reuse its token.
+    pub fn with_token_provider(mut self, provider: TokenProvider) -> Self {
+        self.token_provider = Some(provider);
+        self
+    }
+
+    /// Connect to `wss://` URLs with a client certificate or extra root CAs
+    ///
+    /// Fails with a config error when a certificate or key cannot be loaded.
+    pub fn with_tls(mut self, tls: &TlsConfig) -> RunAgentResult<Self> {
+        self.tls = Some(tls.websocket_connector()?);
+        Ok(self)
+    }
+
+    /// Connect over a Unix domain socket instead of TCP
+    ///
+    /// The WebSocket URL (e.g. `ws://localhost/...`) is only used for the
+    /// handshake; the host and port are never resolved.
+    #[cfg(all(unix, feature = "unix-socket"))]
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Get the Unix domain socket streams connect over, if any
+    pub fn unix_socket(&self) -> Option<&Path> {
+        self.unix_socket.as_deref()
+    }
+
+    /// Set the reconnection behaviour for streams
+    ///
+    /// See [`SocketClient::run_stream`] for when a stream reconnects.
+    pub fn with_config(mut self, config: SocketClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Get the reconnection behaviour for streams
+    pub fn config(&self) -> &SocketClientConfig {
+        &self.config
+    }
+
+    /// Reject streamed chunks larger than `max_mb` megabytes (default: 10)
+    pub fn with_max_payload_mb(mut self, max_mb: f64) -> Self {
+        self.serializer = CoreSerializer::with_max_mb(max_mb)
+            .with_envelope_unwrap(self.serializer.unwraps_envelopes());
+        self
+    }
+
+    /// Decode streamed chunks with an existing payload serializer
+    pub(crate) fn with_serializer(mut self, serializer: CoreSerializer) -> Self {
+        self.serializer = serializer;
+        self
+    }
+
+    /// Carry over stream settings from a client being replaced
+    pub(crate) fn with_settings_of(mut self, other: &SocketClient) -> Self {
+        self.respond_to_server_pings = other.respond_to_server_pings;
+        self.config = other.config.clone();
+        self.serializer = other.serializer.clone();
+        self.token_provider = other.token_provider.clone();
+        self.tls = other.tls.clone();
+        self.user_agent = other.user_agent.clone();
+        self
+    }
+
+    /// Path prefix of the API routes, e.g. `/api/v1`
+    pub fn api_prefix(&self) -> &str {
+        &self.api_prefix
+    }
+
+    /// Enable or disable replies to server-initiated ping frames (default: enabled)
+    ///
+    /// See [`SocketClient::run_stream`] for the heartbeat frame contract.
+    pub fn with_server_ping_response(mut self, respond: bool) -> Self {
+        self.respond_to_server_pings = respond;
+        self
+    }
+
+    /// Check if server-initiated ping frames are answered
+    pub fn responds_to_server_pings(&self) -> bool {
+        self.respond_to_server_pings
+    }
+
     /// Create a default WebSocket client using configuration
     #[allow(clippy::should_implement_trait)]
     pub fn default() -> RunAgentResult<Self> {
@@ -53,47 +272,56 @@ impl SocketClient {
         Self::new(&ws_url, config.api_key(), Some("/api/v1"))
     }
 
+    /// WebSocket URL for a run, with a token from the provider when one is set
+    async fn resolve_websocket_url(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+    ) -> RunAgentResult<Url> {
+        match &self.token_provider {
+            Some(provider) => {
+                let token = provider.token().await?;
+                self.websocket_url_with_token(agent_id, Some(&token))
+            }
+            None => self.get_websocket_url(agent_id, entrypoint_tag),
+        }
+    }
+
     fn get_websocket_url(&self, agent_id: &str, _entrypoint_tag: &str) -> RunAgentResult<Url> {
+        self.websocket_url_with_token(agent_id, self.api_key.as_deref())
+    }
+
+    fn websocket_url_with_token(&self, agent_id: &str, token: Option<&str>) -> RunAgentResult<Url> {
         let path = format!("agents/{}/run-stream", agent_id);
         let mut full_url = format!("{}{}/{}", self.base_socket_url, self.api_prefix, path);
 
         // Add API key as token parameter if available
-        if let Some(ref api_key) = self.api_key {
-            full_url = format!("{}?token={}", full_url, api_key);
+        if let Some(token) = token {
+            full_url = format!("{}?token={}", full_url, token);
         }
 
         Url::parse(&full_url)
             .map_err(|e| RunAgentError::validation(format!("Invalid WebSocket URL: {}", e)))
     }
 
-    /// Run agent with streaming response
-    pub async fn run_stream(
-        &self,
-        agent_id: &str,
+    /// Build the start request sent as the first message of a stream session
+    ///
+    /// This is the body `run_stream` sends; use it with [`SocketClient::open_session`]
+    /// to start a run before exchanging further messages.
+    pub fn start_request(
         entrypoint_tag: &str,
         input_args: &[Value],
         input_kwargs: &HashMap<String, Value>,
         user_id: Option<&str>,
         persistent_memory: bool,
-    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
-        let url = self.get_websocket_url(agent_id, entrypoint_tag)?;
-
-        tracing::debug!("Connecting to WebSocket: {}", url);
-
-        // Connect to WebSocket
-        let (ws_stream, _) = connect_async(url).await.map_err(|e| {
-            RunAgentError::connection(format!("WebSocket connection failed: {}", e))
-        })?;
-
-        let (mut write, mut read) = ws_stream.split();
-
+    ) -> Value {
         // Prepare start stream request with id field (as middleware expects)
         let mut request_data = serde_json::json!({
             "id": "stream_start",
             "entrypoint_tag": entrypoint_tag,
             "input_args": input_args,
             "input_kwargs": input_kwargs,
-            "timeout_seconds": 600,
+            "timeout_seconds": STREAM_TIMEOUT_SECONDS,
             "async_execution": false
         });
 
@@ -112,104 +340,278 @@ impl SocketClient {
             }
         }
 
+        request_data
+    }
+
+    /// Open a raw bidirectional session on the agent's stream endpoint
+    ///
+    /// Nothing is sent on connect. The message contract is:
+    ///
+    /// - The client's first message is the start request (see
+    ///   [`SocketClient::start_request`]).
+    /// - The server replies with JSON frames: `{"type": "status", "status":
+    ///   "stream_started" | "stream_completed"}`, `{"type": "data", "content": ...}`
+    ///   and `{"type": "error", "error" | "detail": ...}`.
+    /// - Any further client messages are agent-defined JSON (e.g. approving a tool
+    ///   call) and are forwarded to the running agent as-is.
+    ///
+    /// Incoming frames are yielded as parsed JSON without interpretation.
+    pub async fn open_session(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+    ) -> RunAgentResult<StreamSession> {
+        let url = self.resolve_websocket_url(agent_id, entrypoint_tag).await?;
+        connect(
+            url,
+            &self.user_agent,
+            self.unix_socket.as_deref(),
+            self.tls.as_ref(),
+        )
+        .await
+    }
+
+    /// Run agent with streaming response
+    ///
+    /// Servers may push application-level heartbeats on idle streams, separate from
+    /// WebSocket protocol pings: `{"type": "ping", "id"?: ...}` frames are answered
+    /// with `{"type": "pong", "id"?: ...}` (echoing `id`) unless disabled with
+    /// [`SocketClient::with_server_ping_response`], and `{"type": "heartbeat"}`
+    /// frames need no reply. Neither is yielded as a chunk.
+    ///
+    /// With [`SocketClientConfig::max_reconnects`] set, a connection that drops
+    /// before the server reports completion (`stream_completed` status, or a
+    /// `complete` / `completed` / `stream_end` chunk) is re-established and the run
+    /// is started again. Each attempt first yields `{"type": "reconnecting",
+    /// "attempt": n}`; chunks after it come from the restarted run.
+    pub async fn run_stream(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &HashMap<String, Value>,
+        user_id: Option<&str>,
+        persistent_memory: bool,
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        let session = self
+            .start_stream(
+                agent_id,
+                entrypoint_tag,
+                input_args,
+                input_kwargs,
+                user_id,
+                persistent_memory,
+            )
+            .await?;
+        Ok(self.chunk_stream(session, None, false))
+    }
+
+    /// Open a session and send the start request
+    pub(crate) async fn start_stream(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &HashMap<String, Value>,
+        user_id: Option<&str>,
+        persistent_memory: bool,
+    ) -> RunAgentResult<StreamSession> {
+        let url = self.resolve_websocket_url(agent_id, entrypoint_tag).await?;
+
         // Send the request data directly (matching Python SDK format)
-        let serialized_msg = serde_json::to_string(&request_data)?;
-        write
-            .send(Message::Text(serialized_msg))
-            .await
-            .map_err(|e| {
-                RunAgentError::connection(format!("Failed to send start message: {}", e))
-            })?;
+        let request_data = Self::start_request(
+            entrypoint_tag,
+            input_args,
+            input_kwargs,
+            user_id,
+            persistent_memory,
+        );
+        let restart = StreamRestart {
+            url,
+            request: request_data,
+            unix_socket: self.unix_socket.clone(),
+            tls: self.tls.clone(),
+            user_agent: self.user_agent.clone(),
+        };
+        let mut session = restart.start().await?;
+        session.restart = Some(restart);
+
+        Ok(session)
+    }
 
+    /// Turn a started session into the chunk stream `run_stream` returns
+    ///
+    /// When `cancel` fires, any partially received frame is dropped, a close frame
+    /// is sent and `{"type": "cancelled"}` is yielded as the final item. With
+    /// `lenient`, recoverable failures are yielded as [`error_item`]s and the
+    /// stream goes on.
+    pub(crate) fn chunk_stream(
+        &self,
+        mut session: StreamSession,
+        cancel: Option<CancellationToken>,
+        lenient: bool,
+    ) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>> {
         // Clone serializer for use in async stream
         let serializer = self.serializer.clone();
+        let respond_to_pings = self.respond_to_server_pings;
+        let config = self.config.clone();
+        let restart = session.restart.take();
 
         // Create stream that processes incoming messages (matching Python SDK behavior)
         let stream = async_stream::stream! {
-            while let Some(message) = read.next().await {
-                match message {
-                    Ok(Message::Text(text)) => {
-                        // Parse as plain JSON (matching Python SDK)
-                        match serde_json::from_str::<serde_json::Value>(&text) {
-                            Ok(msg) => {
-                                let message_type = msg.get("type").and_then(|v| v.as_str());
-
-                                match message_type {
-                                    Some("status") => {
-                                        if let Some(status) = msg.get("status").and_then(|v| v.as_str()) {
-                                            if status == "stream_completed" {
-                                                break;
-                                            } else if status == "stream_started" {
-                                                continue; // Skip status messages
-                                            }
-                                        }
-                                    }
-                                    Some("error") => {
-                                        let error_msg = msg.get("error")
-                                            .or_else(|| msg.get("detail"))
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("Unknown error");
-                                        yield Err(RunAgentError::server(format!("Stream error: {}", error_msg)));
-                                        break;
-                                    }
-                                    Some("data") => {
-                                        // Extract content and deserialize it using the common deserializer
-                                        if let Some(content) = msg.get("content") {
-                                            // Use common deserializer preparation logic (handles JSON strings)
-                                            let prepared = serializer.prepare_for_deserialization(content.clone());
-
-                                            // Deserialize using the common serializer (handles {type, payload} structure)
-                                            match serializer.deserialize_object(prepared) {
-                                                Ok(deserialized) => yield Ok(deserialized),
-                                                Err(e) => {
-                                                    yield Err(RunAgentError::server(format!("Deserialization error: {}", e)));
-                                                    break;
-                                                }
-                                            }
-                                        } else {
-                                            // If no content, yield the whole message
-                                            yield Ok(msg);
-                                        }
-                                    }
-                                    _ => {
-                                        // For other message types, yield the whole message
-                                        yield Ok(msg);
-                                    }
+            let mut finished = false;
+            let mut attempts = 0;
+            let mut keepalive = Keepalive::new(&config);
+            loop {
+                let mut cancelled = false;
+                let mut keepalive_due = false;
+                let mut ponged = false;
+                let pong = session.pong.clone();
+                let message = tokio::select! {
+                    biased;
+                    _ = wait_cancelled(cancel.as_ref()) => {
+                        cancelled = true;
+                        None
+                    }
+                    message = session.next() => message,
+                    _ = pong.notified() => {
+                        ponged = true;
+                        None
+                    }
+                    _ = wait_until(keepalive.deadline()) => {
+                        keepalive_due = true;
+                        None
+                    }
+                };
+                if cancelled {
+                    if let Err(e) = session.close().await {
+                        tracing::debug!("Failed to close cancelled stream: {}", e);
+                    }
+                    yield Ok(serde_json::json!({"type": "cancelled"}));
+                    break;
+                }
+                if ponged {
+                    keepalive.reset();
+                    continue;
+                }
+                let message = if keepalive_due {
+                    match keepalive.on_deadline(&mut session).await {
+                        Ok(()) => continue,
+                        // Handled like any dropped connection below
+                        Err(e) => Some(Err(e)),
+                    }
+                } else {
+                    keepalive.reset();
+                    message
+                };
+                let msg = match message {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(e)) if !matches!(e, RunAgentError::Connection { .. }) => {
+                        yield Err(e);
+                        break;
+                    }
+                    // The connection dropped; resume it unless the run already finished
+                    dropped => {
+                        let error = dropped.and_then(Result::err);
+                        let Some(restart) = restart.as_ref().filter(|_| !finished) else {
+                            if let Some(e) = error {
+                                yield Err(e);
+                            }
+                            break;
+                        };
+
+                        let mut last_error = error;
+                        let mut resumed = None;
+                        while attempts < config.max_reconnects {
+                            attempts += 1;
+                            yield Ok(serde_json::json!({"type": "reconnecting", "attempt": attempts}));
+                            tokio::select! {
+                                biased;
+                                _ = wait_cancelled(cancel.as_ref()) => {
+                                    cancelled = true;
+                                    break;
                                 }
+                                _ = tokio::time::sleep(config.reconnect_delay) => {}
                             }
-                            Err(e) => {
-                                yield Err(RunAgentError::server(format!("Stream error: JSON error: {}", e)));
+                            match restart.start().await {
+                                Ok(new_session) => {
+                                    resumed = Some(new_session);
+                                    break;
+                                }
+                                Err(e) => {
+                                    tracing::debug!("Reconnect attempt {} failed: {}", attempts, e);
+                                    last_error = Some(e);
+                                }
+                            }
+                        }
+                        if cancelled {
+                            yield Ok(serde_json::json!({"type": "cancelled"}));
+                            break;
+                        }
+                        match resumed {
+                            Some(new_session) => {
+                                session = new_session;
+                                keepalive.reset();
+                                continue;
+                            }
+                            None => {
+                                if let Some(e) = last_error {
+                                    yield Err(e);
+                                }
                                 break;
                             }
                         }
                     }
-                    Ok(Message::Close(_)) => {
-                        break;
+                };
+
+                let message_type = msg.get("type").and_then(|v| v.as_str());
+                if let Some("ping") | Some("heartbeat") = message_type {
+                    if respond_to_pings {
+                        if let Some(reply) = heartbeat_reply(&msg) {
+                            if let Err(e) = session.send(&reply).await {
+                                yield Err(e);
+                                break;
+                            }
+                        }
                     }
-                    Ok(_) => {
-                        // Ignore binary and other message types
-                        continue;
+                    continue;
+                }
+
+                match read_frame(&serializer, msg) {
+                    Frame::Skip => continue,
+                    Frame::End => break,
+                    Frame::Chunk(chunk) => {
+                        finished |= is_final_chunk(&chunk);
+                        yield Ok(chunk);
                     }
-                    Err(e) => {
-                        yield Err(RunAgentError::connection(format!("WebSocket error: {}", e)));
+                    Frame::Fail { error, recoverable } if lenient => {
+                        yield Ok(error_item(&error, recoverable));
+                        if !recoverable {
+                            break;
+                        }
+                    }
+                    Frame::Fail { error, .. } => {
+                        yield Err(error);
                         break;
                     }
                 }
             }
         };
 
-        Ok(Box::pin(stream))
+        Box::pin(stream)
     }
 
     /// Send a ping message to test connection
     pub async fn ping(&self, agent_id: &str, entrypoint_tag: &str) -> RunAgentResult<bool> {
-        let url = self.get_websocket_url(agent_id, entrypoint_tag)?;
-
-        let (ws_stream, _) = connect_async(url).await.map_err(|e| {
-            RunAgentError::connection(format!("WebSocket connection failed: {}", e))
-        })?;
-
-        let (mut write, mut read) = ws_stream.split();
+        let url = self.resolve_websocket_url(agent_id, entrypoint_tag).await?;
+        let (mut write, mut read) = connect(
+            url,
+            &self.user_agent,
+            self.unix_socket.as_deref(),
+            self.tls.as_ref(),
+        )
+        .await?
+        .into_split();
 
         // Send ping
         let ping_msg = SafeMessage::new(
@@ -220,6 +622,7 @@ impl SocketClient {
 
         let serialized_msg = self.serializer.serialize_message(&ping_msg)?;
         write
+            .sink
             .send(Message::Text(serialized_msg))
             .await
             .map_err(|e| RunAgentError::connection(format!("Failed to send ping: {}", e)))?;
@@ -235,6 +638,355 @@ impl SocketClient {
     }
 }
 
+/// What a stream does with one server frame
+pub(crate) enum Frame {
+    /// Bookkeeping frame that is not yielded
+    Skip,
+    /// The server finished the run
+    End,
+    /// A chunk for the caller
+    Chunk(Value),
+    /// The run failed; the stream ends after this error unless it is lenient and
+    /// the failure `recoverable`
+    Fail {
+        error: RunAgentError,
+        recoverable: bool,
+    },
+}
+
+/// Interpret a frame of the stream protocol shared by the WebSocket and SSE transports
+///
+/// `data` frames yield their deserialized `content`, `error` frames fail the
+/// stream and `stream_completed` statuses end it. Other frames are yielded whole.
+///
+/// Error frames are recoverable unless they carry `"recoverable": false`; a
+/// chunk that cannot be deserialized is always recoverable.
+pub(crate) fn read_frame(serializer: &CoreSerializer, msg: Value) -> Frame {
+    match msg.get("type").and_then(|v| v.as_str()) {
+        Some("status") => match msg.get("status").and_then(|v| v.as_str()) {
+            Some("stream_completed") => Frame::End,
+            _ => Frame::Skip,
+        },
+        Some("ping") | Some("heartbeat") => Frame::Skip,
+        Some("error") => Frame::Fail {
+            error: extract_error(&msg).unwrap_or_else(|| RunAgentError::server("Unknown error")),
+            recoverable: msg
+                .get("recoverable")
+                .and_then(Value::as_bool)
+                .unwrap_or(true),
+        },
+        Some("data") => match msg.get("content") {
+            // Use common deserializer preparation logic (handles JSON strings),
+            // then the common serializer (handles {type, payload} structure)
+            Some(content) => match serializer
+                .try_prepare_for_deserialization(content.clone())
+                .and_then(|prepared| serializer.deserialize_object(prepared))
+            {
+                Ok(deserialized) => Frame::Chunk(deserialized),
+                Err(e) => Frame::Fail {
+                    error: match e {
+                        RunAgentError::Serialization { .. } => e,
+                        e => RunAgentError::server(format!("Deserialization error: {}", e)),
+                    },
+                    recoverable: true,
+                },
+            },
+            // If no content, yield the whole message
+            None => Frame::Chunk(msg),
+        },
+        _ => Frame::Chunk(msg),
+    }
+}
+
+/// Whether `chunk` reports the end of the run, so a dropped stream must not restart it
+///
+/// Covers the final types of [`StreamItem`](crate::StreamItem) plus `stream_end`,
+/// whether the chunk came wrapped in a `data` frame or as a frame of its own.
+fn is_final_chunk(chunk: &Value) -> bool {
+    matches!(
+        chunk.get("type").and_then(Value::as_str),
+        Some("complete" | "completed" | "done" | "stream_end")
+    )
+}
+
+/// In-band item reporting a failure of a lenient stream
+///
+/// `{"type": "error", "message", "category", "recoverable"}`, where `category`
+/// is [`RunAgentError::category`]. The stream ends after an unrecoverable error.
+pub(crate) fn error_item(error: &RunAgentError, recoverable: bool) -> Value {
+    serde_json::json!({
+        "type": "error",
+        "message": error.to_string(),
+        "category": error.category(),
+        "recoverable": recoverable,
+    })
+}
+
+/// Wait until `deadline`, or forever when there is none
+async fn wait_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Keepalive pings of one stream
+struct Keepalive {
+    ping_interval: Option<Duration>,
+    pong_timeout: Duration,
+    /// Start of the current quiet period
+    idle_since: tokio::time::Instant,
+    /// When the unanswered ping was sent
+    awaiting_pong: Option<tokio::time::Instant>,
+}
+
+impl Keepalive {
+    fn new(config: &SocketClientConfig) -> Self {
+        Self {
+            ping_interval: config.ping_interval,
+            pong_timeout: config.pong_timeout,
+            idle_since: tokio::time::Instant::now(),
+            awaiting_pong: None,
+        }
+    }
+
+    /// When the next ping is due or the outstanding pong times out
+    fn deadline(&self) -> Option<tokio::time::Instant> {
+        let ping_interval = self.ping_interval?;
+        Some(match self.awaiting_pong {
+            Some(sent) => sent + self.pong_timeout,
+            None => self.idle_since + ping_interval,
+        })
+    }
+
+    /// Note incoming traffic or a pong, which prove the connection alive
+    fn reset(&mut self) {
+        self.idle_since = tokio::time::Instant::now();
+        self.awaiting_pong = None;
+    }
+
+    /// Send a ping, or fail when the previous one went unanswered
+    ///
+    /// A pong resets the keepalive before its timeout, so an outstanding ping at
+    /// the deadline was never answered.
+    async fn on_deadline(&mut self, session: &mut StreamSession) -> RunAgentResult<()> {
+        if self.awaiting_pong.is_some() {
+            return Err(RunAgentError::connection(format!(
+                "No pong received within {:?} of a keepalive ping",
+                self.pong_timeout
+            )));
+        }
+        session.sender.ping().await?;
+        self.awaiting_pong = Some(tokio::time::Instant::now());
+        Ok(())
+    }
+}
+
+/// Resolve once `token` is cancelled; never resolves without a token
+pub(crate) async fn wait_cancelled(token: Option<&CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Reply owed to a server-initiated heartbeat frame, if any
+fn heartbeat_reply(msg: &Value) -> Option<Value> {
+    if msg.get("type").and_then(|t| t.as_str()) != Some("ping") {
+        return None;
+    }
+    let mut reply = serde_json::json!({"type": "pong"});
+    if let Some(id) = msg.get("id") {
+        reply["id"] = id.clone();
+    }
+    Some(reply)
+}
+
+/// Open a WebSocket session to `url`, over `unix_socket` when given
+async fn connect(
+    url: Url,
+    user_agent: &str,
+    unix_socket: Option<&Path>,
+    tls: Option<&Connector>,
+) -> RunAgentResult<StreamSession> {
+    tracing::debug!("Connecting to WebSocket: {}", url);
+
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| RunAgentError::validation(format!("Invalid WebSocket URL: {}", e)))?;
+    let user_agent = HeaderValue::from_str(user_agent)
+        .map_err(|e| RunAgentError::config(format!("Invalid user agent: {}", e)))?;
+    request.headers_mut().insert(USER_AGENT, user_agent);
+
+    #[cfg(all(unix, feature = "unix-socket"))]
+    if let Some(path) = unix_socket {
+        let ws_stream = crate::client::unix::connect_websocket(path, request).await?;
+        return Ok(StreamSession::new(ws_stream));
+    }
+    #[cfg(not(all(unix, feature = "unix-socket")))]
+    let _ = unix_socket;
+
+    let (ws_stream, _) = connect_async_tls_with_config(request, None, false, tls.cloned())
+        .await
+        .map_err(|e| RunAgentError::connection(format!("WebSocket connection failed: {}", e)))?;
+    Ok(StreamSession::new(ws_stream))
+}
+
+/// Everything needed to start a stream's run again on a new connection
+struct StreamRestart {
+    url: Url,
+    request: Value,
+    unix_socket: Option<PathBuf>,
+    tls: Option<Connector>,
+    user_agent: String,
+}
+
+impl StreamRestart {
+    /// Connect and send the start request as the first message
+    async fn start(&self) -> RunAgentResult<StreamSession> {
+        let mut session = connect(
+            self.url.clone(),
+            &self.user_agent,
+            self.unix_socket.as_deref(),
+            self.tls.as_ref(),
+        )
+        .await?;
+        session.send(&self.request).await.map_err(|e| {
+            RunAgentError::connection(format!("Failed to send start message: {}", e))
+        })?;
+        Ok(session)
+    }
+}
+
+type WsSink = Pin<Box<dyn Sink<Message, Error = tungstenite::Error> + Send>>;
+
+/// Raw bidirectional WebSocket session with an agent
+///
+/// Created by [`SocketClient::open_session`]. Send JSON messages with
+/// [`StreamSession::send`] and read incoming JSON frames through the `Stream`
+/// implementation. Use [`StreamSession::into_split`] to send and receive from
+/// different tasks.
+pub struct StreamSession {
+    sender: SessionSender,
+    incoming: Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>,
+    /// Signalled whenever a pong frame arrives
+    pong: Arc<Notify>,
+    /// How to re-run the stream after a dropped connection
+    restart: Option<StreamRestart>,
+}
+
+/// Sending half of a [`StreamSession`]
+pub struct SessionSender {
+    sink: WsSink,
+}
+
+impl SessionSender {
+    /// Send a JSON message to the agent
+    pub async fn send(&mut self, message: &Value) -> RunAgentResult<()> {
+        let serialized_msg = serde_json::to_string(message)?;
+        self.sink
+            .send(Message::Text(serialized_msg))
+            .await
+            .map_err(|e| RunAgentError::connection(format!("Failed to send message: {}", e)))
+    }
+
+    /// Send a WebSocket ping frame
+    pub(crate) async fn ping(&mut self) -> RunAgentResult<()> {
+        self.sink
+            .send(Message::Ping(Vec::new()))
+            .await
+            .map_err(|e| RunAgentError::connection(format!("Failed to send ping: {}", e)))
+    }
+
+    /// Send a close frame, ending the session
+    pub async fn close(&mut self) -> RunAgentResult<()> {
+        self.sink
+            .close()
+            .await
+            .map_err(|e| RunAgentError::connection(format!("Failed to close WebSocket: {}", e)))
+    }
+}
+
+impl StreamSession {
+    fn new<S>(ws_stream: WebSocketStream<S>) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (sink, mut read) = ws_stream.split();
+        let pong = Arc::new(Notify::new());
+        let pong_signal = pong.clone();
+
+        let incoming = async_stream::stream! {
+            while let Some(message) = read.next().await {
+                match message {
+                    Ok(Message::Text(text)) => {
+                        // Parse as plain JSON (matching Python SDK)
+                        match serde_json::from_str::<Value>(&text) {
+                            Ok(msg) => yield Ok(msg),
+                            Err(e) => {
+                                yield Err(RunAgentError::server(format!("Stream error: JSON error: {}", e)));
+                                break;
+                            }
+                        }
+                    }
+                    Ok(Message::Close(_)) => {
+                        break;
+                    }
+                    Ok(Message::Pong(_)) => {
+                        pong_signal.notify_one();
+                    }
+                    Ok(_) => {
+                        // Ignore binary and other message types
+                        continue;
+                    }
+                    Err(e) => {
+                        yield Err(RunAgentError::connection(format!("WebSocket error: {}", e)));
+                        break;
+                    }
+                }
+            }
+        };
+
+        Self {
+            sender: SessionSender {
+                sink: Box::pin(sink),
+            },
+            incoming: Box::pin(incoming),
+            pong,
+            restart: None,
+        }
+    }
+
+    /// Send a JSON message to the agent
+    pub async fn send(&mut self, message: &Value) -> RunAgentResult<()> {
+        self.sender.send(message).await
+    }
+
+    /// Send a close frame, ending the session
+    pub async fn close(&mut self) -> RunAgentResult<()> {
+        self.sender.close().await
+    }
+
+    /// Split into a sender and a stream of incoming messages
+    pub fn into_split(
+        self,
+    ) -> (
+        SessionSender,
+        Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>,
+    ) {
+        (self.sender, self.incoming)
+    }
+}
+
+impl Stream for StreamSession {
+    type Item = RunAgentResult<Value>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.incoming.as_mut().poll_next(cx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,12 +1002,278 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_websocket_url_with_custom_prefix() {
+        let client =
+            SocketClient::new("wss://proxy.local", None, Some("/runagent/api/v2")).unwrap();
+        let url = client.get_websocket_url("test-agent", "generic").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "wss://proxy.local/runagent/api/v2/agents/test-agent/run-stream"
+        );
+    }
+
     #[test]
     fn test_client_creation() {
         let client = SocketClient::new("ws://localhost:8000", None, None);
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_start_request_body() {
+        let mut kwargs = HashMap::new();
+        kwargs.insert("message".to_string(), serde_json::json!("hi"));
+
+        let body = SocketClient::start_request("chat_stream", &[], &kwargs, Some("u1"), true);
+        assert_eq!(body["id"], "stream_start");
+        assert_eq!(body["entrypoint_tag"], "chat_stream");
+        assert_eq!(body["input_kwargs"]["message"], "hi");
+        assert_eq!(body["user_id"], "u1");
+        assert_eq!(body["persistent_memory"], true);
+
+        let body = SocketClient::start_request("chat_stream", &[], &kwargs, None, false);
+        assert!(body.get("user_id").is_none());
+        assert!(body.get("persistent_memory").is_none());
+    }
+
+    #[test]
+    fn test_heartbeat_reply() {
+        let reply = heartbeat_reply(&serde_json::json!({"type": "ping", "id": 7})).unwrap();
+        assert_eq!(reply, serde_json::json!({"type": "pong", "id": 7}));
+        assert_eq!(
+            heartbeat_reply(&serde_json::json!({"type": "ping"})),
+            Some(serde_json::json!({"type": "pong"}))
+        );
+        assert!(heartbeat_reply(&serde_json::json!({"type": "heartbeat"})).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_stream_closes_and_reports() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.next().await; // start request
+            let chunk = serde_json::json!({"type": "data", "content": "first"});
+            ws.send(Message::Text(chunk.to_string())).await.unwrap();
+            // Never completes; wait for the client to close
+            while let Some(Ok(message)) = ws.next().await {
+                if message.is_close() {
+                    return true;
+                }
+            }
+            false
+        });
+
+        let client = SocketClient::new(&format!("ws://{}", addr), None, None).unwrap();
+        let session = client
+            .start_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap();
+        let token = CancellationToken::new();
+        let mut stream = client.chunk_stream(session, Some(token.clone()), false);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "first");
+        token.cancel();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            serde_json::json!({"type": "cancelled"})
+        );
+        assert!(stream.next().await.is_none());
+        assert!(server.await.unwrap());
+    }
+
+    /// Serve one scripted connection per entry, then close it cleanly or drop it
+    async fn scripted_server(scripts: Vec<(Vec<Value>, bool)>) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for (frames, close) in scripts {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                ws.next().await; // start request
+                for frame in frames {
+                    ws.send(Message::Text(frame.to_string())).await.unwrap();
+                }
+                if close {
+                    ws.close(None).await.unwrap();
+                }
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_stream_reconnects_after_dropped_connection() {
+        use serde_json::json;
+
+        let addr = scripted_server(vec![
+            (vec![json!({"type": "data", "content": "first"})], false),
+            (vec![json!({"type": "completed"})], true),
+        ])
+        .await;
+        let client = SocketClient::new(&format!("ws://{}", addr), None, None)
+            .unwrap()
+            .with_config(
+                SocketClientConfig::default()
+                    .with_max_reconnects(2)
+                    .with_reconnect_delay(Duration::from_millis(10)),
+            );
+
+        let stream = client
+            .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap();
+        let chunks: Vec<Value> = stream.map(|chunk| chunk.unwrap()).collect().await;
+
+        assert_eq!(
+            chunks,
+            vec![
+                json!("first"),
+                json!({"type": "reconnecting", "attempt": 1}),
+                json!({"type": "completed"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_does_not_reconnect_after_final_event() {
+        use serde_json::json;
+
+        let addr = scripted_server(vec![(vec![json!({"type": "stream_end"})], false)]).await;
+        let client = SocketClient::new(&format!("ws://{}", addr), None, None)
+            .unwrap()
+            .with_config(SocketClientConfig::default().with_max_reconnects(2));
+
+        let mut stream = client
+            .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            json!({"type": "stream_end"})
+        );
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.category(), "connection");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_does_not_reconnect_after_data_wrapped_final_chunk() {
+        use serde_json::json;
+
+        for final_type in ["complete", "done"] {
+            let final_chunk = json!({"type": final_type, "output": "answer"});
+            let addr = scripted_server(vec![
+                (
+                    vec![json!({"type": "data", "content": final_chunk.clone()})],
+                    false,
+                ),
+                // A second run the client must not start
+                (vec![json!({"type": "data", "content": "rerun"})], true),
+            ])
+            .await;
+            let client = SocketClient::new(&format!("ws://{}", addr), None, None)
+                .unwrap()
+                .with_config(
+                    SocketClientConfig::default()
+                        .with_max_reconnects(2)
+                        .with_reconnect_delay(Duration::from_millis(10)),
+                );
+
+            let mut stream = client
+                .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+                .await
+                .unwrap();
+
+            assert_eq!(stream.next().await.unwrap().unwrap(), final_chunk);
+            let err = stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err.category(), "connection");
+            assert!(stream.next().await.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_pings_idle_streams() {
+        use serde_json::json;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.next().await; // start request
+                             // Stay quiet but keep reading, so pings get answered
+            let mut pings = 0;
+            let quiet = tokio::time::sleep(Duration::from_millis(300));
+            tokio::pin!(quiet);
+            loop {
+                tokio::select! {
+                    _ = &mut quiet => break,
+                    message = ws.next() => {
+                        if matches!(message, Some(Ok(Message::Ping(_)))) {
+                            pings += 1;
+                        }
+                    }
+                }
+            }
+            let done = json!({"type": "completed"});
+            ws.send(Message::Text(done.to_string())).await.unwrap();
+            ws.close(None).await.unwrap();
+            pings
+        });
+
+        let client = SocketClient::new(&format!("ws://{}", addr), None, None)
+            .unwrap()
+            .with_config(
+                SocketClientConfig::default()
+                    .with_ping_interval(Duration::from_millis(40))
+                    // Pings must follow the idle interval, not the pong timeout
+                    .with_pong_timeout(Duration::from_secs(1)),
+            );
+        let stream = client
+            .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap();
+        let chunks: Vec<Value> = stream.map(|chunk| chunk.unwrap()).collect().await;
+
+        assert_eq!(chunks, vec![json!({"type": "completed"})]);
+        assert!(server.await.unwrap() >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_missing_pong_drops_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.next().await; // start request
+                             // Hold the connection without reading, so pings go unanswered
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(ws);
+        });
+
+        let client = SocketClient::new(&format!("ws://{}", addr), None, None)
+            .unwrap()
+            .with_config(
+                SocketClientConfig::default()
+                    .with_ping_interval(Duration::from_millis(20))
+                    .with_pong_timeout(Duration::from_millis(50)),
+            );
+        let mut stream = client
+            .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap();
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.category(), "connection");
+        assert!(err.to_string().contains("No pong"));
+        assert!(stream.next().await.is_none());
+    }
+
     #[test]
     fn test_url_conversion() {
         // Test HTTP to WebSocket URL conversion
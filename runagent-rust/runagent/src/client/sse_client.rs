@@ -0,0 +1,310 @@
+//! Server-sent events client for streaming agent interactions
+//!
+//! Some proxies and load balancers pass `text/event-stream` responses more
+//! reliably than WebSocket upgrades. [`SseClient`] starts a run with a POST to
+//! the agent's `run-stream` endpoint and reads the frames of the WebSocket
+//! protocol from the `data:` lines of the event stream, so both transports
+//! yield the same chunks. Select it for a [`RunAgentClient`](crate::RunAgentClient)
+//! with [`RunAgentClientConfig::with_stream_transport`](crate::RunAgentClientConfig::with_stream_transport).
+
+use crate::client::rest_client::RestClient;
+use crate::client::socket_client::{error_item, read_frame, wait_cancelled, Frame, SocketClient};
+use crate::constants::DEFAULT_MAX_PAYLOAD_MB;
+use crate::types::{RunAgentError, RunAgentResult};
+use crate::utils::serializer::CoreSerializer;
+use futures::{Stream, StreamExt};
+use reqwest::Response;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use tokio_util::sync::CancellationToken;
+
+/// Transport used for streaming runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamTransport {
+    /// WebSocket connection to the `run-stream` endpoint (default)
+    #[default]
+    Ws,
+    /// Server-sent events from a POST to the `run-stream` endpoint
+    Sse,
+}
+
+impl fmt::Display for StreamTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamTransport::Ws => f.write_str("WebSocket"),
+            StreamTransport::Sse => f.write_str("SSE"),
+        }
+    }
+}
+
+/// Server-sent events client for agent streaming
+///
+/// Requests go through the wrapped [`RestClient`], so its credentials, token
+/// provider, interceptors and Unix socket transport apply. Unlike WebSocket
+/// streams, event streams are one-way: server heartbeats are skipped without a
+/// reply, and dropped connections are not resumed.
+#[derive(Clone)]
+pub struct SseClient {
+    rest_client: RestClient,
+    serializer: CoreSerializer,
+}
+
+impl SseClient {
+    /// Create an SSE client sending requests through `rest_client`
+    pub fn new(rest_client: RestClient) -> RunAgentResult<Self> {
+        Ok(Self {
+            rest_client,
+            serializer: CoreSerializer::new(DEFAULT_MAX_PAYLOAD_MB)?,
+        })
+    }
+
+    /// SSE client sharing an existing payload serializer
+    pub(crate) fn with_serializer(rest_client: RestClient, serializer: CoreSerializer) -> Self {
+        Self {
+            rest_client,
+            serializer,
+        }
+    }
+
+    /// Reject streamed chunks larger than `max_mb` megabytes (default: 10)
+    pub fn with_max_payload_mb(mut self, max_mb: f64) -> Self {
+        self.serializer = CoreSerializer::with_max_mb(max_mb)
+            .with_envelope_unwrap(self.serializer.unwraps_envelopes());
+        self
+    }
+
+    /// Run agent with streaming response
+    ///
+    /// Sends the same start request as [`SocketClient::run_stream`] and yields
+    /// the same chunks.
+    pub async fn run_stream(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &HashMap<String, Value>,
+        user_id: Option<&str>,
+        persistent_memory: bool,
+    ) -> RunAgentResult<Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>> {
+        let response = self
+            .start_stream(
+                agent_id,
+                entrypoint_tag,
+                input_args,
+                input_kwargs,
+                user_id,
+                persistent_memory,
+            )
+            .await?;
+        Ok(self.chunk_stream(response, None, false))
+    }
+
+    /// Send the start request and wait for the event stream to open
+    pub(crate) async fn start_stream(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: &str,
+        input_args: &[Value],
+        input_kwargs: &HashMap<String, Value>,
+        user_id: Option<&str>,
+        persistent_memory: bool,
+    ) -> RunAgentResult<Response> {
+        let request_data = SocketClient::start_request(
+            entrypoint_tag,
+            input_args,
+            input_kwargs,
+            user_id,
+            persistent_memory,
+        );
+        self.rest_client
+            .open_event_stream(agent_id, &request_data)
+            .await
+    }
+
+    /// Turn an open event stream into the chunk stream `run_stream` returns
+    ///
+    /// When `cancel` fires the response is dropped, closing the connection, and
+    /// `{"type": "cancelled"}` is yielded as the final item. With `lenient`,
+    /// recoverable failures are yielded as error items and the stream goes on.
+    pub(crate) fn chunk_stream(
+        &self,
+        response: Response,
+        cancel: Option<CancellationToken>,
+        lenient: bool,
+    ) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>> {
+        let serializer = self.serializer.clone();
+
+        let stream = async_stream::stream! {
+            let mut body = response.bytes_stream();
+            let mut parser = EventParser::default();
+            'read: loop {
+                let bytes = tokio::select! {
+                    biased;
+                    _ = wait_cancelled(cancel.as_ref()) => {
+                        yield Ok(serde_json::json!({"type": "cancelled"}));
+                        break;
+                    }
+                    bytes = body.next() => bytes,
+                };
+                let bytes = match bytes {
+                    Some(Ok(bytes)) => bytes,
+                    Some(Err(e)) => {
+                        yield Err(RunAgentError::from(e));
+                        break;
+                    }
+                    // Per the SSE spec, an event cut off by the end of the body is dropped
+                    None => break,
+                };
+
+                for msg in parser.feed(&bytes) {
+                    match read_frame(&serializer, msg) {
+                        Frame::Skip => {}
+                        Frame::End => break 'read,
+                        Frame::Chunk(chunk) => yield Ok(chunk),
+                        Frame::Fail { error, recoverable } if lenient => {
+                            yield Ok(error_item(&error, recoverable));
+                            if !recoverable {
+                                break 'read;
+                            }
+                        }
+                        Frame::Fail { error, .. } => {
+                            yield Err(error);
+                            break 'read;
+                        }
+                    }
+                }
+            }
+        };
+
+        Box::pin(stream)
+    }
+}
+
+/// Incremental `text/event-stream` parser yielding each event's data as JSON
+///
+/// Events named `error` whose data is not an `{"type": ...}` frame are turned
+/// into error frames. Data that is not JSON is yielded as a string.
+#[derive(Default)]
+struct EventParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl EventParser {
+    /// Consume a chunk of the body, returning the events it completed
+    fn feed(&mut self, bytes: &[u8]) -> Vec<Value> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if let Some(event) = self.dispatch() {
+                    events.push(event);
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue; // comment, often used as a keep-alive
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => self.data.push(value.to_string()),
+                "event" => self.event = Some(value.to_string()),
+                _ => {} // `id` and `retry` only matter for EventSource reconnects
+            }
+        }
+        events
+    }
+
+    fn dispatch(&mut self) -> Option<Value> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data).join("\n");
+        let msg = serde_json::from_str(&data).unwrap_or(Value::String(data));
+        if event.as_deref() == Some("error") && msg.get("type").is_none() {
+            return Some(serde_json::json!({"type": "error", "error": msg}));
+        }
+        Some(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_parser_handles_split_and_multiline_events() {
+        let mut parser = EventParser::default();
+        assert!(parser
+            .feed(b": keep-alive\n\ndata: {\"type\": \"data\", ")
+            .is_empty());
+        assert_eq!(
+            parser.feed(b"\"content\": \"hi\"}\r\n\r\ndata: line one\ndata: line two\n\n"),
+            vec![
+                json!({"type": "data", "content": "hi"}),
+                json!("line one\nline two")
+            ]
+        );
+        assert_eq!(
+            parser.feed(b"event: error\ndata: \"quota exceeded\"\n\n"),
+            vec![json!({"type": "error", "error": "quota exceeded"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_yields_chunks() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let read = socket.read(&mut buf).await.unwrap();
+            let events = [
+                json!({"type": "status", "status": "stream_started"}),
+                json!({"type": "data", "content": "Hello"}),
+                json!({"type": "heartbeat"}),
+                json!({"type": "data", "content": " world"}),
+                json!({"type": "status", "status": "stream_completed"}),
+            ]
+            .iter()
+            .map(|event| format!("data: {}\n\n", event))
+            .collect::<String>();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\n\r\n{}",
+                events.len(),
+                events
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..read]).to_string()
+        });
+
+        let rest_client = RestClient::new(&format!("http://{}", addr), None, None).unwrap();
+        let client = SseClient::new(rest_client).unwrap();
+        let kwargs = HashMap::from([("message".to_string(), json!("hi"))]);
+        let chunks: Vec<Value> = client
+            .run_stream("agent", "chat_stream", &[], &kwargs, None, false)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(chunks, vec![json!("Hello"), json!(" world")]);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/v1/agents/agent/run-stream"));
+        assert!(request.to_lowercase().contains("accept: text/event-stream"));
+        assert!(request.contains(r#""entrypoint_tag":"chat_stream""#));
+    }
+}
@@ -0,0 +1,704 @@
+//! Adapters over streaming agent responses
+
+use crate::types::{extract_error, RunAgentError, RunAgentResult};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Extract the text carried by a stream chunk
+///
+/// Chunks are either bare strings or objects with a string `content` field; any
+/// other chunk carries no text.
+pub fn chunk_text(chunk: &Value) -> Option<&str> {
+    chunk
+        .as_str()
+        .or_else(|| chunk.get("content").and_then(|c| c.as_str()))
+}
+
+/// Lifecycle stage of a streamed agent response
+///
+/// Chunks are classified by their `type` field, following the convention agents
+/// use for structured streams:
+///
+/// | Chunk | Item |
+/// |-------|------|
+/// | `{"type": "start" \| "metadata", ...}` | `Started(chunk)` |
+/// | `{"type": "content", "content": "..."}` or a bare string | `Content(text)` |
+/// | `{"type": "complete" \| "completed" \| "done", ...}` | `Completed(chunk)` |
+/// | `{"type": "error", ...}` or a transport error | `Error(err)` |
+/// | anything else | `Data(chunk)` |
+#[derive(Debug)]
+pub enum StreamItem {
+    /// Start or metadata chunk emitted before content
+    Started(Value),
+    /// Text content
+    Content(String),
+    /// Structured chunk without a lifecycle meaning
+    Data(Value),
+    /// Final chunk, typically carrying summary fields
+    Completed(Value),
+    /// Error reported by the agent or the transport
+    Error(RunAgentError),
+}
+
+impl StreamItem {
+    /// Classify a chunk from a raw `run_stream` stream
+    pub fn from_chunk(chunk: RunAgentResult<Value>) -> Self {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => return Self::Error(e),
+        };
+        if let Some(text) = chunk.as_str() {
+            return Self::Content(text.to_string());
+        }
+
+        match chunk.get("type").and_then(|t| t.as_str()) {
+            Some("start" | "metadata") => Self::Started(chunk),
+            Some("content") => match chunk.get("content").and_then(|c| c.as_str()) {
+                Some(text) => Self::Content(text.to_string()),
+                None => Self::Data(chunk),
+            },
+            Some("complete" | "completed" | "done") => Self::Completed(chunk),
+            Some("error") => Self::Error(
+                extract_error(&chunk).unwrap_or_else(|| RunAgentError::server("Unknown error")),
+            ),
+            _ => Self::Data(chunk),
+        }
+    }
+}
+
+/// Typed view of a streaming response, yielding [`StreamItem`]s
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::{RunAgentClient, RunAgentClientConfig, StreamItem};
+/// use futures::StreamExt;
+/// use serde_json::json;
+///
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     let client = RunAgentClient::new(
+///         RunAgentClientConfig::new("agent-id", "generic_stream").with_api_key("key"),
+///     )
+///     .await?;
+///
+///     let mut stream = client.run_stream_lifecycle(&[("message", json!("Hi"))]).await?;
+///     while let Some(item) = stream.next().await {
+///         match item {
+///             StreamItem::Started(meta) => println!("started: {}", meta),
+///             StreamItem::Content(text) => print!("{}", text),
+///             StreamItem::Data(value) => println!("data: {}", value),
+///             StreamItem::Completed(summary) => println!("\ndone: {}", summary),
+///             StreamItem::Error(e) => return Err(e),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct RunStream {
+    inner: Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>,
+}
+
+impl RunStream {
+    /// Wrap a raw chunk stream, such as the one returned by `run_stream_with_args`
+    pub fn new(inner: Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>) -> Self {
+        Self { inner }
+    }
+
+    /// Get the underlying stream of raw chunks
+    pub fn into_raw(self) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>> {
+        self.inner
+    }
+}
+
+impl Stream for RunStream {
+    type Item = StreamItem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner
+            .as_mut()
+            .poll_next(cx)
+            .map(|chunk| chunk.map(StreamItem::from_chunk))
+    }
+}
+
+/// Stream chunk tagged with its arrival order and time
+///
+/// `seq` is assigned by the client, starting at 0 and counting every chunk
+/// received, so it orders chunks as they arrived rather than as the agent sent
+/// them. Errors are not numbered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequencedChunk {
+    /// Position of the chunk in the stream, from 0
+    pub seq: u64,
+    /// When the client received the chunk
+    pub received_at: Instant,
+    /// The chunk itself
+    pub data: Value,
+}
+
+impl SequencedChunk {
+    /// Time elapsed between `earlier` and this chunk
+    pub fn since(&self, earlier: &SequencedChunk) -> Duration {
+        self.received_at
+            .saturating_duration_since(earlier.received_at)
+    }
+}
+
+/// Whether a chunk has the given `type` field
+fn has_type(chunk: &Value, types: &[&str]) -> bool {
+    chunk
+        .get("type")
+        .and_then(|t| t.as_str())
+        .is_some_and(|t| types.contains(&t))
+}
+
+/// Streaming response with chunk combinators
+///
+/// Yields `RunAgentResult<T>`, where `T` is a raw `Value` until the stream is
+/// mapped or deserialized. Chunks are moved through each combinator rather than
+/// cloned, and errors always pass through unchanged. `AgentStream` implements
+/// [`Stream`], so it composes with [`futures::StreamExt`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::{AgentStream, RunAgentClient, RunAgentClientConfig};
+/// use futures::StreamExt;
+/// use serde::Deserialize;
+/// use serde_json::json;
+///
+/// #[derive(Deserialize)]
+/// struct Content {
+///     content: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     let client = RunAgentClient::new(
+///         RunAgentClientConfig::new("agent-id", "generic_stream").with_api_key("key"),
+///     )
+///     .await?;
+///
+///     let raw = client.run_stream(&[("message", json!("Hi"))]).await?;
+///     let mut stream = AgentStream::new(raw).filter_content().deserialize::<Content>();
+///     while let Some(chunk) = stream.next().await {
+///         print!("{}", chunk?.content);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct AgentStream<T = Value> {
+    inner: Pin<Box<dyn Stream<Item = RunAgentResult<T>> + Send>>,
+}
+
+impl<T: Send + 'static> AgentStream<T> {
+    /// Wrap any stream of chunk results
+    pub fn new<S>(inner: S) -> Self
+    where
+        S: Stream<Item = RunAgentResult<T>> + Send + 'static,
+    {
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+
+    /// Get the underlying boxed stream
+    pub fn into_inner(self) -> Pin<Box<dyn Stream<Item = RunAgentResult<T>> + Send>> {
+        self.inner
+    }
+}
+
+impl AgentStream<Value> {
+    /// Keep only `{"type": "content", ...}` chunks
+    pub fn filter_content(self) -> Self {
+        self.filter_chunks(|chunk| has_type(chunk, &["content"]))
+    }
+
+    /// Keep only final chunks (`type` of `complete`, `completed` or `done`)
+    pub fn only_final(self) -> Self {
+        self.filter_chunks(|chunk| has_type(chunk, &["complete", "completed", "done"]))
+    }
+
+    /// Transform each chunk with `f`, which may fail
+    pub fn map_json<U, F>(self, mut f: F) -> AgentStream<U>
+    where
+        U: Send + 'static,
+        F: FnMut(Value) -> RunAgentResult<U> + Send + 'static,
+    {
+        AgentStream::new(self.inner.map(move |item| item.and_then(&mut f)))
+    }
+
+    /// Deserialize each chunk into `U`
+    ///
+    /// A chunk that does not match `U` yields a validation error; the stream
+    /// continues with the next chunk.
+    pub fn deserialize<U>(self) -> AgentStream<U>
+    where
+        U: DeserializeOwned + Send + 'static,
+    {
+        self.map_json(|chunk| {
+            serde_json::from_value(chunk).map_err(|e| {
+                RunAgentError::validation(format!("Failed to deserialize stream chunk: {}", e))
+            })
+        })
+    }
+
+    /// Tag each chunk with its sequence number and arrival time
+    ///
+    /// Numbering happens at this point of the pipeline, so chunks removed by an
+    /// earlier filter are not counted.
+    pub fn sequenced(self) -> AgentStream<SequencedChunk> {
+        let mut next_seq = 0;
+        self.map_json(move |data| {
+            let seq = next_seq;
+            next_seq += 1;
+            Ok(SequencedChunk {
+                seq,
+                received_at: Instant::now(),
+                data,
+            })
+        })
+    }
+
+    /// Pass every chunk through and report the stream's final chunk separately
+    ///
+    /// The receiver resolves when the stream ends: to the first `complete`,
+    /// `completed` or `done` chunk, else to the `stream_end` chunk, else to a
+    /// validation error. The first stream error resolves it to that error
+    /// straight away. Dropping the stream before it ends cancels the receiver.
+    pub fn with_final(self) -> (Self, oneshot::Receiver<RunAgentResult<Value>>) {
+        let (sender, receiver) = oneshot::channel();
+        let mut inner = self.inner;
+        let stream = async_stream::stream! {
+            let mut sender = Some(sender);
+            let mut completed = None;
+            let mut ended = None;
+            while let Some(item) = inner.next().await {
+                match &item {
+                    Ok(chunk) if completed.is_none()
+                        && has_type(chunk, &["complete", "completed", "done"]) =>
+                    {
+                        completed = Some(chunk.clone());
+                    }
+                    Ok(chunk) if has_type(chunk, &["stream_end"]) => ended = Some(chunk.clone()),
+                    Err(e) => {
+                        if let Some(sender) = sender.take() {
+                            let _ = sender.send(Err(e.duplicate()));
+                        }
+                    }
+                    Ok(_) => {}
+                }
+                yield item;
+            }
+            if let Some(sender) = sender {
+                let _ = sender.send(completed.or(ended).ok_or_else(|| {
+                    RunAgentError::validation("Stream ended without a complete or stream_end chunk")
+                }));
+            }
+        };
+        (Self::new(stream), receiver)
+    }
+
+    fn filter_chunks<F>(self, keep: F) -> Self
+    where
+        F: Fn(&Value) -> bool + Send + 'static,
+    {
+        Self::new(self.inner.filter(move |item| {
+            let keep = item.as_ref().map_or(true, &keep);
+            futures::future::ready(keep)
+        }))
+    }
+}
+
+impl<T> Stream for AgentStream<T> {
+    type Item = RunAgentResult<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Completion fraction (0.0–1.0) carried by a progress chunk
+///
+/// Progress chunks have the form `{"type": "progress", "current": n, "total": m}`.
+/// Returns `None` for any other chunk, or when `total` is not positive.
+pub fn progress_fraction(chunk: &Value) -> Option<f32> {
+    if chunk.get("type").and_then(|t| t.as_str()) != Some("progress") {
+        return None;
+    }
+    let current = chunk.get("current").and_then(|c| c.as_f64())?;
+    let total = chunk.get("total").and_then(|t| t.as_f64())?;
+    if total <= 0.0 {
+        return None;
+    }
+    Some((current / total).clamp(0.0, 1.0) as f32)
+}
+
+/// Unit of text a buffered stream yields
+#[derive(Debug, Clone, Copy)]
+enum TextUnit {
+    Line,
+    Sentence,
+}
+
+impl TextUnit {
+    /// Remove and return every complete unit at the front of `buffer`
+    fn take_complete(self, buffer: &mut String) -> Vec<String> {
+        let mut pieces = Vec::new();
+        while let Some(end) = self.find_end(buffer) {
+            let piece: String = buffer.drain(..end).collect();
+            let piece = match self {
+                Self::Line => piece.trim_end_matches(['\n', '\r']).to_string(),
+                Self::Sentence => piece.trim().to_string(),
+            };
+            if matches!(self, Self::Line) || !piece.is_empty() {
+                pieces.push(piece);
+            }
+        }
+        pieces
+    }
+
+    /// Byte offset just past the end of the first complete unit, if any
+    fn find_end(self, text: &str) -> Option<usize> {
+        match self {
+            Self::Line => text.find('\n').map(|pos| pos + 1),
+            Self::Sentence => {
+                let mut chars = text.char_indices().peekable();
+                while let Some((i, c)) = chars.next() {
+                    if c == '\n' {
+                        return Some(i + 1);
+                    }
+                    // Terminal punctuation only ends a sentence once followed by
+                    // whitespace, so "3.14" or a chunk ending in "." is not split
+                    if matches!(c, '.' | '!' | '?') {
+                        if let Some(&(next_i, next)) = chars.peek() {
+                            if next.is_whitespace() {
+                                return Some(next_i);
+                            }
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Text left at stream end, if it is worth yielding
+    fn flush(self, buffer: String) -> Option<String> {
+        let rest = match self {
+            Self::Line => buffer,
+            Self::Sentence => buffer.trim().to_string(),
+        };
+        (!rest.trim().is_empty()).then_some(rest)
+    }
+}
+
+fn buffer_text<S>(
+    stream: S,
+    unit: TextUnit,
+) -> Pin<Box<dyn Stream<Item = RunAgentResult<String>> + Send>>
+where
+    S: Stream<Item = RunAgentResult<Value>> + Send + 'static,
+{
+    let stream = async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        let mut buffer = String::new();
+
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(chunk) => {
+                    if let Some(text) = chunk_text(&chunk) {
+                        buffer.push_str(text);
+                        for piece in unit.take_complete(&mut buffer) {
+                            yield Ok(piece);
+                        }
+                    }
+                }
+                Err(e) => yield Err(e),
+            }
+        }
+
+        if let Some(rest) = unit.flush(buffer) {
+            yield Ok(rest);
+        }
+    };
+
+    Box::pin(stream)
+}
+
+/// Text-buffering adapters for streams returned by `run_stream`
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::{ChunkStreamExt, RunAgentClient, RunAgentClientConfig};
+/// use futures::StreamExt;
+/// use serde_json::json;
+///
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     let client = RunAgentClient::new(
+///         RunAgentClientConfig::new("agent-id", "generic_stream").with_api_key("key"),
+///     )
+///     .await?;
+///
+///     let stream = client.run_stream(&[("message", json!("Tell me a story"))]).await?;
+///     let mut lines = stream.buffer_lines();
+///     while let Some(line) = lines.next().await {
+///         println!("{}", line?);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait ChunkStreamExt: Stream<Item = RunAgentResult<Value>> + Sized + Send + 'static {
+    /// Accumulate chunk text and yield complete lines (without the newline)
+    ///
+    /// Any trailing text without a newline is yielded when the stream ends. Errors
+    /// are passed through as they arrive; chunks without text are skipped.
+    fn buffer_lines(self) -> Pin<Box<dyn Stream<Item = RunAgentResult<String>> + Send>> {
+        buffer_text(self, TextUnit::Line)
+    }
+
+    /// Accumulate chunk text and yield complete sentences
+    ///
+    /// A sentence ends at `.`, `!` or `?` followed by whitespace, or at a newline.
+    /// Any remainder is yielded when the stream ends.
+    fn buffer_sentences(self) -> Pin<Box<dyn Stream<Item = RunAgentResult<String>> + Send>> {
+        buffer_text(self, TextUnit::Sentence)
+    }
+
+    /// Report progress chunks to `callback` and remove them from the stream
+    ///
+    /// Each `{"type": "progress", "current", "total"}` chunk fires `callback` with
+    /// the completion fraction (see [`progress_fraction`]); all other items pass
+    /// through unchanged. Streams without progress chunks never fire it.
+    fn on_progress<F>(
+        self,
+        mut callback: F,
+    ) -> Pin<Box<dyn Stream<Item = RunAgentResult<Value>> + Send>>
+    where
+        F: FnMut(f32) + Send + 'static,
+    {
+        Box::pin(self.filter_map(move |item| {
+            let progress = item.as_ref().ok().and_then(progress_fraction);
+            let item = match progress {
+                Some(fraction) => {
+                    callback(fraction);
+                    None
+                }
+                None => Some(item),
+            };
+            futures::future::ready(item)
+        }))
+    }
+}
+
+impl<S> ChunkStreamExt for S where S: Stream<Item = RunAgentResult<Value>> + Send + 'static {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RunAgentError;
+    use serde_json::json;
+
+    fn chunks(items: Vec<RunAgentResult<Value>>) -> impl Stream<Item = RunAgentResult<Value>> {
+        futures::stream::iter(items)
+    }
+
+    #[tokio::test]
+    async fn test_buffer_lines() {
+        let stream = chunks(vec![
+            Ok(json!("Hel")),
+            Ok(json!("lo\nWor")),
+            Ok(json!({"content": "ld\r\n\nTail"})),
+            Ok(json!({"type": "status"})),
+        ]);
+
+        let lines: Vec<String> = stream
+            .buffer_lines()
+            .map(|line| line.unwrap())
+            .collect()
+            .await;
+        assert_eq!(lines, vec!["Hello", "World", "", "Tail"]);
+    }
+
+    #[tokio::test]
+    async fn test_buffer_sentences() {
+        let stream = chunks(vec![
+            Ok(json!("Hi there. How")),
+            Ok(json!(" are you? Pi is 3.")),
+            Ok(json!("14! Done")),
+        ]);
+
+        let sentences: Vec<String> = stream
+            .buffer_sentences()
+            .map(|s| s.unwrap())
+            .collect()
+            .await;
+        assert_eq!(
+            sentences,
+            vec!["Hi there.", "How are you?", "Pi is 3.14!", "Done"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_passes_errors_through() {
+        let stream = chunks(vec![
+            Ok(json!("partial")),
+            Err(RunAgentError::server("boom")),
+            Ok(json!(" line\n")),
+        ]);
+
+        let items: Vec<RunAgentResult<String>> = stream.buffer_lines().collect().await;
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_err());
+        assert_eq!(items[1].as_ref().unwrap(), "partial line");
+    }
+
+    #[tokio::test]
+    async fn test_on_progress() {
+        use std::sync::{Arc, Mutex};
+
+        let stream = chunks(vec![
+            Ok(json!({"type": "progress", "current": 1, "total": 4})),
+            Ok(json!("day 1 done")),
+            Ok(json!({"type": "progress", "current": 4, "total": 4})),
+            Ok(json!({"type": "progress", "current": 1, "total": 0})),
+        ]);
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let items: Vec<Value> = stream
+            .on_progress(move |fraction| sink.lock().unwrap().push(fraction))
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(*seen.lock().unwrap(), vec![0.25, 1.0]);
+        // A progress chunk with no usable total is passed through as data
+        assert_eq!(
+            items,
+            vec![
+                json!("day 1 done"),
+                json!({"type": "progress", "current": 1, "total": 0})
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_stream_combinators() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Content {
+            content: String,
+        }
+
+        let items = || {
+            vec![
+                Ok(json!({"type": "metadata", "thread_id": "t1"})),
+                Ok(json!({"type": "content", "content": "Hel"})),
+                Err(RunAgentError::server("hiccup")),
+                Ok(json!({"type": "content", "content": "lo"})),
+                Ok(json!({"type": "complete", "thread_id": "t1"})),
+            ]
+        };
+
+        let contents: Vec<RunAgentResult<Content>> = AgentStream::new(chunks(items()))
+            .filter_content()
+            .deserialize::<Content>()
+            .collect()
+            .await;
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[0].as_ref().unwrap().content, "Hel");
+        assert!(contents[1].is_err());
+        assert_eq!(contents[2].as_ref().unwrap().content, "lo");
+
+        let finals: Vec<String> = AgentStream::new(chunks(items()))
+            .only_final()
+            .map_json(|chunk| Ok(chunk["thread_id"].as_str().unwrap_or_default().to_string()))
+            .filter_map(|item| futures::future::ready(item.ok()))
+            .collect()
+            .await;
+        assert_eq!(finals, vec!["t1"]);
+
+        let mismatch: Vec<RunAgentResult<Content>> = AgentStream::new(chunks(items()))
+            .deserialize()
+            .collect()
+            .await;
+        assert!(matches!(mismatch[0], Err(RunAgentError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_chunks() {
+        let items: Vec<RunAgentResult<SequencedChunk>> = AgentStream::new(chunks(vec![
+            Ok(json!("a")),
+            Err(RunAgentError::server("hiccup")),
+            Ok(json!("b")),
+            Ok(json!("c")),
+        ]))
+        .sequenced()
+        .collect()
+        .await;
+
+        assert!(items[1].is_err());
+        let items: Vec<SequencedChunk> = items.into_iter().filter_map(Result::ok).collect();
+        let seqs: Vec<u64> = items.iter().map(|item| item.seq).collect();
+        assert_eq!(seqs, vec![0, 1, 2]);
+        assert_eq!(items[2].data, json!("c"));
+        assert!(items[2].received_at >= items[0].received_at);
+        assert_eq!(items[0].since(&items[2]), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_with_final_reports_complete_chunk() {
+        let (stream, done) = AgentStream::new(chunks(vec![
+            Ok(json!({"type": "content", "content": "Hi"})),
+            Ok(json!({"type": "complete", "thread_id": "t1", "message_count": 2})),
+            Ok(json!({"type": "stream_end"})),
+        ]))
+        .with_final();
+        let live: Vec<RunAgentResult<Value>> = stream.collect().await;
+        assert_eq!(live.len(), 3);
+        assert_eq!(done.await.unwrap().unwrap()["thread_id"], "t1");
+
+        let (stream, done) = AgentStream::new(chunks(vec![Ok(json!("a"))])).with_final();
+        let _: Vec<_> = stream.collect().await;
+        assert_eq!(done.await.unwrap().unwrap_err().category(), "validation");
+
+        let (stream, done) = AgentStream::new(chunks(vec![
+            Err(RunAgentError::server("boom")),
+            Ok(json!({"type": "stream_end"})),
+        ]))
+        .with_final();
+        let _: Vec<_> = stream.collect().await;
+        assert_eq!(done.await.unwrap().unwrap_err().category(), "server");
+    }
+
+    #[tokio::test]
+    async fn test_run_stream_lifecycle() {
+        let stream = RunStream::new(Box::pin(chunks(vec![
+            Ok(json!({"type": "metadata", "thread_id": "t1"})),
+            Ok(json!({"type": "content", "content": "Hel"})),
+            Ok(json!("lo")),
+            Ok(json!({"type": "tool_call", "name": "search"})),
+            Ok(json!({"type": "complete", "total_length": 5})),
+            Ok(json!({"type": "error", "error": "late failure"})),
+        ])));
+
+        let items: Vec<StreamItem> = stream.collect().await;
+        assert!(matches!(&items[0], StreamItem::Started(meta) if meta["thread_id"] == "t1"));
+        assert!(matches!(&items[1], StreamItem::Content(text) if text == "Hel"));
+        assert!(matches!(&items[2], StreamItem::Content(text) if text == "lo"));
+        assert!(matches!(&items[3], StreamItem::Data(chunk) if chunk["name"] == "search"));
+        assert!(matches!(&items[4], StreamItem::Completed(done) if done["total_length"] == 5));
+        assert!(matches!(
+            &items[5],
+            StreamItem::Error(RunAgentError::Server { .. })
+        ));
+    }
+}
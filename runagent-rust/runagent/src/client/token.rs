@@ -0,0 +1,185 @@
+//! Bearer tokens fetched on demand
+//!
+//! A [`TokenProvider`] replaces a static API key for short-lived credentials
+//! (e.g. OIDC tokens): the client asks it for a token before each request and
+//! forces a refresh when the server answers 401.
+
+use crate::types::RunAgentResult;
+use futures::future::BoxFuture;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Tokens this close to expiry are refreshed instead of reused
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Token returned by a [`TokenProvider`] callback
+#[derive(Debug, Clone)]
+pub struct BearerToken {
+    /// Token sent as `Authorization: Bearer <token>`
+    pub token: String,
+    /// When the token expires; tokens without an expiry are never cached
+    pub expires_at: Option<Instant>,
+}
+
+impl BearerToken {
+    /// Token without a known expiry
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            expires_at: None,
+        }
+    }
+
+    /// Mark the token as expiring `ttl` from now
+    pub fn expires_in(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(Instant::now() + ttl);
+        self
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Instant::now() + EXPIRY_MARGIN < expires_at)
+    }
+}
+
+impl From<String> for BearerToken {
+    fn from(token: String) -> Self {
+        Self::new(token)
+    }
+}
+
+impl From<&str> for BearerToken {
+    fn from(token: &str) -> Self {
+        Self::new(token)
+    }
+}
+
+type FetchToken = dyn Fn() -> BoxFuture<'static, RunAgentResult<BearerToken>> + Send + Sync;
+
+/// Source of bearer tokens used instead of a static API key
+///
+/// The callback runs before each request unless the last token carried an
+/// expiry that is still more than 30 seconds away. Clones share the cached
+/// token, and concurrent callers wait for a single fetch.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::client::BearerToken;
+/// use runagent::{RunAgentClient, RunAgentClientConfig};
+/// use std::time::Duration;
+///
+/// async fn fetch_oidc_token() -> runagent::RunAgentResult<BearerToken> {
+///     Ok(BearerToken::new("short-lived-token").expires_in(Duration::from_secs(300)))
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     let client = RunAgentClient::new(
+///         RunAgentClientConfig::new("agent-id", "entrypoint").with_token_provider(fetch_oidc_token),
+///     )
+///     .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TokenProvider {
+    fetch: Arc<FetchToken>,
+    cached: Arc<Mutex<Option<BearerToken>>>,
+}
+
+impl TokenProvider {
+    /// Create a provider from an async callback returning a token
+    ///
+    /// The callback may return a `String` or a [`BearerToken`] with an expiry.
+    pub fn new<F, Fut, T>(fetch: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RunAgentResult<T>> + Send + 'static,
+        T: Into<BearerToken>,
+    {
+        let fetch = move || -> BoxFuture<'static, RunAgentResult<BearerToken>> {
+            let future = fetch();
+            Box::pin(async move { future.await.map(Into::into) })
+        };
+        Self {
+            fetch: Arc::new(fetch),
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Current token, fetching a new one unless the cached token is still fresh
+    pub async fn token(&self) -> RunAgentResult<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref().filter(|token| token.is_fresh()) {
+            return Ok(token.token.clone());
+        }
+        Self::fetch_into(self.fetch.as_ref(), &mut cached).await
+    }
+
+    /// Fetch a new token, discarding any cached one
+    pub async fn refresh(&self) -> RunAgentResult<String> {
+        let mut cached = self.cached.lock().await;
+        Self::fetch_into(self.fetch.as_ref(), &mut cached).await
+    }
+
+    async fn fetch_into(
+        fetch: &FetchToken,
+        cached: &mut Option<BearerToken>,
+    ) -> RunAgentResult<String> {
+        *cached = None;
+        let token = fetch().await?;
+        let value = token.token.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for TokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenProvider")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting_provider(ttl: Option<Duration>) -> (TokenProvider, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let provider = TokenProvider::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                let token = BearerToken::new(format!("token-{}", n));
+                Ok::<_, crate::RunAgentError>(match ttl {
+                    Some(ttl) => token.expires_in(ttl),
+                    None => token,
+                })
+            }
+        });
+        (provider, calls)
+    }
+
+    #[tokio::test]
+    async fn test_tokens_cached_until_expiry() {
+        let (provider, calls) = counting_provider(Some(Duration::from_secs(300)));
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(provider.refresh().await.unwrap(), "token-2");
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Within the expiry margin, and without an expiry, tokens are refetched
+        let (provider, _) = counting_provider(Some(Duration::from_secs(10)));
+        provider.token().await.unwrap();
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+        let (provider, _) = counting_provider(None);
+        provider.token().await.unwrap();
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+    }
+}
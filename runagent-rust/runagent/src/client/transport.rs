@@ -0,0 +1,78 @@
+//! Pluggable transport for REST requests
+//!
+//! A [`RestClient`](crate::RestClient) hands every built request to a
+//! [`Transport`] and reads the response it returns. The default transport is the
+//! client's `reqwest::Client`; a custom one can stand in for it, e.g. to answer
+//! requests from canned fixtures in tests. Token refresh, interceptors and
+//! error mapping still run around a custom transport.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use runagent::client::Transport;
+//! use runagent::{async_trait, RunAgentClient, RunAgentClientConfig, RunAgentResult};
+//! use std::sync::Arc;
+//!
+//! struct Canned;
+//!
+//! #[async_trait]
+//! impl Transport for Canned {
+//!     async fn execute(&self, request: reqwest::Request) -> RunAgentResult<reqwest::Response> {
+//!         let body = format!(r#"{{"success": true, "data": "{}"}}"#, request.url().path());
+//!         let response = http::Response::builder()
+//!             .status(200)
+//!             .header("Content-Type", "application/json")
+//!             .body(body)
+//!             .unwrap();
+//!         Ok(reqwest::Response::from(response))
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> RunAgentResult<()> {
+//!     let client = RunAgentClient::new(
+//!         RunAgentClientConfig::new("agent-id", "entrypoint")
+//!             .with_api_key("key")
+//!             .with_transport(Arc::new(Canned)),
+//!     )
+//!     .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::types::{RunAgentError, RunAgentResult};
+use async_trait::async_trait;
+use reqwest::{Client, Request, Response};
+use std::fmt;
+use std::time::Instant;
+
+/// Sends REST requests built by a [`RestClient`](crate::RestClient)
+///
+/// Non-2xx responses are returned as `Ok`; the client maps them to errors.
+/// Failures to get any response should be returned as `Err`.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send `request` and return the server's response
+    async fn execute(&self, request: Request) -> RunAgentResult<Response>;
+}
+
+/// The default transport: send over the HTTP client's connection pool
+#[async_trait]
+impl Transport for Client {
+    async fn execute(&self, request: Request) -> RunAgentResult<Response> {
+        let started = Instant::now();
+        Client::execute(self, request).await.map_err(|e| {
+            if e.is_timeout() {
+                RunAgentError::timeout(started.elapsed())
+            } else {
+                e.into()
+            }
+        })
+    }
+}
+
+impl fmt::Debug for dyn Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Transport")
+    }
+}
@@ -0,0 +1,224 @@
+//! Unix domain socket transport for local agents
+//!
+//! Requests keep their usual `http://localhost/...` and `ws://localhost/...` URLs;
+//! only the connection is made to the socket path instead of a TCP address.
+
+use crate::types::{RunAgentError, RunAgentResult};
+use futures::future::BoxFuture;
+use hyper::client::connect::{Connected, Connection};
+use hyper::{Body, Uri};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::UnixStream;
+use tokio_tungstenite::tungstenite::http::Request;
+use tokio_tungstenite::WebSocketStream;
+
+/// Request timeout when the request does not set one (matches the HTTP client)
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// HTTP transport sending every request over one Unix socket path
+#[derive(Clone)]
+pub(crate) struct UnixTransport {
+    client: hyper::Client<UnixConnector, Body>,
+    path: Arc<PathBuf>,
+}
+
+impl UnixTransport {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        let path = Arc::new(path);
+        let client = hyper::Client::builder().build(UnixConnector { path: path.clone() });
+        Self { client, path }
+    }
+
+    /// Socket path requests are sent to
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Send a request built with `reqwest` over the socket
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> RunAgentResult<reqwest::Response> {
+        let timeout = request
+            .timeout()
+            .copied()
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+        let mut builder = hyper::Request::builder()
+            .method(request.method().clone())
+            .uri(request.url().as_str());
+        for (name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+        let body = match request.body() {
+            None => Body::empty(),
+            Some(body) => match body.as_bytes() {
+                Some(bytes) => Body::from(bytes.to_vec()),
+                None => {
+                    return Err(RunAgentError::validation(
+                        "Streaming request bodies are not supported over a Unix socket",
+                    ))
+                }
+            },
+        };
+        let hyper_request = builder
+            .body(body)
+            .map_err(|e| RunAgentError::validation(format!("Invalid request: {}", e)))?;
+
+        let started = Instant::now();
+        let response = tokio::time::timeout(timeout, self.client.request(hyper_request))
+            .await
+            .map_err(|_| RunAgentError::timeout(started.elapsed()))?
+            .map_err(|e| {
+                RunAgentError::connection(format!(
+                    "Request over Unix socket {} failed: {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+/// Open a WebSocket over a Unix socket, sending `request` as the handshake
+pub(crate) async fn connect_websocket(
+    path: &Path,
+    request: Request<()>,
+) -> RunAgentResult<WebSocketStream<UnixStream>> {
+    let stream = UnixStream::connect(path).await.map_err(|e| {
+        RunAgentError::connection(format!(
+            "Failed to connect to Unix socket {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let (ws_stream, _) = tokio_tungstenite::client_async(request, stream)
+        .await
+        .map_err(|e| RunAgentError::connection(format!("WebSocket connection failed: {}", e)))?;
+    Ok(ws_stream)
+}
+
+/// Connector that ignores the request host and dials the socket path
+#[derive(Clone)]
+struct UnixConnector {
+    path: Arc<PathBuf>,
+}
+
+impl tower_service::Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<UnixConnection>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move {
+            UnixStream::connect(path.as_path())
+                .await
+                .map(UnixConnection)
+        })
+    }
+}
+
+/// Unix stream usable as a hyper connection
+struct UnixConnection(UnixStream);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{RestClient, SocketClient};
+    use futures::{SinkExt, StreamExt};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn test_rest_and_stream_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            // First connection: one HTTP request
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let read = stream.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..read]).to_string();
+            assert!(request.starts_with("GET /api/v1/health"));
+            let body = json!({"status": "healthy"}).to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(reply.as_bytes()).await.unwrap();
+
+            // Second connection: a WebSocket stream
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.next().await; // start request
+            let chunk = json!({"type": "data", "content": "over uds"});
+            ws.send(Message::Text(chunk.to_string())).await.unwrap();
+            ws.close(None).await.unwrap();
+        });
+
+        let rest = RestClient::new("http://localhost", None, None)
+            .unwrap()
+            .with_unix_socket(&path);
+        assert_eq!(rest.health_check().await.unwrap()["status"], "healthy");
+
+        let socket = SocketClient::new("ws://localhost", None, None)
+            .unwrap()
+            .with_unix_socket(&path);
+        let chunks: Vec<_> = socket
+            .run_stream("agent", "chat_stream", &[], &HashMap::new(), None, false)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].as_ref().unwrap(), "over uds");
+    }
+}
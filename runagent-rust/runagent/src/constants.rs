@@ -8,15 +8,69 @@ pub const ENV_RUNAGENT_API_KEY: &str = "RUNAGENT_API_KEY";
 /// Environment variable for base URL
 pub const ENV_RUNAGENT_BASE_URL: &str = "RUNAGENT_BASE_URL";
 
+/// Environment variable selecting a named config profile
+pub const ENV_RUNAGENT_PROFILE: &str = "RUNAGENT_PROFILE";
+
+/// Environment variable for the local cache directory
+pub const ENV_RUNAGENT_CACHE_DIR: &str = "RUNAGENT_CACHE_DIR";
+
+/// Environment variable for the SDK's log level (e.g. `debug`)
+pub const ENV_RUNAGENT_LOGGING_LEVEL: &str = "RUNAGENT_LOGGING_LEVEL";
+
+/// User config file name, stored in the cache directory (`~/.runagent` by default)
+pub const USER_CONFIG_FILE_NAME: &str = "config.toml";
+
 /// Default base URL for remote agents
 pub const DEFAULT_BASE_URL: &str = "https://backend.run-agent.ai";
 // pub const DEFAULT_BASE_URL: &str = "http://20.84.81.110:8335/";
 
+/// Response header carrying the regional base URL a client should switch to
+pub const REGION_URL_HEADER: &str = "X-RunAgent-Region-URL";
+
+/// Health response field carrying the regional base URL (alternative to the header)
+pub const REGION_URL_FIELD: &str = "region_url";
+
+/// Request header repeating a run body's `idempotency_key`
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
 /// Default API prefix
 pub const DEFAULT_API_PREFIX: &str = "/api/v1";
 
 /// Default timeout for agent execution (5 minutes)
 pub const DEFAULT_TIMEOUT_SECONDS: u64 = 300;
 
+/// Server-side timeout requested for streaming runs (10 minutes)
+pub const STREAM_TIMEOUT_SECONDS: u64 = 600;
+
+/// Default timeout for reaching the server during client setup
+pub const DEFAULT_CONNECT_TIMEOUT_SECONDS: u64 = 10;
+
+/// Default limit for a single response payload, in megabytes
+pub const DEFAULT_MAX_PAYLOAD_MB: f64 = 10.0;
+
+/// How long a client pool reuses a fetched agent architecture
+pub const DEFAULT_ARCHITECTURE_CACHE_TTL_SECONDS: u64 = 300;
+
+/// Largest agent folder (uncompressed) accepted for upload, in megabytes
+pub const MAX_UPLOAD_FOLDER_MB: u64 = 100;
+
+/// Smallest JSON request body gzipped when request compression is enabled, in bytes
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 16 * 1024;
+
+/// Smallest run request body serialized while it is sent rather than up front, in bytes
+pub const STREAMED_BODY_THRESHOLD_BYTES: usize = 4 * 1024 * 1024;
+
+/// Size of the pieces a streamed request body is sent in, in bytes
+pub const STREAMED_BODY_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Agents requested per page when listing an account's agents
+pub const LIST_AGENTS_PAGE_SIZE: usize = 50;
+
+/// Longest raw response body kept on a server error, in bytes
+pub const MAX_ERROR_BODY_BYTES: usize = 4096;
+
+/// File listing paths to leave out of agent uploads (gitignore syntax)
+pub const RUNAGENT_IGNORE_FILE: &str = ".runagentignore";
+
 /// Agent config file name (for reading agent configs, not for creating them)
 pub const AGENT_CONFIG_FILE_NAME: &str = "runagent.config.json";
@@ -2,8 +2,12 @@
 //!
 //! This module provides a simple database interface for looking up local agent
 //! metadata (host, port) by agent ID. This allows connecting to agents without
-//! explicitly specifying the address.
+//! explicitly specifying the address, and for recording and reading agent
+//! invocations and runs in the same tables the local server uses.
 
 pub mod service;
 
-pub use service::DatabaseService;
+pub use service::{
+    AgentFilter, AgentInfo, DatabaseService, ImportSummary, InvocationInfo, InvocationStatus,
+    MergeStrategy,
+};
@@ -1,8 +1,13 @@
 //! Database service for agent lookups
 
-use crate::types::{RunAgentError, RunAgentResult};
+use crate::types::{AgentRun, AgentRunStats, RunAgentError, RunAgentResult};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use once_cell::sync::Lazy;
-use sqlx::{sqlite::SqlitePool, Row};
+use serde_json::{json, Value};
+use sqlx::{
+    sqlite::{SqlitePool, SqliteRow},
+    Row, Sqlite, Transaction,
+};
 use std::path::PathBuf;
 
 /// Database file name
@@ -35,9 +40,170 @@ pub struct AgentInfo {
     pub status: Option<String>,
 }
 
+/// Criteria for [`DatabaseService::find_agents`]
+///
+/// Unset fields match any agent; set fields must match exactly.
+///
+/// ```rust,no_run
+/// use runagent::db::{AgentFilter, DatabaseService};
+///
+/// # async fn example() -> runagent::RunAgentResult<()> {
+/// let db = DatabaseService::new(None).await?;
+/// let agents = db
+///     .find_agents(AgentFilter::new().with_framework("langgraph").with_status("deployed"))
+///     .await?;
+/// for agent in agents {
+///     println!("{} at {}:{}", agent.agent_id, agent.host, agent.port);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgentFilter {
+    pub framework: Option<String>,
+    pub status: Option<String>,
+    pub host: Option<String>,
+}
+
+impl AgentFilter {
+    /// Create a filter matching every agent
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match agents of a framework (e.g. `langgraph`)
+    pub fn with_framework<S: Into<String>>(mut self, framework: S) -> Self {
+        self.framework = Some(framework.into());
+        self
+    }
+
+    /// Match agents with a status (e.g. `deployed`)
+    pub fn with_status<S: Into<String>>(mut self, status: S) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+
+    /// Match agents served from a host
+    pub fn with_host<S: Into<String>>(mut self, host: S) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+}
+
+/// Agent invocation recorded by the local server
+#[derive(Debug, Clone)]
+pub struct InvocationInfo {
+    pub invocation_id: String,
+    pub agent_id: String,
+    pub entrypoint_tag: Option<String>,
+    pub status: String,
+    pub input_data: String,
+    pub output_data: Option<String>,
+    pub error_detail: Option<String>,
+    pub request_timestamp: Option<String>,
+    pub response_timestamp: Option<String>,
+    pub execution_time_ms: Option<f64>,
+    pub sdk_type: Option<String>,
+    /// Client SDK name reported with the request (e.g. `rust-sdk`)
+    pub client_sdk: Option<String>,
+    /// Client SDK version reported with the request
+    pub client_version: Option<String>,
+    /// User ID the invocation ran for
+    pub user_id: Option<String>,
+    /// Client-supplied request ID, for correlating retries and logs
+    pub request_id: Option<String>,
+}
+
+/// Lifecycle state of an agent invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvocationStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl InvocationStatus {
+    /// Value stored in the `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// SDK name recorded as both `sdk_type` and `client_sdk` of invocations started
+/// from Rust, after the Python SDK's `python-sdk`
+const CLIENT_SDK: &str = "rust-sdk";
+
+/// Client metadata of an invocation, kept under these keys of its `client_info` JSON
+///
+/// Earlier SDK versions added them as `agent_invocations` columns instead; those
+/// are still read when present.
+const INVOCATION_METADATA: [&str; 4] = ["client_sdk", "client_version", "user_id", "request_id"];
+
+const INVOCATION_COLUMNS: &str = "invocation_id, agent_id, entrypoint_tag, status, input_data, \
+    output_data, error_detail, CAST(request_timestamp AS TEXT) AS request_timestamp, \
+    CAST(response_timestamp AS TEXT) AS response_timestamp, execution_time_ms, sdk_type";
+
+/// Agents allowed locally without enhanced limits, as in `get_local_db_limits`
+const DEFAULT_AGENT_LIMIT: usize = 5;
+
+/// Format version written by [`DatabaseService::export_agents`]
+const EXPORT_FORMAT_VERSION: u64 = 1;
+
+const AGENT_COLUMNS: &str = "agent_id, agent_path, host, port, framework, status";
+
+/// `agents` columns as a JSON object, for snapshots
+const AGENT_JSON: &str = "json_object('agent_id', agent_id, 'agent_path', agent_path, \
+    'host', host, 'port', port, 'framework', framework, 'status', status, \
+    'is_local', is_local, 'fingerprint', fingerprint, 'deployed_at', deployed_at, \
+    'created_at', created_at, 'updated_at', updated_at)";
+
+/// `agent_runs` columns (except the row ID) as a JSON object, for snapshots
+const AGENT_RUN_JSON: &str = "json_object('agent_id', agent_id, 'input_data', input_data, \
+    'output_data', output_data, 'success', success, 'error_message', error_message, \
+    'execution_time', execution_time, 'started_at', CAST(started_at AS TEXT), \
+    'completed_at', CAST(completed_at AS TEXT))";
+
+const AGENT_RUN_COLUMNS: &str = "id, agent_id, input_data, output_data, success, error_message, \
+    execution_time, CAST(started_at AS TEXT) AS started_at, \
+    CAST(completed_at AS TEXT) AS completed_at";
+
+/// How [`DatabaseService::import_agents`] handles an agent ID that already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the existing agent and ignore the imported one and its runs
+    Skip,
+    /// Replace the existing agent, and its runs if the snapshot has any
+    Overwrite,
+    /// Import the agent under a new ID (`<id>-imported`, `<id>-imported-2`, ...)
+    Rename,
+}
+
+/// Outcome of [`DatabaseService::import_agents`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Agents added under their own ID
+    pub imported: Vec<String>,
+    /// Existing agents replaced by the snapshot
+    pub overwritten: Vec<String>,
+    /// Agents left out because their ID already exists
+    pub skipped: Vec<String>,
+    /// Agents added under a new ID, as `(snapshot ID, new ID)`
+    pub renamed: Vec<(String, String)>,
+    /// Runs added for the imported agents
+    pub runs: usize,
+}
+
 /// Minimal database service for agent lookups
+#[derive(Clone)]
 pub struct DatabaseService {
     pool: SqlitePool,
+    agent_limit: Option<usize>,
+    /// [`INVOCATION_METADATA`] keys that also exist as `agent_invocations` columns
+    metadata_columns: Vec<&'static str>,
 }
 
 impl DatabaseService {
@@ -60,8 +226,23 @@ impl DatabaseService {
 
         // Initialize database schema
         Self::init_schema(&pool).await?;
+        let metadata_columns = Self::metadata_columns(&pool).await?;
+
+        Ok(Self {
+            pool,
+            agent_limit: Some(DEFAULT_AGENT_LIMIT),
+            metadata_columns,
+        })
+    }
 
-        Ok(Self { pool })
+    /// Set how many agents [`import_agents`](Self::import_agents) may leave in
+    /// the database (default: 5, `None` for no limit)
+    ///
+    /// Use the `max_agents` from `RestClient::get_local_db_limits` when the API
+    /// key grants enhanced limits.
+    pub fn with_agent_limit(mut self, limit: Option<usize>) -> Self {
+        self.agent_limit = limit;
+        self
     }
 
     /// Initialize database schema
@@ -87,31 +268,146 @@ impl DatabaseService {
         .await
         .map_err(|e| RunAgentError::database(format!("Failed to create schema: {}", e)))?;
 
+        // Same shape as the Python SDK's agent_invocations table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS agent_invocations (
+                invocation_id TEXT PRIMARY KEY,
+                agent_id TEXT NOT NULL REFERENCES agents(agent_id) ON DELETE CASCADE,
+                input_data TEXT NOT NULL,
+                output_data TEXT,
+                error_detail TEXT,
+                request_timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                response_timestamp DATETIME,
+                execution_time_ms FLOAT,
+                status TEXT NOT NULL DEFAULT 'pending',
+                entrypoint_tag TEXT,
+                sdk_type TEXT,
+                client_info TEXT
+            )
+            "#,
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to create schema: {}", e)))?;
+
+        // Same shape as the Python SDK's agent_runs table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS agent_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                agent_id TEXT NOT NULL REFERENCES agents(agent_id) ON DELETE CASCADE,
+                input_data TEXT NOT NULL,
+                output_data TEXT,
+                success BOOLEAN NOT NULL,
+                error_message TEXT,
+                execution_time FLOAT,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                completed_at DATETIME
+            )
+            "#,
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to create schema: {}", e)))?;
+
+        for column in ["agent_id", "started_at"] {
+            sqlx::query(&format!(
+                "CREATE INDEX IF NOT EXISTS idx_agent_runs_{0} ON agent_runs ({0})",
+                column
+            ))
+            .execute(pool)
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to create index: {}", e)))?;
+        }
+
         Ok(())
     }
 
+    /// Client metadata columns an earlier SDK version added to `agent_invocations`
+    ///
+    /// The table belongs to the Python SDK, so the client only reads them.
+    async fn metadata_columns(pool: &SqlitePool) -> RunAgentResult<Vec<&'static str>> {
+        let existing: Vec<String> =
+            sqlx::query("SELECT name FROM pragma_table_info('agent_invocations')")
+                .fetch_all(pool)
+                .await
+                .map_err(|e| RunAgentError::database(format!("Failed to inspect schema: {}", e)))?
+                .iter()
+                .map(|row| row.get("name"))
+                .collect();
+
+        Ok(INVOCATION_METADATA
+            .into_iter()
+            .filter(|key| existing.iter().any(|name| name == key))
+            .collect())
+    }
+
+    /// SQL expression reading one [`INVOCATION_METADATA`] key of an invocation
+    fn metadata_expr(&self, key: &str) -> String {
+        let from_info = format!(
+            "(CASE WHEN json_valid(client_info) \
+             THEN CAST(json_extract(client_info, '$.{}') AS TEXT) END)",
+            key
+        );
+        if self.metadata_columns.contains(&key) {
+            format!("COALESCE({}, {})", key, from_info)
+        } else {
+            from_info
+        }
+    }
+
     /// Get agent by ID
     pub async fn get_agent(&self, agent_id: &str) -> RunAgentResult<Option<AgentInfo>> {
-        let row = sqlx::query(
-            "SELECT agent_id, agent_path, host, port, framework, status FROM agents WHERE agent_id = ?"
-        )
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM agents WHERE agent_id = ?",
+            AGENT_COLUMNS
+        ))
         .bind(agent_id)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| RunAgentError::database(format!("Failed to query agent: {}", e)))?;
 
-        if let Some(row) = row {
-            Ok(Some(AgentInfo {
-                agent_id: row.get("agent_id"),
-                agent_path: row.get("agent_path"),
-                host: row.get("host"),
-                port: row.get("port"),
-                framework: row.get::<Option<String>, _>("framework"),
-                status: row.get::<Option<String>, _>("status"),
-            }))
-        } else {
-            Ok(None)
+        Ok(row.as_ref().map(agent_from_row))
+    }
+
+    /// Get every registered agent, ordered by agent ID
+    pub async fn list_agents(&self) -> RunAgentResult<Vec<AgentInfo>> {
+        self.find_agents(AgentFilter::default()).await
+    }
+
+    /// Get the agents matching every field set in `filter`, ordered by agent ID
+    pub async fn find_agents(&self, filter: AgentFilter) -> RunAgentResult<Vec<AgentInfo>> {
+        let conditions: Vec<(&str, String)> = [
+            ("framework", filter.framework),
+            ("status", filter.status),
+            ("host", filter.host),
+        ]
+        .into_iter()
+        .filter_map(|(column, value)| value.map(|value| (column, value)))
+        .collect();
+
+        let mut sql = format!("SELECT {} FROM agents", AGENT_COLUMNS);
+        if !conditions.is_empty() {
+            let clauses: Vec<String> = conditions
+                .iter()
+                .map(|(column, _)| format!("{} = ?", column))
+                .collect();
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY agent_id");
+
+        let mut query = sqlx::query(&sql);
+        for (_, value) in &conditions {
+            query = query.bind(value);
         }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to query agents: {}", e)))?;
+
+        Ok(rows.iter().map(agent_from_row).collect())
     }
 
     /// Get agent address (host, port) by ID
@@ -122,6 +418,589 @@ impl DatabaseService {
             Ok(None)
         }
     }
+
+    /// Delete an agent's local record along with its runs and invocations
+    ///
+    /// Everything is removed in one transaction. Returns `false` if no agent has
+    /// this ID.
+    pub async fn delete_agent(&self, agent_id: &str) -> RunAgentResult<bool> {
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                RunAgentError::database(format!("Failed to start transaction: {}", e))
+            })?;
+
+        for table in ["agent_runs", "agent_invocations"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE agent_id = ?", table))
+                .bind(agent_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    RunAgentError::database(format!("Failed to delete from {}: {}", table, e))
+                })?;
+        }
+        let result = sqlx::query("DELETE FROM agents WHERE agent_id = ?")
+            .bind(agent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to delete agent: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to commit deletion: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Snapshot every agent, and optionally their runs, as JSON
+    ///
+    /// The snapshot has `version`, `exported_at`, an `agents` array of full
+    /// `agents` rows and, with `include_runs`, a `runs` array of `agent_runs`
+    /// rows without their row IDs. It can be loaded with
+    /// [`import_agents`](Self::import_agents).
+    pub async fn export_agents(&self, include_runs: bool) -> RunAgentResult<Value> {
+        let mut snapshot = json!({
+            "version": EXPORT_FORMAT_VERSION,
+            "exported_at": Utc::now().to_rfc3339(),
+            "agents": self
+                .json_rows(&format!(
+                    "SELECT {} FROM agents ORDER BY agent_id",
+                    AGENT_JSON
+                ))
+                .await?,
+        });
+        if include_runs {
+            snapshot["runs"] = Value::Array(
+                self.json_rows(&format!(
+                    "SELECT {} FROM agent_runs ORDER BY agent_id, started_at, id",
+                    AGENT_RUN_JSON
+                ))
+                .await?,
+            );
+        }
+        Ok(snapshot)
+    }
+
+    /// Load agents (and runs) from an [`export_agents`](Self::export_agents) snapshot
+    ///
+    /// Agents whose ID already exists are handled by `strategy`. Runs are only
+    /// added for agents imported from the same snapshot; other runs are ignored.
+    /// Everything happens in one transaction, which is rolled back if the
+    /// import would leave more agents than the agent limit allows.
+    pub async fn import_agents(
+        &self,
+        snapshot: &Value,
+        strategy: MergeStrategy,
+    ) -> RunAgentResult<ImportSummary> {
+        if let Some(version) = snapshot.get("version") {
+            if version.as_u64() != Some(EXPORT_FORMAT_VERSION) {
+                return Err(RunAgentError::validation(format!(
+                    "Unsupported agent snapshot version: {}",
+                    version
+                )));
+            }
+        }
+        let agents = snapshot
+            .get("agents")
+            .and_then(Value::as_array)
+            .ok_or_else(|| RunAgentError::validation("Agent snapshot has no agents array"))?;
+
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                RunAgentError::database(format!("Failed to start transaction: {}", e))
+            })?;
+        let count_before = count_agents(&mut tx).await?;
+
+        let mut summary = ImportSummary::default();
+        // Snapshot agent ID -> ID it was imported under
+        let mut imported_ids = std::collections::HashMap::new();
+        for agent in agents {
+            let agent_id = agent_text(agent, "agent_id")?;
+            if agent_text(agent, "agent_path").is_err() {
+                return Err(RunAgentError::validation(format!(
+                    "Agent {} in snapshot has no agent_path",
+                    agent_id
+                )));
+            }
+
+            let target_id = if !agent_exists(&mut tx, &agent_id).await? {
+                summary.imported.push(agent_id.clone());
+                agent_id.clone()
+            } else {
+                match strategy {
+                    MergeStrategy::Skip => {
+                        summary.skipped.push(agent_id);
+                        continue;
+                    }
+                    MergeStrategy::Overwrite => {
+                        if snapshot.get("runs").is_some() {
+                            sqlx::query("DELETE FROM agent_runs WHERE agent_id = ?")
+                                .bind(&agent_id)
+                                .execute(&mut *tx)
+                                .await
+                                .map_err(|e| {
+                                    RunAgentError::database(format!(
+                                        "Failed to replace runs of {}: {}",
+                                        agent_id, e
+                                    ))
+                                })?;
+                        }
+                        summary.overwritten.push(agent_id.clone());
+                        agent_id.clone()
+                    }
+                    MergeStrategy::Rename => {
+                        let mut new_id = format!("{}-imported", agent_id);
+                        let mut suffix = 2;
+                        while agent_exists(&mut tx, &new_id).await? {
+                            new_id = format!("{}-imported-{}", agent_id, suffix);
+                            suffix += 1;
+                        }
+                        summary.renamed.push((agent_id.clone(), new_id.clone()));
+                        new_id
+                    }
+                }
+            };
+
+            upsert_agent(&mut tx, &target_id, agent).await?;
+            imported_ids.insert(agent_id, target_id);
+        }
+
+        let count_after = count_agents(&mut tx).await?;
+        if let Some(limit) = self.agent_limit {
+            if count_after > limit && count_after > count_before {
+                return Err(RunAgentError::validation(format!(
+                    "Importing would leave {} agents, over the limit of {}",
+                    count_after, limit
+                )));
+            }
+        }
+
+        let runs = snapshot
+            .get("runs")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for run in runs {
+            let Some(target_id) = run
+                .get("agent_id")
+                .and_then(Value::as_str)
+                .and_then(|agent_id| imported_ids.get(agent_id))
+            else {
+                continue;
+            };
+            insert_run(&mut tx, target_id, run).await?;
+            summary.runs += 1;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to commit import: {}", e)))?;
+        tracing::debug!("Imported agent snapshot: {:?}", summary);
+        Ok(summary)
+    }
+
+    /// Run a query selecting one JSON object per row and parse the objects
+    async fn json_rows(&self, query: &str) -> RunAgentResult<Vec<Value>> {
+        let rows = sqlx::query(query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RunAgentError::database(format!("Failed to export agents: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                serde_json::from_str(row.get(0)).map_err(|e| {
+                    RunAgentError::database(format!("Failed to read exported row: {}", e))
+                })
+            })
+            .collect()
+    }
+
+    /// Record a new pending invocation and return its ID
+    ///
+    /// The Rust SDK name and version are stored in the invocation's `client_info`.
+    pub async fn start_invocation(
+        &self,
+        agent_id: &str,
+        entrypoint_tag: Option<&str>,
+        input: &Value,
+    ) -> RunAgentResult<String> {
+        let invocation_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO agent_invocations (invocation_id, agent_id, input_data, entrypoint_tag, \
+             status, sdk_type, client_info) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&invocation_id)
+        .bind(agent_id)
+        .bind(input.to_string())
+        .bind(entrypoint_tag)
+        .bind(InvocationStatus::Pending.as_str())
+        .bind(CLIENT_SDK)
+        .bind(
+            serde_json::json!({"client_sdk": CLIENT_SDK, "client_version": crate::VERSION})
+                .to_string(),
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to start invocation: {}", e)))?;
+
+        tracing::debug!(
+            "Started invocation {} for agent {}",
+            invocation_id,
+            agent_id
+        );
+        Ok(invocation_id)
+    }
+
+    /// Finish an invocation with its output or error
+    ///
+    /// The response timestamp and execution time (since the request timestamp) are
+    /// filled in. Returns `false` if no invocation has this ID.
+    pub async fn complete_invocation(
+        &self,
+        invocation_id: &str,
+        output: Option<&Value>,
+        status: InvocationStatus,
+        error: Option<&str>,
+    ) -> RunAgentResult<bool> {
+        let result = sqlx::query(
+            "UPDATE agent_invocations SET status = ?, output_data = ?, error_detail = ?, \
+             response_timestamp = CURRENT_TIMESTAMP, \
+             execution_time_ms = (julianday('now') - julianday(request_timestamp)) * 86400000.0 \
+             WHERE invocation_id = ?",
+        )
+        .bind(status.as_str())
+        .bind(output.map(Value::to_string))
+        .bind(error)
+        .bind(invocation_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to complete invocation: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get an invocation by ID
+    pub async fn get_invocation(
+        &self,
+        invocation_id: &str,
+    ) -> RunAgentResult<Option<InvocationInfo>> {
+        Ok(self
+            .query_invocations("invocation_id", invocation_id)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Get invocations made for a user, most recent first
+    ///
+    /// Matches the `user_id` in an invocation's `client_info`. The local server
+    /// does not record one, so only invocations whose client stored it match.
+    pub async fn invocations_by_user(&self, user_id: &str) -> RunAgentResult<Vec<InvocationInfo>> {
+        self.query_invocations(&self.metadata_expr("user_id"), user_id)
+            .await
+    }
+
+    /// Get invocations carrying a client request ID, most recent first
+    ///
+    /// Matches the `request_id` in an invocation's `client_info`, like
+    /// [`invocations_by_user`](Self::invocations_by_user).
+    pub async fn invocations_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> RunAgentResult<Vec<InvocationInfo>> {
+        self.query_invocations(&self.metadata_expr("request_id"), request_id)
+            .await
+    }
+
+    /// Get an agent's runs, most recent first
+    pub async fn list_agent_runs(
+        &self,
+        agent_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> RunAgentResult<Vec<AgentRun>> {
+        self.query_agent_runs(agent_id, None, limit, offset).await
+    }
+
+    /// Get an agent's successful (or failed) runs, most recent first
+    pub async fn list_agent_runs_by_outcome(
+        &self,
+        agent_id: &str,
+        success: bool,
+        limit: usize,
+        offset: usize,
+    ) -> RunAgentResult<Vec<AgentRun>> {
+        self.query_agent_runs(agent_id, Some(success), limit, offset)
+            .await
+    }
+
+    /// Get run counts for every registered agent, ordered by agent ID
+    ///
+    /// Agents without runs are included with zero counts.
+    pub async fn agent_run_stats(&self) -> RunAgentResult<Vec<AgentRunStats>> {
+        let rows = sqlx::query(
+            "SELECT a.agent_id, COUNT(r.id) AS total_runs, \
+             COALESCE(SUM(r.success = 1), 0) AS successful_runs, \
+             COALESCE(SUM(r.success = 0), 0) AS failed_runs \
+             FROM agents a LEFT JOIN agent_runs r ON r.agent_id = a.agent_id \
+             GROUP BY a.agent_id ORDER BY a.agent_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to query run stats: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AgentRunStats {
+                agent_id: row.get("agent_id"),
+                total_runs: row.get("total_runs"),
+                successful_runs: row.get("successful_runs"),
+                failed_runs: row.get("failed_runs"),
+            })
+            .collect())
+    }
+
+    /// Get a single run by ID
+    pub async fn get_agent_run(&self, id: i64) -> RunAgentResult<Option<AgentRun>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM agent_runs WHERE id = ?",
+            AGENT_RUN_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to query agent run: {}", e)))?;
+
+        row.as_ref().map(agent_run_from_row).transpose()
+    }
+
+    async fn query_agent_runs(
+        &self,
+        agent_id: &str,
+        success: Option<bool>,
+        limit: usize,
+        offset: usize,
+    ) -> RunAgentResult<Vec<AgentRun>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM agent_runs WHERE agent_id = ? AND (? IS NULL OR success = ?) \
+             ORDER BY started_at DESC, id DESC LIMIT ? OFFSET ?",
+            AGENT_RUN_COLUMNS
+        ))
+        .bind(agent_id)
+        .bind(success)
+        .bind(success)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to query agent runs: {}", e)))?;
+
+        rows.iter().map(agent_run_from_row).collect()
+    }
+
+    /// Invocations where the SQL expression `filter` equals `value`
+    async fn query_invocations(
+        &self,
+        filter: &str,
+        value: &str,
+    ) -> RunAgentResult<Vec<InvocationInfo>> {
+        let metadata: Vec<String> = INVOCATION_METADATA
+            .iter()
+            .map(|key| format!("{} AS {}", self.metadata_expr(key), key))
+            .collect();
+        let rows = sqlx::query(&format!(
+            "SELECT {}, {} FROM agent_invocations WHERE {} = ? ORDER BY request_timestamp DESC",
+            INVOCATION_COLUMNS,
+            metadata.join(", "),
+            filter
+        ))
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to query invocations: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| InvocationInfo {
+                invocation_id: row.get("invocation_id"),
+                agent_id: row.get("agent_id"),
+                entrypoint_tag: row.get("entrypoint_tag"),
+                status: row.get("status"),
+                input_data: row.get("input_data"),
+                output_data: row.get("output_data"),
+                error_detail: row.get("error_detail"),
+                request_timestamp: row.get("request_timestamp"),
+                response_timestamp: row.get("response_timestamp"),
+                execution_time_ms: row.get("execution_time_ms"),
+                sdk_type: row.get("sdk_type"),
+                client_sdk: row.get("client_sdk"),
+                client_version: row.get("client_version"),
+                user_id: row.get("user_id"),
+                request_id: row.get("request_id"),
+            })
+            .collect())
+    }
+}
+
+/// Build an [`AgentInfo`] from a row selected with [`AGENT_COLUMNS`]
+fn agent_from_row(row: &SqliteRow) -> AgentInfo {
+    AgentInfo {
+        agent_id: row.get("agent_id"),
+        agent_path: row.get("agent_path"),
+        host: row.get("host"),
+        port: row.get("port"),
+        framework: row.get("framework"),
+        status: row.get("status"),
+    }
+}
+
+async fn count_agents(tx: &mut Transaction<'_, Sqlite>) -> RunAgentResult<usize> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM agents")
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to count agents: {}", e)))?;
+    Ok(count as usize)
+}
+
+async fn agent_exists(tx: &mut Transaction<'_, Sqlite>, agent_id: &str) -> RunAgentResult<bool> {
+    let row = sqlx::query("SELECT 1 FROM agents WHERE agent_id = ?")
+        .bind(agent_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| RunAgentError::database(format!("Failed to query agent: {}", e)))?;
+    Ok(row.is_some())
+}
+
+/// Insert a snapshot agent under `agent_id`, replacing any existing row in place
+///
+/// Missing columns get the schema defaults. Updating in place keeps the
+/// agent's invocations, which a delete would cascade to.
+async fn upsert_agent(
+    tx: &mut Transaction<'_, Sqlite>,
+    agent_id: &str,
+    agent: &Value,
+) -> RunAgentResult<()> {
+    sqlx::query(
+        "INSERT INTO agents (agent_id, agent_path, host, port, framework, status, is_local, \
+         fingerprint, deployed_at, created_at, updated_at) \
+         VALUES (?, ?, COALESCE(?, 'localhost'), COALESCE(?, 8450), ?, COALESCE(?, 'deployed'), \
+         COALESCE(?, 1), ?, ?, COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP)) \
+         ON CONFLICT(agent_id) DO UPDATE SET agent_path = excluded.agent_path, \
+         host = excluded.host, port = excluded.port, framework = excluded.framework, \
+         status = excluded.status, is_local = excluded.is_local, \
+         fingerprint = excluded.fingerprint, deployed_at = excluded.deployed_at, \
+         created_at = excluded.created_at, updated_at = excluded.updated_at",
+    )
+    .bind(agent_id)
+    .bind(agent["agent_path"].as_str())
+    .bind(agent["host"].as_str())
+    .bind(agent["port"].as_i64())
+    .bind(agent["framework"].as_str())
+    .bind(agent["status"].as_str())
+    .bind(json_flag(&agent["is_local"]))
+    .bind(agent["fingerprint"].as_str())
+    .bind(agent["deployed_at"].as_str())
+    .bind(agent["created_at"].as_str())
+    .bind(agent["updated_at"].as_str())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| RunAgentError::database(format!("Failed to import agent {}: {}", agent_id, e)))?;
+    Ok(())
+}
+
+/// Insert a snapshot run for `agent_id`
+async fn insert_run(
+    tx: &mut Transaction<'_, Sqlite>,
+    agent_id: &str,
+    run: &Value,
+) -> RunAgentResult<()> {
+    sqlx::query(
+        "INSERT INTO agent_runs (agent_id, input_data, output_data, success, error_message, \
+         execution_time, started_at, completed_at) \
+         VALUES (?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?)",
+    )
+    .bind(agent_id)
+    .bind(json_text(&run["input_data"]).unwrap_or_else(|| "{}".to_string()))
+    .bind(json_text(&run["output_data"]))
+    .bind(json_flag(&run["success"]).unwrap_or(false))
+    .bind(run["error_message"].as_str())
+    .bind(run["execution_time"].as_f64())
+    .bind(run["started_at"].as_str())
+    .bind(run["completed_at"].as_str())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| RunAgentError::database(format!("Failed to import run of {}: {}", agent_id, e)))?;
+    Ok(())
+}
+
+/// Required string field of a snapshot agent
+fn agent_text(agent: &Value, key: &str) -> RunAgentResult<String> {
+    agent
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| RunAgentError::validation(format!("Agent in snapshot has no {}", key)))
+}
+
+/// Boolean column exported as `0`/`1`, also accepting JSON booleans
+fn json_flag(value: &Value) -> Option<bool> {
+    value
+        .as_bool()
+        .or_else(|| value.as_i64().map(|flag| flag != 0))
+}
+
+/// Text column holding JSON, accepting either the stored text or parsed JSON
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Build an [`AgentRun`] from a row selected with [`AGENT_RUN_COLUMNS`]
+fn agent_run_from_row(row: &SqliteRow) -> RunAgentResult<AgentRun> {
+    let id: i64 = row.get("id");
+    let started_at = row
+        .get::<Option<String>, _>("started_at")
+        .as_deref()
+        .and_then(parse_timestamp)
+        .ok_or_else(|| {
+            RunAgentError::database(format!("Agent run {} has no valid started_at", id))
+        })?;
+
+    Ok(AgentRun {
+        id,
+        agent_id: row.get("agent_id"),
+        input_data: parse_json_column(row.get("input_data")),
+        output_data: row
+            .get::<Option<String>, _>("output_data")
+            .map(parse_json_column),
+        success: row.get("success"),
+        error_message: row.get("error_message"),
+        execution_time: row.get("execution_time"),
+        started_at,
+        completed_at: row
+            .get::<Option<String>, _>("completed_at")
+            .as_deref()
+            .and_then(parse_timestamp),
+    })
+}
+
+/// JSON stored as text, kept as a plain string if it does not parse
+fn parse_json_column(text: String) -> Value {
+    serde_json::from_str(&text).unwrap_or(Value::String(text))
+}
+
+/// Parse SQLite (`YYYY-MM-DD HH:MM:SS[.ffffff]`, UTC) or RFC 3339 timestamps
+fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .map(|naive| naive.and_utc())
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(text)
+                .ok()
+                .map(|date| date.with_timezone(&Utc))
+        })
 }
 
 impl Drop for DatabaseService {
@@ -129,3 +1008,369 @@ impl Drop for DatabaseService {
         // Note: sqlx pool handles cleanup automatically
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `agent_invocations` as created by the Python SDK
+    const PYTHON_INVOCATIONS_TABLE: &str = "CREATE TABLE agent_invocations (\
+        invocation_id TEXT PRIMARY KEY, agent_id TEXT NOT NULL, input_data TEXT NOT NULL, \
+        output_data TEXT, error_detail TEXT, \
+        request_timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP, response_timestamp DATETIME, \
+        execution_time_ms FLOAT, status TEXT NOT NULL DEFAULT 'pending', entrypoint_tag TEXT, \
+        sdk_type TEXT, client_info TEXT";
+
+    async fn invocation_table_columns(db: &DatabaseService) -> Vec<String> {
+        sqlx::query("SELECT name FROM pragma_table_info('agent_invocations')")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("name"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_invocation_metadata_leaves_python_schema_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("invocations.db");
+        std::fs::File::create(&db_path).unwrap();
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        sqlx::query(&format!("{})", PYTHON_INVOCATIONS_TABLE))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO agent_invocations (invocation_id, agent_id, input_data, sdk_type, client_info) \
+             VALUES ('server', 'a1', '{}', 'local_server', '{\"server_port\": 8450}'), \
+             ('client', 'a1', '{}', 'rust-sdk', \
+              '{\"client_sdk\": \"rust-sdk\", \"user_id\": \"u1\", \"request_id\": 7}'), \
+             ('plain', 'a1', '{}', NULL, 'not json')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let db = DatabaseService::new(Some(db_path)).await.unwrap();
+        let columns = invocation_table_columns(&db).await;
+        assert_eq!(columns.len(), 12);
+        assert!(!columns.iter().any(|name| name == "user_id"));
+
+        let by_user = db.invocations_by_user("u1").await.unwrap();
+        assert_eq!(by_user.len(), 1);
+        assert_eq!(by_user[0].invocation_id, "client");
+        assert_eq!(by_user[0].client_sdk.as_deref(), Some("rust-sdk"));
+        assert!(by_user[0].request_timestamp.is_some());
+
+        let by_request = db.invocations_by_request_id("7").await.unwrap();
+        assert_eq!(by_request[0].user_id.as_deref(), Some("u1"));
+        assert!(db.invocations_by_user("nobody").await.unwrap().is_empty());
+
+        let plain = db.get_invocation("plain").await.unwrap().unwrap();
+        assert_eq!(plain.user_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_invocation_metadata_reads_columns_of_earlier_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("invocations.db");
+        std::fs::File::create(&db_path).unwrap();
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        sqlx::query(&format!(
+            "{}, client_sdk TEXT, client_version TEXT, user_id TEXT, request_id TEXT)",
+            PYTHON_INVOCATIONS_TABLE
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO agent_invocations (invocation_id, agent_id, input_data, client_sdk, \
+             client_version, user_id, request_id) VALUES ('old', 'a1', '{}', 'rust-sdk', '0.1.0', \
+             'u1', 'req-1')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let db = DatabaseService::new(Some(db_path)).await.unwrap();
+        let by_user = db.invocations_by_user("u1").await.unwrap();
+        assert_eq!(by_user.len(), 1);
+        assert_eq!(by_user[0].client_version.as_deref(), Some("0.1.0"));
+        assert_eq!(
+            db.invocations_by_request_id("req-1").await.unwrap()[0].invocation_id,
+            "old"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_run_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("runs.db");
+        std::fs::File::create(&db_path).unwrap();
+        let db = DatabaseService::new(Some(db_path)).await.unwrap();
+        sqlx::query(
+            "INSERT INTO agents (agent_id, agent_path) VALUES ('a1', '/a1'), ('a2', '/a2')",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        for (agent_id, input, success, started_at) in [
+            ("a1", r#"{"n": 1}"#, true, "2024-01-01 10:00:00.000000"),
+            ("a1", r#"{"n": 2}"#, false, "2024-01-01 11:00:00"),
+            ("a1", r#"{"n": 3}"#, true, "2024-01-01 12:00:00.250000"),
+            ("a2", "not json", true, "2024-01-01 13:00:00"),
+        ] {
+            sqlx::query(
+                "INSERT INTO agent_runs (agent_id, input_data, success, started_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(agent_id)
+            .bind(input)
+            .bind(success)
+            .bind(started_at)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+
+        let runs = db.list_agent_runs("a1", 10, 0).await.unwrap();
+        let inputs: Vec<_> = runs.iter().map(|run| run.input_data["n"].clone()).collect();
+        assert_eq!(inputs, vec![3, 2, 1]);
+
+        let page = db.list_agent_runs("a1", 1, 1).await.unwrap();
+        assert_eq!(page[0].input_data["n"], 2);
+
+        let failed = db
+            .list_agent_runs_by_outcome("a1", false, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert!(!failed[0].success);
+        assert_eq!(
+            db.list_agent_runs_by_outcome("a1", true, 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let other = db.list_agent_runs("a2", 10, 0).await.unwrap();
+        assert_eq!(other[0].input_data, "not json");
+        let run = db.get_agent_run(other[0].id).await.unwrap().unwrap();
+        assert_eq!(run.started_at.to_rfc3339(), "2024-01-01T13:00:00+00:00");
+        assert!(db.get_agent_run(999).await.unwrap().is_none());
+
+        let stats = db.agent_run_stats().await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            (
+                stats[0].total_runs,
+                stats[0].successful_runs,
+                stats[0].failed_runs
+            ),
+            (3, 2, 1)
+        );
+        assert_eq!(stats[1].agent_id, "a2");
+
+        assert!(db.delete_agent("a1").await.unwrap());
+        assert!(db.get_agent("a1").await.unwrap().is_none());
+        assert!(db.list_agent_runs("a1", 10, 0).await.unwrap().is_empty());
+        assert_eq!(db.list_agent_runs("a2", 10, 0).await.unwrap().len(), 1);
+        assert!(!db.delete_agent("a1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invocation_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("invocations.db");
+        std::fs::File::create(&db_path).unwrap();
+        let db = DatabaseService::new(Some(db_path)).await.unwrap();
+        sqlx::query("INSERT INTO agents (agent_id, agent_path) VALUES ('a1', '/a1')")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let id = db
+            .start_invocation("a1", Some("chat"), &serde_json::json!({"message": "hi"}))
+            .await
+            .unwrap();
+        let pending = db.get_invocation(&id).await.unwrap().unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!(pending.sdk_type.as_deref(), Some(CLIENT_SDK));
+        assert_eq!(pending.client_sdk.as_deref(), Some(CLIENT_SDK));
+        assert_eq!(pending.client_version.as_deref(), Some(crate::VERSION));
+        assert!(pending.response_timestamp.is_none());
+
+        let output = serde_json::json!({"reply": "hello"});
+        assert!(db
+            .complete_invocation(&id, Some(&output), InvocationStatus::Completed, None)
+            .await
+            .unwrap());
+        let done = db.get_invocation(&id).await.unwrap().unwrap();
+        assert_eq!(done.status, "completed");
+        assert_eq!(done.output_data.as_deref(), Some(r#"{"reply":"hello"}"#));
+        assert!(done.response_timestamp.is_some());
+        assert!(done.execution_time_ms.unwrap() >= 0.0);
+
+        assert!(!db
+            .complete_invocation("missing", None, InvocationStatus::Failed, Some("boom"))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_path = dir.path().join("source.db");
+        let target_path = dir.path().join("target.db");
+        std::fs::File::create(&source_path).unwrap();
+        std::fs::File::create(&target_path).unwrap();
+
+        let source = DatabaseService::new(Some(source_path)).await.unwrap();
+        sqlx::query(
+            "INSERT INTO agents (agent_id, agent_path, port, framework) \
+             VALUES ('a1', '/a1', 8451, 'langgraph'), ('a2', '/a2', 8452, NULL)",
+        )
+        .execute(&source.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO agent_runs (agent_id, input_data, output_data, success, started_at) \
+             VALUES ('a1', '{\"n\": 1}', '{\"ok\": true}', 1, '2024-01-01 10:00:00')",
+        )
+        .execute(&source.pool)
+        .await
+        .unwrap();
+
+        let snapshot = source.export_agents(true).await.unwrap();
+        assert_eq!(snapshot["agents"].as_array().unwrap().len(), 2);
+        assert_eq!(snapshot["agents"][0]["framework"], "langgraph");
+        assert_eq!(snapshot["runs"][0]["input_data"], r#"{"n": 1}"#);
+        assert!(source
+            .export_agents(false)
+            .await
+            .unwrap()
+            .get("runs")
+            .is_none());
+
+        let target = DatabaseService::new(Some(target_path)).await.unwrap();
+        sqlx::query("INSERT INTO agents (agent_id, agent_path, port) VALUES ('a1', '/old', 9000)")
+            .execute(&target.pool)
+            .await
+            .unwrap();
+
+        let skipped = target
+            .import_agents(&snapshot, MergeStrategy::Skip)
+            .await
+            .unwrap();
+        assert_eq!(skipped.imported, vec!["a2"]);
+        assert_eq!(skipped.skipped, vec!["a1"]);
+        assert_eq!(skipped.runs, 0);
+        assert_eq!(target.get_agent("a1").await.unwrap().unwrap().port, 9000);
+
+        let overwritten = target
+            .import_agents(&snapshot, MergeStrategy::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(overwritten.overwritten, vec!["a1", "a2"]);
+        assert_eq!(target.get_agent("a1").await.unwrap().unwrap().port, 8451);
+        let runs = target.list_agent_runs("a1", 10, 0).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].output_data, Some(serde_json::json!({"ok": true})));
+
+        let renamed = target
+            .import_agents(&snapshot, MergeStrategy::Rename)
+            .await
+            .unwrap();
+        assert_eq!(
+            renamed.renamed[0],
+            ("a1".to_string(), "a1-imported".to_string())
+        );
+        assert_eq!(renamed.runs, 1);
+        assert_eq!(
+            target
+                .list_agent_runs("a1-imported", 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Four agents now; another renamed copy would exceed the default limit
+        let err = target
+            .import_agents(&snapshot, MergeStrategy::Rename)
+            .await
+            .unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert!(target.get_agent("a1-imported-2").await.unwrap().is_none());
+
+        let unlimited = target.clone().with_agent_limit(None);
+        let renamed = unlimited
+            .import_agents(&snapshot, MergeStrategy::Rename)
+            .await
+            .unwrap();
+        assert_eq!(renamed.renamed[0].1, "a1-imported-2");
+
+        assert!(target
+            .import_agents(
+                &serde_json::json!({"agents": [{"agent_id": "x"}]}),
+                MergeStrategy::Skip
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("agents.db");
+        std::fs::File::create(&db_path).unwrap();
+        let db = DatabaseService::new(Some(db_path)).await.unwrap();
+        sqlx::query(
+            "INSERT INTO agents (agent_id, agent_path, host, framework, status) VALUES \
+             ('a1', '/a1', 'localhost', 'langgraph', 'deployed'), \
+             ('a2', '/a2', '10.0.0.2', 'langgraph', 'stopped'), \
+             ('a3', '/a3', 'localhost', 'crewai', 'deployed')",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let ids = |agents: Vec<AgentInfo>| -> Vec<String> {
+            agents.into_iter().map(|agent| agent.agent_id).collect()
+        };
+        assert_eq!(ids(db.list_agents().await.unwrap()), ["a1", "a2", "a3"]);
+        assert_eq!(
+            ids(db
+                .find_agents(AgentFilter::new().with_framework("langgraph"))
+                .await
+                .unwrap()),
+            ["a1", "a2"]
+        );
+        assert_eq!(
+            ids(db
+                .find_agents(
+                    AgentFilter::new()
+                        .with_status("deployed")
+                        .with_host("localhost")
+                )
+                .await
+                .unwrap()),
+            ["a1", "a3"]
+        );
+        // Values are bound, not spliced into the SQL
+        assert!(db
+            .find_agents(AgentFilter::new().with_framework("x' OR '1'='1"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! Serve agent streams from axum handlers as server-sent events
+//!
+//! [`sse_response`] turns the stream returned by
+//! [`RunAgentClient::run_stream`] into an axum [`Sse`] response, and
+//! [`stream_response`] also starts the run. Events are framed as:
+//!
+//! | Event | Data |
+//! |-------|------|
+//! | `chunk` | a stream chunk, as JSON |
+//! | `error` | `{"type": "error", "message", "category", "recoverable"}` |
+//! | `done` | `{}`, once the agent's stream completes |
+//!
+//! A failed stream ends with an unrecoverable `error` event and no `done`.
+//! In-band error items of [`RunAgentClient::run_stream_lenient`] streams are
+//! sent as `error` events without ending the response. Keep-alive comments are
+//! sent while the agent is quiet.
+//!
+//! The agent stream is owned by the response body, not by a spawned task, so
+//! when the browser disconnects axum drops the body and with it the agent
+//! stream, closing its connection.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use axum::{extract::State, routing::post, Json, Router};
+//! use runagent::integrations::axum::stream_response;
+//! use runagent::RunAgentClient;
+//! use serde_json::Value;
+//!
+//! async fn chat(
+//!     State(client): State<RunAgentClient>,
+//!     Json(body): Json<Value>,
+//! ) -> impl axum::response::IntoResponse {
+//!     stream_response(&client, &[("message", body["message"].clone())]).await
+//! }
+//!
+//! # async fn app(client: RunAgentClient) {
+//! let app: Router = Router::new().route("/chat", post(chat)).with_state(client);
+//! # }
+//! ```
+
+use crate::client::socket_client::error_item;
+use crate::types::RunAgentResult;
+use crate::RunAgentClient;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{Stream, StreamExt};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::pin::Pin;
+
+/// Event stream of an [`Sse`] response built from an agent stream
+pub type SseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// Serve an agent stream as server-sent events
+///
+/// See the [module documentation](self) for the event framing.
+pub fn sse_response<S>(stream: S) -> Sse<SseStream>
+where
+    S: Stream<Item = RunAgentResult<Value>> + Send + 'static,
+{
+    let events = async_stream::stream! {
+        futures::pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(chunk) if is_error_item(&chunk) => yield event("error", &chunk),
+                Ok(chunk) => yield event("chunk", &chunk),
+                Err(e) => {
+                    yield event("error", &error_item(&e, false));
+                    return;
+                }
+            }
+        }
+        yield Event::default().event("done").data("{}");
+    };
+    let events: SseStream = Box::pin(events.map(Ok));
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Start a streaming run and serve it as server-sent events
+///
+/// A run that fails to start is reported as a single `error` event, so the
+/// handler always answers with an event stream.
+pub async fn stream_response(
+    client: &RunAgentClient,
+    input_kwargs: &[(&str, Value)],
+) -> Sse<SseStream> {
+    match client.run_stream(input_kwargs).await {
+        Ok(stream) => sse_response(stream),
+        Err(e) => sse_response(futures::stream::iter([Err(e)])),
+    }
+}
+
+fn event(name: &str, data: &Value) -> Event {
+    Event::default().event(name).data(data.to_string())
+}
+
+fn is_error_item(chunk: &Value) -> bool {
+    chunk.get("type").and_then(Value::as_str) == Some("error") && chunk.get("category").is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RunAgentError;
+    use axum::response::IntoResponse;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    async fn body_text(sse: Sse<SseStream>) -> String {
+        let body = sse.into_response().into_body();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_frames_chunks_and_errors() {
+        let lenient_error = json!({"type": "error", "message": "m", "category": "server"});
+        let completed = futures::stream::iter(vec![
+            Ok(json!("Hel")),
+            Ok(lenient_error.clone()),
+            Ok(json!({"text": "lo"})),
+        ]);
+        assert_eq!(
+            body_text(sse_response(completed)).await,
+            format!(
+                "event: chunk\ndata: \"Hel\"\n\nevent: error\ndata: {}\n\n\
+                 event: chunk\ndata: {{\"text\":\"lo\"}}\n\nevent: done\ndata: {{}}\n\n",
+                lenient_error
+            )
+        );
+
+        let failed = futures::stream::iter(vec![
+            Ok(json!("partial")),
+            Err(RunAgentError::connection("dropped")),
+            Ok(json!("never sent")),
+        ]);
+        let text = body_text(sse_response(failed)).await;
+        assert!(text.starts_with("event: chunk\ndata: \"partial\"\n\nevent: error\n"));
+        assert!(text.contains(r#""category":"connection""#));
+        assert!(!text.contains("never sent") && !text.contains("event: done"));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_response_drops_agent_stream() {
+        /// Sets its flag when dropped along with the stream owning it
+        struct DropFlag(Arc<AtomicBool>);
+
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+        let agent_stream = futures::stream::pending::<RunAgentResult<Value>>().map(move |item| {
+            let _ = &flag;
+            item
+        });
+
+        let response = sse_response(agent_stream).into_response();
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(response); // what axum does when the client disconnects
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
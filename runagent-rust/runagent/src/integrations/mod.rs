@@ -0,0 +1,6 @@
+//! Integrations with web frameworks
+//!
+//! Each integration is behind the feature of the same name.
+
+#[cfg(feature = "axum")]
+pub mod axum;
@@ -203,7 +203,7 @@
 //!
 //
 //! ```rust,no_run
-//! use runagent::{RunAgentError, RunAgentResult};
+//! use runagent::{ErrorCode, RunAgentError, RunAgentResult};
 //!
 //! fn handle_errors() -> RunAgentResult<()> {
 //!     // Your operation here
@@ -225,6 +225,11 @@
 //!                 Err(RunAgentError::connection("Connection failed"))
 //!             }
 //!         }
+//!         // Branch on the machine-readable code instead of the message text
+//!         Err(e) if e.error_code() == Some(ErrorCode::PermissionDenied) => {
+//!             eprintln!("This API key cannot access the agent: {}", e);
+//!             Err(e)
+//!         }
 //!         Err(e) => Err(e),
 //!     }
 //! }
@@ -255,6 +260,15 @@ pub mod utils;
 #[cfg(feature = "db")]
 pub mod db;
 
+#[cfg(feature = "otel")]
+pub mod telemetry;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "axum")]
+pub mod integrations;
+
 /// Blocking (synchronous) wrapper for RunAgentClient
 ///
 /// This module provides a synchronous interface that wraps the async client.
@@ -279,8 +293,14 @@ pub mod db;
 pub mod blocking;
 
 // Re-export commonly used types and functions
-pub use client::{RestClient, RunAgentClient, RunAgentClientConfig, SocketClient};
-pub use types::{RunAgentError, RunAgentResult};
+pub use async_trait::async_trait;
+pub use client::{
+    AgentStream, ChunkStreamExt, RestClient, RetryPolicy, RunAgentClient, RunAgentClientConfig,
+    RunAgentClientPool, RunStream, SequencedChunk, SocketClient, SocketClientConfig, SseClient,
+    StreamItem, StreamPolicy, StreamTransport,
+};
+pub use tokio_util::sync::CancellationToken;
+pub use types::{ErrorCode, RunAgentError, RunAgentResult};
 
 // Re-export blocking client for convenience
 pub use blocking::{BlockingStream, RunAgentClient as BlockingRunAgentClient};
@@ -288,6 +308,9 @@ pub use blocking::{BlockingStream, RunAgentClient as BlockingRunAgentClient};
 #[cfg(feature = "db")]
 pub use db::DatabaseService;
 
+#[cfg(feature = "otel")]
+pub use telemetry::{init_telemetry, shutdown_telemetry};
+
 // Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -304,13 +327,55 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// ```
 pub fn init_logging() {
     tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("runagent=info".parse().unwrap()),
-        )
+        .with_env_filter(logging_filter())
         .init();
 }
 
+/// Initialize logging for the RunAgent SDK as one JSON object per line
+///
+/// Each line carries the event's fields and the current span with its fields,
+/// such as `agent_id` and `entrypoint_tag` on `runagent.run`, which suits log
+/// collectors in containerized deployments. Levels are configured like
+/// [`init_logging`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// runagent::init_json_logging();
+/// tracing::info!("RunAgent SDK initialized");
+/// ```
+pub fn init_json_logging() {
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    json_subscriber(std::io::stdout).init();
+}
+
+/// JSON formatting subscriber writing to `make_writer`
+fn json_subscriber<W>(make_writer: W) -> impl tracing::Subscriber + Send + Sync
+where
+    W: for<'writer> tracing_subscriber::fmt::MakeWriter<'writer> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(false)
+        .with_env_filter(logging_filter())
+        .with_writer(make_writer)
+        .finish()
+}
+
+/// `RUST_LOG` directives, with the SDK's own level from `RUNAGENT_LOGGING_LEVEL`
+/// (default: `info`)
+fn logging_filter() -> tracing_subscriber::EnvFilter {
+    let level = std::env::var(constants::ENV_RUNAGENT_LOGGING_LEVEL)
+        .map(|level| level.trim().to_lowercase())
+        .unwrap_or_default();
+    let directive = format!("runagent={}", level)
+        .parse()
+        .unwrap_or_else(|_| "runagent=info".parse().unwrap());
+    tracing_subscriber::EnvFilter::from_default_env().add_directive(directive)
+}
+
 /// Configuration builder for the RunAgent SDK
 ///
 /// Provides a fluent interface for configuring the SDK with various options
@@ -335,6 +400,8 @@ pub struct RunAgentConfig {
     pub base_url: Option<String>,
     /// Whether to enable logging
     pub enable_logging: bool,
+    /// Whether logging (when enabled) writes JSON lines instead of text
+    pub json_logging: bool,
 }
 
 impl RunAgentConfig {
@@ -400,6 +467,23 @@ impl RunAgentConfig {
         self
     }
 
+    /// Enable logging initialization with JSON output
+    ///
+    /// When enabled, the `build()` method will initialize logging with
+    /// [`init_json_logging`] instead of [`init_logging`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use runagent::RunAgentConfig;
+    /// let config = RunAgentConfig::new().with_json_logging();
+    /// ```
+    pub fn with_json_logging(mut self) -> Self {
+        self.enable_logging = true;
+        self.json_logging = true;
+        self
+    }
+
     /// Build the configuration and optionally initialize logging
     ///
     /// If logging was enabled via `with_logging()`, this will initialize
@@ -415,7 +499,11 @@ impl RunAgentConfig {
     /// ```
     pub fn build(self) -> Self {
         if self.enable_logging {
-            init_logging();
+            if self.json_logging {
+                init_json_logging();
+            } else {
+                init_logging();
+            }
         }
         self
     }
@@ -434,8 +522,11 @@ impl RunAgentConfig {
 /// // Now you have access to RunAgentClient, RunAgentError, etc.
 /// ```
 pub mod prelude {
-    pub use crate::client::{RestClient, RunAgentClient, RunAgentClientConfig, SocketClient};
-    pub use crate::types::{RunAgentError, RunAgentResult};
+    pub use crate::client::{
+        AgentStream, ChunkStreamExt, RestClient, RunAgentClient, RunAgentClientConfig, RunStream,
+        SocketClient, StreamItem,
+    };
+    pub use crate::types::{ErrorCode, RunAgentError, RunAgentResult};
 
     #[cfg(feature = "db")]
     pub use crate::db::DatabaseService;
@@ -469,5 +560,40 @@ mod tests {
         assert!(config.api_key.is_none());
         assert!(config.base_url.is_none());
         assert!(!config.enable_logging);
+        assert!(!config.json_logging);
+    }
+
+    #[test]
+    fn test_json_logging_includes_span_fields() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        tracing::subscriber::with_default(json_subscriber(move || writer.clone()), || {
+            let span =
+                tracing::info_span!("runagent.run", agent_id = "a1", entrypoint_tag = "chat");
+            let _entered = span.enter();
+            tracing::info!(chunks = 3, "run finished");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["fields"]["message"], "run finished");
+        assert_eq!(line["span"]["agent_id"], "a1");
+        assert_eq!(line["span"]["entrypoint_tag"], "chat");
     }
 }
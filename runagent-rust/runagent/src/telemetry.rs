@@ -0,0 +1,63 @@
+//! OpenTelemetry export of the SDK's tracing spans (`otel` feature)
+//!
+//! Client calls emit `runagent.run`, `runagent.run_stream` and `runagent.http`
+//! spans carrying `agent_id`, `entrypoint_tag` and `local`; [`init_telemetry`]
+//! ships them to an OTLP collector over HTTP.
+
+use crate::types::{RunAgentError, RunAgentResult};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Initialize logging and export spans to an OTLP/HTTP collector
+///
+/// Installs the same filtered fmt output as [`init_logging`](crate::init_logging)
+/// plus an OpenTelemetry layer. Spans are batched on the Tokio runtime, so this
+/// must be called from within one; call [`shutdown_telemetry`] before exiting to
+/// flush pending spans.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// #[tokio::main]
+/// async fn main() -> runagent::RunAgentResult<()> {
+///     runagent::init_telemetry("http://localhost:4318/v1/traces", "lead-agent")?;
+///     // ... use RunAgentClient ...
+///     runagent::shutdown_telemetry();
+///     Ok(())
+/// }
+/// ```
+pub fn init_telemetry(endpoint: &str, service_name: &str) -> RunAgentResult<()> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|e| RunAgentError::config(format!("Failed to set up OTLP exporter: {}", e)))?;
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("runagent=info".parse().unwrap()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| RunAgentError::config(format!("Failed to install tracing subscriber: {}", e)))
+}
+
+/// Flush pending spans and shut down the exporter
+pub fn shutdown_telemetry() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
@@ -0,0 +1,298 @@
+//! In-memory agent for testing code that uses [`RunAgentClient`](crate::RunAgentClient)
+//!
+//! [`MockAgent`] is a [`Transport`] that answers the client's requests from canned
+//! responses and streams keyed by entrypoint tag, so tests need no server and no
+//! network. [`MockAgent::config`] builds a client config wired to it; streams are
+//! served as server-sent events, since WebSocket streams do not go through the
+//! transport.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures::StreamExt;
+//! use runagent::testing::MockAgent;
+//! use runagent::RunAgentClient;
+//! use serde_json::json;
+//!
+//! #[tokio::main]
+//! async fn main() -> runagent::RunAgentResult<()> {
+//!     let agent = MockAgent::new()
+//!         .respond("chat", json!({"response": "Hi!"}))
+//!         .stream("chat_stream", vec![json!("Hel"), json!("lo")]);
+//!
+//!     let client = RunAgentClient::new(agent.config("agent-id", "chat")).await?;
+//!     assert_eq!(client.run(&[("message", json!("Hello"))]).await?["response"], "Hi!");
+//!     assert_eq!(agent.requests()[0]["input_kwargs"]["message"], "Hello");
+//!
+//!     let stream = client.with_entrypoint("chat_stream")?.run_stream(&[]).await?;
+//!     let chunks: Vec<_> = stream.map(Result::unwrap).collect().await;
+//!     assert_eq!(chunks, vec![json!("Hel"), json!("lo")]);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::client::{StreamTransport, Transport};
+use crate::types::{RunAgentError, RunAgentResult};
+use crate::RunAgentClientConfig;
+use async_trait::async_trait;
+use reqwest::{Method, Request, Response};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Base URL of clients built by [`MockAgent::config`]; never contacted
+const MOCK_BASE_URL: &str = "http://mock.runagent.invalid";
+
+/// Canned answer for one entrypoint
+#[derive(Debug, Clone)]
+enum Reply {
+    Respond(Value),
+    Fail(String),
+    Stream(Vec<Value>),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    replies: BTreeMap<String, Reply>,
+    requests: Vec<Value>,
+}
+
+/// A fake agent answering a client's requests in memory
+///
+/// Clones share their replies and recorded requests, so a test can keep one
+/// handle for assertions after passing another to the client.
+///
+/// Served requests:
+/// - `GET health`: healthy;
+/// - `GET agents/{id}/architecture`: an entrypoint for every registered tag;
+/// - `POST agents/{id}/run`: the reply registered for the request's tag;
+/// - `POST agents/{id}/run-stream`: the registered chunks as server-sent events.
+///
+/// Runs of unregistered tags fail with an `ENTRYPOINT_NOT_FOUND` error; other
+/// paths get a 404.
+#[derive(Debug, Clone, Default)]
+pub struct MockAgent {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockAgent {
+    /// Create an agent with no entrypoints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer runs of `tag` with `value`
+    pub fn respond(self, tag: impl Into<String>, value: Value) -> Self {
+        self.reply(tag, Reply::Respond(value))
+    }
+
+    /// Fail runs of `tag` with a server error carrying `message`
+    pub fn fail(self, tag: impl Into<String>, message: impl Into<String>) -> Self {
+        self.reply(tag, Reply::Fail(message.into()))
+    }
+
+    /// Answer streaming runs of `tag` with `chunks`, then complete the stream
+    pub fn stream(self, tag: impl Into<String>, chunks: Vec<Value>) -> Self {
+        self.reply(tag, Reply::Stream(chunks))
+    }
+
+    /// Run requests received so far, oldest first
+    ///
+    /// Each is the JSON body the client sent, with `entrypoint_tag`,
+    /// `input_args` and `input_kwargs`.
+    pub fn requests(&self) -> Vec<Value> {
+        self.lock().requests.clone()
+    }
+
+    /// Config for a remote client whose requests this agent answers
+    ///
+    /// Uses a placeholder API key and base URL, server-sent event streams and
+    /// no local registry. Further builders may be chained.
+    pub fn config(
+        &self,
+        agent_id: impl Into<String>,
+        entrypoint_tag: impl Into<String>,
+    ) -> RunAgentClientConfig {
+        RunAgentClientConfig::remote(agent_id, entrypoint_tag, "mock-key")
+            .with_base_url(MOCK_BASE_URL)
+            .with_transport(Arc::new(self.clone()))
+            .with_stream_transport(StreamTransport::Sse)
+    }
+
+    fn reply(self, tag: impl Into<String>, reply: Reply) -> Self {
+        self.lock().replies.insert(tag.into(), reply);
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn architecture(&self, agent_id: &str) -> Value {
+        let entrypoints: Vec<Value> = self
+            .lock()
+            .replies
+            .keys()
+            .map(|tag| json!({"file": "mock.py", "module": tag, "tag": tag}))
+            .collect();
+        json!({"success": true, "data": {"agent_id": agent_id, "entrypoints": entrypoints}})
+    }
+
+    /// Record a run request and find the reply for its tag
+    fn run(&self, request: &Request) -> Result<Reply, Value> {
+        let body: Value = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok())
+            .ok_or_else(|| failure("INVALID_REQUEST", "MockAgent reads JSON run requests only"))?;
+        let tag = body["entrypoint_tag"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let mut state = self.lock();
+        state.requests.push(body);
+        state.replies.get(&tag).cloned().ok_or_else(|| {
+            failure(
+                "ENTRYPOINT_NOT_FOUND",
+                &format!("Entrypoint `{}` is not mocked", tag),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for MockAgent {
+    async fn execute(&self, request: Request) -> RunAgentResult<Response> {
+        let path = request.url().path().to_string();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let (status, content_type, body) = match (request.method(), segments.as_slice()) {
+            (&Method::GET, [.., "health"]) => json_reply(json!({"status": "healthy"})),
+            (&Method::GET, [.., "agents", agent_id, "architecture"]) => {
+                json_reply(self.architecture(agent_id))
+            }
+            (&Method::POST, [.., "agents", _, "run"]) => match self.run(&request) {
+                Ok(Reply::Respond(value)) => json_reply(json!({"success": true, "data": value})),
+                Ok(Reply::Fail(message)) => json_reply(failure("AGENT_ERROR", &message)),
+                Ok(Reply::Stream(_)) => json_reply(failure(
+                    "STREAM_ENTRYPOINT",
+                    "Streaming entrypoints must be run with run_stream",
+                )),
+                Err(error) => json_reply(error),
+            },
+            (&Method::POST, [.., "agents", _, "run-stream"]) => {
+                let events = match self.run(&request) {
+                    Ok(Reply::Stream(chunks)) => event_stream(chunks),
+                    Ok(Reply::Fail(message)) => stream_error(&message),
+                    Ok(Reply::Respond(_)) => {
+                        stream_error("Non-streaming entrypoints must be run with run")
+                    }
+                    Err(error) => stream_error(error["error"]["message"].as_str().unwrap_or("")),
+                };
+                (200, "text/event-stream", events)
+            }
+            _ => (
+                404,
+                "application/json",
+                json!({"detail": "Not found"}).to_string(),
+            ),
+        };
+        let response = http::Response::builder()
+            .status(status)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .map_err(|e| RunAgentError::connection(format!("Invalid mock response: {}", e)))?;
+        Ok(Response::from(response))
+    }
+}
+
+fn json_reply(body: Value) -> (u16, &'static str, String) {
+    (200, "application/json", body.to_string())
+}
+
+fn failure(code: &str, message: &str) -> Value {
+    json!({"success": false, "error": {"code": code, "message": message}})
+}
+
+/// Server-sent events carrying `chunks` as data frames, then completion
+fn event_stream(chunks: Vec<Value>) -> String {
+    event_stream_of(
+        chunks
+            .into_iter()
+            .map(|chunk| json!({"type": "data", "content": chunk}))
+            .chain([json!({"type": "status", "status": "stream_completed"})]),
+    )
+}
+
+/// A server-sent error event ending the stream
+fn stream_error(message: &str) -> String {
+    event_stream_of([json!({"type": "error", "error": message})])
+}
+
+fn event_stream_of(events: impl IntoIterator<Item = Value>) -> String {
+    events
+        .into_iter()
+        .map(|event| format!("data: {}\n\n", event))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RunAgentClient;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_mock_agent_serves_runs_and_streams() {
+        let agent = MockAgent::new()
+            .respond("chat", json!({"response": "Hi!"}))
+            .fail("broken", "tool call failed")
+            .stream("chat_stream", vec![json!("Hel"), json!({"text": "lo"})]);
+        let client = RunAgentClient::new(agent.config("agent-id", "chat"))
+            .await
+            .unwrap();
+
+        let result = client.run(&[("message", json!("Hello"))]).await.unwrap();
+        assert_eq!(result, json!({"response": "Hi!"}));
+
+        let err = client
+            .with_entrypoint("broken")
+            .unwrap()
+            .run(&[])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("tool call failed"));
+
+        let chunks: Vec<Value> = client
+            .with_entrypoint("chat_stream")
+            .unwrap()
+            .run_stream(&[])
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(chunks, vec![json!("Hel"), json!({"text": "lo"})]);
+
+        let requests = agent.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0]["input_kwargs"]["message"], "Hello");
+        assert_eq!(requests[2]["entrypoint_tag"], "chat_stream");
+
+        let err = RunAgentClient::new(agent.config("agent-id", "missing"))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_agent_client_keeps_its_base_url() {
+        let agent = MockAgent::new().respond("chat", json!("ok"));
+        let client = RunAgentClient::new(agent.config("agent-id", "chat"))
+            .await
+            .unwrap();
+
+        assert_eq!(client.region_base_url(), None);
+        assert_eq!(client.endpoint_key(), MOCK_BASE_URL);
+    }
+}
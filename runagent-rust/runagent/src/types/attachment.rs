@@ -0,0 +1,209 @@
+//! Binary attachments sent with agent runs
+//!
+//! [`Attachment`]s reach the agent either inline, as base64 objects in the
+//! `attachments` kwarg, or as file parts of a `multipart/form-data` run request.
+//! See [`AttachmentEncoding`].
+
+use crate::types::{RunAgentError, RunAgentResult};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+/// Kwarg holding base64-encoded attachments
+pub const ATTACHMENTS_KWARG: &str = "attachments";
+
+/// A named file sent to or returned by an agent, such as a PDF or an image
+#[derive(Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// File name, e.g. `report.pdf`
+    pub name: String,
+    /// MIME type, e.g. `application/pdf`
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Attachment {
+    /// Create an attachment from in-memory bytes
+    pub fn new(
+        name: impl Into<String>,
+        mime: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mime: mime.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Read an attachment from a file, named after the file
+    pub fn from_file(path: impl AsRef<Path>, mime: impl Into<String>) -> RunAgentResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            RunAgentError::validation(format!(
+                "Failed to read attachment {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self::new(name, mime, bytes))
+    }
+
+    /// Size in bytes, before any encoding
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the attachment has no content
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Base64 form sent in the `attachments` kwarg
+    ///
+    /// `{"name", "mime", "size", "encoding": "base64", "data"}`, where `size` is
+    /// the decoded length.
+    pub fn to_value(&self) -> Value {
+        serde_json::json!({
+            "name": self.name,
+            "mime": self.mime,
+            "size": self.bytes.len(),
+            "encoding": "base64",
+            "data": BASE64.encode(&self.bytes),
+        })
+    }
+
+    /// Decode a file an agent returned in the form of [`Attachment::to_value`]
+    ///
+    /// `mime` defaults to `application/octet-stream` and `encoding`, when
+    /// present, must be `base64`. Returns `None` for values of any other shape.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let encoding = value.get("encoding").and_then(Value::as_str);
+        if encoding.is_some_and(|encoding| encoding != "base64") {
+            return None;
+        }
+        let bytes = BASE64.decode(value.get("data")?.as_str()?).ok()?;
+        Some(Self::new(
+            value.get("name")?.as_str()?,
+            value
+                .get("mime")
+                .and_then(Value::as_str)
+                .unwrap_or("application/octet-stream"),
+            bytes,
+        ))
+    }
+}
+
+impl fmt::Debug for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Attachment")
+            .field("name", &self.name)
+            .field("mime", &self.mime)
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// How [`Attachment`]s are sent with a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AttachmentEncoding {
+    /// Base64 objects in the `attachments` kwarg of the JSON run request
+    /// (default; accepted by every server)
+    ///
+    /// Encoding grows each attachment by a third, and the whole body counts
+    /// toward the request size limit.
+    #[default]
+    Base64,
+    /// A `multipart/form-data` run request: the JSON run request in a `request`
+    /// part and each attachment as an `attachments` file part
+    ///
+    /// For servers that accept multipart runs. The body is built in memory and
+    /// counts toward the request size limit.
+    Multipart,
+}
+
+/// A `multipart/form-data` run request body
+pub(crate) struct MultipartBody {
+    pub(crate) content_type: String,
+    pub(crate) bytes: Vec<u8>,
+}
+
+impl MultipartBody {
+    /// Encode the JSON run request and its attachments
+    pub(crate) fn new(request: &Value, attachments: &[Attachment]) -> RunAgentResult<Self> {
+        let boundary = format!("runagent-{}", uuid::Uuid::new_v4().simple());
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"request\"\r\n\
+                 Content-Type: application/json\r\n\r\n",
+                boundary
+            )
+            .as_bytes(),
+        );
+        serde_json::to_writer(&mut bytes, request)?;
+        for attachment in attachments {
+            bytes.extend_from_slice(
+                format!(
+                    "\r\n--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: {}\r\n\r\n",
+                    boundary,
+                    ATTACHMENTS_KWARG,
+                    quote_filename(&attachment.name),
+                    attachment.mime
+                )
+                .as_bytes(),
+            );
+            bytes.extend_from_slice(&attachment.bytes);
+        }
+        bytes.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+        Ok(Self {
+            content_type: format!("multipart/form-data; boundary={}", boundary),
+            bytes,
+        })
+    }
+}
+
+/// Escape a file name for a `Content-Disposition` header, as browsers do
+fn quote_filename(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_encodings() {
+        let attachment = Attachment::new("scan \"1\".png", "image/png", vec![0u8, 159, 146, 150]);
+        let value = attachment.to_value();
+        assert_eq!(value["data"], "AJ+Slg==");
+        assert_eq!(value["size"], 4);
+        assert_eq!(Attachment::from_value(&value), Some(attachment.clone()));
+        assert_eq!(
+            Attachment::from_value(
+                &serde_json::json!({"name": "a", "encoding": "hex", "data": "00"})
+            ),
+            None
+        );
+
+        let body = MultipartBody::new(&serde_json::json!({"entrypoint_tag": "ocr"}), &[attachment])
+            .unwrap();
+        let boundary = body.content_type.split("boundary=").nth(1).unwrap();
+        let text = String::from_utf8_lossy(&body.bytes);
+        assert!(text.starts_with(&format!("--{}\r\n", boundary)));
+        assert!(text.contains("{\"entrypoint_tag\":\"ocr\"}"));
+        assert!(text.contains("filename=\"scan %221%22.png\"\r\nContent-Type: image/png\r\n\r\n"));
+        assert!(body
+            .bytes
+            .ends_with(format!("\r\n--{}--\r\n", boundary).as_bytes()));
+    }
+}
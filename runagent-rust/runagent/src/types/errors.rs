@@ -1,7 +1,9 @@
 //! Error types for the RunAgent SDK
 
+use crate::constants::MAX_ERROR_BODY_BYTES;
 use serde_json::Value;
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Main error type for the RunAgent SDK
@@ -20,8 +22,15 @@ pub enum RunAgentError {
     Connection { message: String },
 
     /// Server-side errors
+    ///
+    /// For HTTP error responses, `status` and a truncated raw `body` are kept for
+    /// debugging; they appear in `Debug` output but not in `Display`.
     #[error("Server error: {message}")]
-    Server { message: String },
+    Server {
+        message: String,
+        status: Option<u16>,
+        body: Option<String>,
+    },
 
     /// Template-related errors
     #[error("Template error: {message}")]
@@ -48,6 +57,21 @@ pub enum RunAgentError {
         details: Option<Value>,
     },
 
+    /// Request deadline elapsed before a response arrived
+    #[error("Timeout error: no response after {elapsed:?}")]
+    Timeout { elapsed: Duration },
+
+    /// Request rejected with HTTP 429; `retry_after` is the server's `Retry-After`
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
+    /// Payload rejected by the serializer (e.g. over the size limit)
+    #[error("Serialization error: {message}")]
+    Serialization { message: String },
+
     /// IO errors
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -91,6 +115,28 @@ impl RunAgentError {
     pub fn server<S: Into<String>>(message: S) -> Self {
         Self::Server {
             message: message.into(),
+            status: None,
+            body: None,
+        }
+    }
+
+    /// Create a server error for an HTTP response, keeping its status and body
+    ///
+    /// Bodies over [`MAX_ERROR_BODY_BYTES`] are truncated.
+    pub fn server_response<S: Into<String>>(message: S, status: u16, body: &str) -> Self {
+        let body = if body.len() <= MAX_ERROR_BODY_BYTES {
+            body.to_string()
+        } else {
+            let mut end = MAX_ERROR_BODY_BYTES;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}…", &body[..end])
+        };
+        Self::Server {
+            message: message.into(),
+            status: Some(status),
+            body: Some(body),
         }
     }
 
@@ -137,6 +183,26 @@ impl RunAgentError {
         }
     }
 
+    /// Create a new timeout error
+    pub fn timeout(elapsed: Duration) -> Self {
+        Self::Timeout { elapsed }
+    }
+
+    /// Create a new rate limit error
+    pub fn rate_limited<S: Into<String>>(message: S, retry_after: Option<Duration>) -> Self {
+        Self::RateLimited {
+            message: message.into(),
+            retry_after,
+        }
+    }
+
+    /// Create a new serialization error
+    pub fn serialization<S: Into<String>>(message: S) -> Self {
+        Self::Serialization {
+            message: message.into(),
+        }
+    }
+
     /// Create a new generic error
     pub fn generic<S: Into<String>>(message: S) -> Self {
         Self::Generic {
@@ -156,6 +222,9 @@ impl RunAgentError {
             Self::Database { .. } => "database",
             Self::Config { .. } => "config",
             Self::Execution { .. } => "execution",
+            Self::Timeout { .. } => "timeout",
+            Self::RateLimited { .. } => "rate_limit",
+            Self::Serialization { .. } => "serialization",
             Self::Io(_) => "io",
             Self::Json(_) => "json",
             Self::Http(_) => "http",
@@ -163,15 +232,287 @@ impl RunAgentError {
         }
     }
 
+    /// Get the machine-readable error code, if the error carries one
+    ///
+    /// [`RunAgentError::Execution`] codes are parsed with [`ErrorCode::from_code`];
+    /// authentication, timeout and rate limit errors map to their own codes. Other variants
+    /// return `None`.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::Execution { code, .. } => Some(ErrorCode::from_code(code)),
+            Self::Authentication { .. } => Some(ErrorCode::AuthenticationFailed),
+            Self::Timeout { .. } => Some(ErrorCode::Timeout),
+            Self::RateLimited { .. } => Some(ErrorCode::RateLimited),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response behind a server error, if any
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Self::Server { status, .. } => *status,
+            _ => None,
+        }
+    }
+
+    /// Raw (possibly truncated) response body behind a server error, if any
+    pub fn response_body(&self) -> Option<&str> {
+        match self {
+            Self::Server { body, .. } => body.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Delay the server asked for before retrying, if any
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Copy of this error for another caller waiting on the same result
+    ///
+    /// Structured variants are cloned. Wrapped IO and JSON errors keep their kind
+    /// and message; HTTP errors become connection errors with the same message.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Self::Authentication { message } => Self::authentication(message.clone()),
+            Self::Validation { message } => Self::validation(message.clone()),
+            Self::Connection { message } => Self::connection(message.clone()),
+            Self::Server {
+                message,
+                status,
+                body,
+            } => Self::Server {
+                message: message.clone(),
+                status: *status,
+                body: body.clone(),
+            },
+            Self::Template { message } => Self::template(message.clone()),
+            Self::Deployment { message } => Self::deployment(message.clone()),
+            Self::Database { message } => Self::database(message.clone()),
+            Self::Config { message } => Self::config(message.clone()),
+            Self::Execution {
+                code,
+                message,
+                suggestion,
+                details,
+            } => Self::Execution {
+                code: code.clone(),
+                message: message.clone(),
+                suggestion: suggestion.clone(),
+                details: details.clone(),
+            },
+            Self::Timeout { elapsed } => Self::timeout(*elapsed),
+            Self::RateLimited {
+                message,
+                retry_after,
+            } => Self::rate_limited(message.clone(), *retry_after),
+            Self::Serialization { message } => Self::serialization(message.clone()),
+            Self::Io(e) => Self::Io(std::io::Error::new(e.kind(), e.to_string())),
+            Self::Json(e) => Self::Json(serde::de::Error::custom(e.to_string())),
+            Self::Http(e) => Self::connection(e.to_string()),
+            Self::Generic { message } => Self::generic(message.clone()),
+        }
+    }
+
     /// Check if the error is retryable
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
-            Self::Connection { .. } | Self::Server { .. } | Self::Http(_)
+            Self::Connection { .. }
+                | Self::Server { .. }
+                | Self::Timeout { .. }
+                | Self::RateLimited { .. }
+                | Self::Http(_)
         ) || matches!(self, Self::Execution { code, .. } if code == "CONNECTION_ERROR" || code == "SERVER_ERROR")
     }
 }
 
+/// Machine-readable error codes, for branching without parsing messages
+///
+/// Codes travel as upper-case strings (the `code` of [`RunAgentError::Execution`]
+/// and of server error objects), shared with the other RunAgent SDKs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The agent is not registered locally or not deployed remotely
+    AgentNotFound,
+    /// The API key is valid but may not access this agent
+    PermissionDenied,
+    /// The API key is missing, invalid or expired
+    AuthenticationFailed,
+    /// The API key's request or agent limits were exceeded
+    RateLimited,
+    /// The entrypoint does not exist or does not match the call (stream vs run)
+    InvalidEntrypoint,
+    /// The stream connection closed before the run completed
+    StreamClosed,
+    /// The agent has no entrypoint architecture
+    ArchitectureMissing,
+    /// The server could not be reached
+    ConnectionFailed,
+    /// The server failed while handling the request
+    ServerError,
+    /// No response arrived before the deadline
+    Timeout,
+    /// Any code not listed above
+    Unknown,
+}
+
+impl ErrorCode {
+    /// Parse a wire code, accepting the variants used across SDKs
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "AGENT_NOT_FOUND" | "AGENT_NOT_FOUND_LOCAL" | "AGENT_NOT_FOUND_REMOTE" => {
+                Self::AgentNotFound
+            }
+            "PERMISSION_ERROR" | "PERMISSION_DENIED" => Self::PermissionDenied,
+            "AUTHENTICATION_ERROR" => Self::AuthenticationFailed,
+            "RATE_LIMITED" | "RATE_LIMIT_EXCEEDED" => Self::RateLimited,
+            "INVALID_ENTRYPOINT"
+            | "ENTRYPOINT_NOT_FOUND"
+            | "STREAM_ENTRYPOINT"
+            | "NON_STREAM_ENTRYPOINT" => Self::InvalidEntrypoint,
+            "STREAM_CLOSED" => Self::StreamClosed,
+            "ARCHITECTURE_MISSING" => Self::ArchitectureMissing,
+            "CONNECTION_ERROR" => Self::ConnectionFailed,
+            "SERVER_ERROR" => Self::ServerError,
+            "TIMEOUT" => Self::Timeout,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Canonical wire code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AgentNotFound => "AGENT_NOT_FOUND",
+            Self::PermissionDenied => "PERMISSION_ERROR",
+            Self::AuthenticationFailed => "AUTHENTICATION_ERROR",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::InvalidEntrypoint => "INVALID_ENTRYPOINT",
+            Self::StreamClosed => "STREAM_CLOSED",
+            Self::ArchitectureMissing => "ARCHITECTURE_MISSING",
+            Self::ConnectionFailed => "CONNECTION_ERROR",
+            Self::ServerError => "SERVER_ERROR",
+            Self::Timeout => "TIMEOUT",
+            Self::Unknown => "UNKNOWN_ERROR",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error reported in a response's `error` (or `detail`) field
+///
+/// The field is either a plain message string or an object
+/// `{"code": ..., "message": ...}`; anything else reads as `"Unknown error"`.
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::types::{ErrorDetail, RunAgentError};
+/// use serde_json::json;
+///
+/// let detail = ErrorDetail::from_value(Some(&json!({"code": "TIMEOUT", "message": "Agent timed out"})));
+/// assert_eq!(detail.code.as_deref(), Some("TIMEOUT"));
+/// assert_eq!(
+///     RunAgentError::from(detail).to_string(),
+///     "Server error: [TIMEOUT] Agent timed out"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDetail {
+    pub message: String,
+    pub code: Option<String>,
+}
+
+impl ErrorDetail {
+    /// Read an `error` field, `None` meaning the field is absent
+    pub fn from_value(error: Option<&Value>) -> Self {
+        match error {
+            Some(Value::String(message)) => Self {
+                message: message.clone(),
+                code: None,
+            },
+            Some(Value::Object(obj)) => Self {
+                message: obj
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown error")
+                    .to_string(),
+                code: obj.get("code").and_then(|c| c.as_str()).map(str::to_string),
+            },
+            _ => Self {
+                message: "Unknown error".to_string(),
+                code: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "[{}] {}", code, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Reported as [`RunAgentError::Server`] with the message `"[code] message"`
+impl From<ErrorDetail> for RunAgentError {
+    fn from(detail: ErrorDetail) -> Self {
+        RunAgentError::server(detail.to_string())
+    }
+}
+
+/// Extract the error carried by a run response or stream chunk, if any
+///
+/// Both modalities report errors the same way, so callers get one error shape
+/// regardless of whether they used `run` or `run_stream`:
+///
+/// - Non-streaming responses: `{"success": false, "error": ...}` (a response
+///   without `success` but with a non-null `error` also counts as failed)
+/// - Stream chunks (canonical format): `{"type": "error", "error": ...}`, with
+///   `detail` accepted in place of `error`
+///
+/// The `error` value is read as an [`ErrorDetail`]: a plain message string or an
+/// object `{"code": ..., "message": ...}`, which yields `"[code] message"`.
+/// Errors are reported as [`RunAgentError::Server`]. Returns `None` for successful responses
+/// and non-error chunks.
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::types::extract_error;
+/// use serde_json::json;
+///
+/// let chunk = json!({"type": "error", "error": {"code": "TIMEOUT", "message": "Agent timed out"}});
+/// let err = extract_error(&chunk).unwrap();
+/// assert_eq!(err.to_string(), "Server error: [TIMEOUT] Agent timed out");
+///
+/// assert!(extract_error(&json!({"type": "data", "content": "hi"})).is_none());
+/// ```
+pub fn extract_error(value: &Value) -> Option<RunAgentError> {
+    let is_error_chunk = value.get("type").and_then(|t| t.as_str()) == Some("error");
+    let is_failed_response = match value.get("success") {
+        Some(success) => success.as_bool() == Some(false),
+        None => value.get("error").is_some_and(|e| !e.is_null()),
+    };
+    if !is_error_chunk && !is_failed_response {
+        return None;
+    }
+
+    let error = value.get("error").or_else(|| value.get("detail"));
+    Some(ErrorDetail::from_value(error).into())
+}
+
 /// Result type alias for RunAgent operations
 pub type RunAgentResult<T> = Result<T, RunAgentError>;
 
@@ -217,6 +558,17 @@ mod tests {
         assert!(connection_err.is_retryable());
     }
 
+    #[test]
+    fn test_server_response_body_truncated() {
+        let body = "é".repeat(MAX_ERROR_BODY_BYTES);
+        let err = RunAgentError::server_response("boom", 502, &body);
+        let kept = err.response_body().unwrap();
+        assert!(kept.len() <= MAX_ERROR_BODY_BYTES + '…'.len_utf8());
+        assert!(kept.ends_with('…'));
+        assert_eq!(err.http_status(), Some(502));
+        assert_eq!(err.to_string(), "Server error: boom");
+    }
+
     #[test]
     fn test_error_display() {
         let err = RunAgentError::server("Internal server error");
@@ -224,4 +576,51 @@ mod tests {
         assert!(error_string.contains("Server error"));
         assert!(error_string.contains("Internal server error"));
     }
+
+    #[test]
+    fn test_error_codes() {
+        let err = RunAgentError::execution("PERMISSION_ERROR", "Access denied", None, None);
+        assert_eq!(err.error_code(), Some(ErrorCode::PermissionDenied));
+
+        for code in [
+            "STREAM_ENTRYPOINT",
+            "NON_STREAM_ENTRYPOINT",
+            "ENTRYPOINT_NOT_FOUND",
+        ] {
+            assert_eq!(ErrorCode::from_code(code), ErrorCode::InvalidEntrypoint);
+        }
+        assert_eq!(
+            ErrorCode::from_code("AGENT_NOT_FOUND_REMOTE"),
+            ErrorCode::AgentNotFound
+        );
+        assert_eq!(ErrorCode::from_code("SOMETHING_NEW"), ErrorCode::Unknown);
+        assert_eq!(ErrorCode::RateLimited.to_string(), "RATE_LIMITED");
+
+        assert_eq!(
+            RunAgentError::authentication("bad key").error_code(),
+            Some(ErrorCode::AuthenticationFailed)
+        );
+        assert_eq!(RunAgentError::server("boom").error_code(), None);
+    }
+
+    #[test]
+    fn test_extract_error_is_uniform_across_modalities() {
+        use serde_json::json;
+
+        let response =
+            json!({"success": false, "error": {"code": "AGENT_FAILED", "message": "boom"}});
+        let chunk = json!({"type": "error", "error": {"code": "AGENT_FAILED", "message": "boom"}});
+        assert_eq!(
+            extract_error(&response).unwrap().to_string(),
+            extract_error(&chunk).unwrap().to_string()
+        );
+
+        let legacy = extract_error(&json!({"type": "error", "detail": "bad input"})).unwrap();
+        assert_eq!(legacy.to_string(), "Server error: bad input");
+        let unknown = extract_error(&json!({"success": false})).unwrap();
+        assert_eq!(unknown.to_string(), "Server error: Unknown error");
+
+        assert!(extract_error(&json!({"success": true, "data": "ok"})).is_none());
+        assert!(extract_error(&json!("plain chunk")).is_none());
+    }
 }
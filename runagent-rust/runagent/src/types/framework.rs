@@ -0,0 +1,149 @@
+//! Agent framework identifiers
+//!
+//! Mirrors the Python SDK's `Framework` enum so tooling built on the Rust SDK can
+//! list and validate the frameworks the RunAgent server supports.
+
+use crate::types::{RunAgentError, RunAgentResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Frameworks supported by RunAgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Framework {
+    Default,
+    Ag2,
+    Agno,
+    Autogen,
+    Crewai,
+    Langchain,
+    Langgraph,
+    Letta,
+    Llamaindex,
+    Openai,
+    N8n,
+    Parlant,
+    Openclaw,
+    Picoclaw,
+    Zeroclaw,
+}
+
+impl Framework {
+    /// All frameworks, in the same order as the Python SDK
+    pub const ALL: [Framework; 15] = [
+        Framework::Default,
+        Framework::Ag2,
+        Framework::Agno,
+        Framework::Autogen,
+        Framework::Crewai,
+        Framework::Langchain,
+        Framework::Langgraph,
+        Framework::Letta,
+        Framework::Llamaindex,
+        Framework::Openai,
+        Framework::N8n,
+        Framework::Parlant,
+        Framework::Openclaw,
+        Framework::Picoclaw,
+        Framework::Zeroclaw,
+    ];
+
+    /// Get the framework name as used in `runagent.config.json`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Ag2 => "ag2",
+            Self::Agno => "agno",
+            Self::Autogen => "autogen",
+            Self::Crewai => "crewai",
+            Self::Langchain => "langchain",
+            Self::Langgraph => "langgraph",
+            Self::Letta => "letta",
+            Self::Llamaindex => "llamaindex",
+            Self::Openai => "openai",
+            Self::N8n => "n8n",
+            Self::Parlant => "parlant",
+            Self::Openclaw => "openclaw",
+            Self::Picoclaw => "picoclaw",
+            Self::Zeroclaw => "zeroclaw",
+        }
+    }
+
+    /// Check if agents of this framework are Python entrypoints
+    pub fn is_pythonic(&self) -> bool {
+        matches!(
+            self,
+            Self::Ag2
+                | Self::Agno
+                | Self::Autogen
+                | Self::Crewai
+                | Self::Langchain
+                | Self::Langgraph
+                | Self::Letta
+                | Self::Llamaindex
+                | Self::Openai
+                | Self::Parlant
+        )
+    }
+
+    /// Check if agents of this framework are reached through webhooks
+    pub fn is_webhook(&self) -> bool {
+        matches!(self, Self::N8n)
+    }
+}
+
+impl fmt::Display for Framework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Framework {
+    type Err = RunAgentError;
+
+    fn from_str(s: &str) -> RunAgentResult<Self> {
+        Self::ALL
+            .iter()
+            .find(|f| f.as_str() == s)
+            .copied()
+            .ok_or_else(|| {
+                RunAgentError::validation(format!(
+                    "Invalid framework: '{}'. Valid options: {:?}",
+                    s,
+                    supported_frameworks()
+                ))
+            })
+    }
+}
+
+/// Names of all frameworks that can be selected for an agent (excludes `default`)
+pub fn supported_frameworks() -> Vec<&'static str> {
+    Framework::ALL
+        .iter()
+        .filter(|f| **f != Framework::Default)
+        .map(|f| f.as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framework_round_trip() {
+        for framework in Framework::ALL {
+            assert_eq!(framework.as_str().parse::<Framework>().unwrap(), framework);
+            let json = serde_json::to_string(&framework).unwrap();
+            assert_eq!(json, format!("\"{}\"", framework));
+        }
+    }
+
+    #[test]
+    fn test_supported_frameworks() {
+        let frameworks = supported_frameworks();
+        assert!(frameworks.contains(&"langgraph"));
+        assert!(!frameworks.contains(&"default"));
+        assert!("unknown".parse::<Framework>().is_err());
+    }
+}
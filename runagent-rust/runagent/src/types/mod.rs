@@ -1,10 +1,14 @@
 //! Type definitions for the RunAgent SDK
 
+pub mod attachment;
 pub mod errors;
+pub mod framework;
 pub mod responses;
 pub mod schema;
 
 // Re-export commonly used types
-pub use errors::{RunAgentError, RunAgentResult};
+pub use attachment::{Attachment, AttachmentEncoding};
+pub use errors::{extract_error, ErrorCode, ErrorDetail, RunAgentError, RunAgentResult};
+pub use framework::{supported_frameworks, Framework};
 pub use responses::*;
 pub use schema::*;
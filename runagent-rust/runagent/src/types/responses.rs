@@ -1,7 +1,10 @@
 //! Response types for API interactions
 
+use crate::types::{ErrorDetail, RunAgentError, RunAgentResult};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Generic API response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +48,95 @@ impl<T> ApiResponse<T> {
     }
 }
 
+/// Payload returned by an agent run, with accessors for common fields
+///
+/// Wraps the `Value` returned by `RunAgentClient::run`. Obtain one with
+/// `client.run_typed::<AgentResponse>(...)` or `AgentResponse::from(value)`.
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::types::AgentResponse;
+/// use serde_json::json;
+///
+/// let response = AgentResponse::from(json!({
+///     "response": "Hi there!",
+///     "thread_id": "conversation_001",
+///     "message_count": 4
+/// }));
+/// assert_eq!(response.text(), Some("Hi there!"));
+/// assert_eq!(response.thread_id(), Some("conversation_001"));
+/// assert_eq!(response.message_count(), Some(4));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AgentResponse(serde_json::Value);
+
+impl AgentResponse {
+    /// Text of the response
+    ///
+    /// The payload itself when it is a string, otherwise the first string field
+    /// among `response`, `content`, `text`, `output` and `answer`.
+    pub fn text(&self) -> Option<&str> {
+        if let Some(text) = self.0.as_str() {
+            return Some(text);
+        }
+        ["response", "content", "text", "output", "answer"]
+            .iter()
+            .find_map(|key| self.0.get(key).and_then(|v| v.as_str()))
+    }
+
+    /// Conversation thread ID, if the agent returned one
+    pub fn thread_id(&self) -> Option<&str> {
+        self.0.get("thread_id").and_then(|v| v.as_str())
+    }
+
+    /// Number of messages in the conversation
+    ///
+    /// Read from `message_count`, or the length of a `messages` array.
+    pub fn message_count(&self) -> Option<u64> {
+        self.0
+            .get("message_count")
+            .and_then(|v| v.as_u64())
+            .or_else(|| {
+                self.0
+                    .get("messages")
+                    .and_then(|v| v.as_array())
+                    .map(|m| m.len() as u64)
+            })
+    }
+
+    /// Get the raw payload
+    pub fn as_value(&self) -> &serde_json::Value {
+        &self.0
+    }
+
+    /// Get the raw payload, consuming the response
+    pub fn into_value(self) -> serde_json::Value {
+        self.0
+    }
+
+    /// Deserialize the payload into a user type
+    ///
+    /// Fails with a validation error naming the mismatch when the payload does not
+    /// have the expected shape.
+    pub fn into<T: DeserializeOwned>(self) -> RunAgentResult<T> {
+        serde_json::from_value(self.0).map_err(|e| {
+            RunAgentError::validation(format!(
+                "Agent response does not match {}: {}",
+                std::any::type_name::<T>(),
+                e
+            ))
+        })
+    }
+}
+
+impl From<serde_json::Value> for AgentResponse {
+    fn from(value: serde_json::Value) -> Self {
+        Self(value)
+    }
+}
+
 /// Response for agent execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResponse {
@@ -53,6 +145,230 @@ pub struct ExecutionResponse {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// Timing and token usage reported alongside a run's payload
+///
+/// Parsed from the response envelope by `RunAgentClient::run_with_metadata`.
+/// Fields the server or framework did not report are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionMetadata {
+    /// Server-side execution time
+    pub duration: Option<Duration>,
+    /// Prompt tokens consumed by the model
+    pub input_tokens: Option<u64>,
+    /// Completion tokens produced by the model
+    pub output_tokens: Option<u64>,
+    /// Model that served the run
+    pub model: Option<String>,
+}
+
+impl ExecutionMetadata {
+    /// Read metadata from a run response envelope
+    ///
+    /// Looks in `usage_metadata`, `metadata` and `execution_metadata`, first on
+    /// the envelope and then on an object `data`. The first source reporting a
+    /// field wins. Durations are read from `duration`, `execution_time` or
+    /// `runtime_seconds` (seconds) and `duration_ms` or `execution_time_ms`;
+    /// token counts also accept the `prompt_tokens`/`completion_tokens` names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use runagent::types::ExecutionMetadata;
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// let metadata = ExecutionMetadata::from_response(&json!({
+    ///     "success": true,
+    ///     "data": "{}",
+    ///     "usage_metadata": {"input_tokens": 12, "output_tokens": 40, "model": "gpt-4o-mini"},
+    ///     "metadata": {"execution_time_ms": 1500}
+    /// }));
+    /// assert_eq!(metadata.duration, Some(Duration::from_millis(1500)));
+    /// assert_eq!(metadata.input_tokens, Some(12));
+    /// assert_eq!(metadata.model.as_deref(), Some("gpt-4o-mini"));
+    /// ```
+    pub fn from_response(response: &serde_json::Value) -> Self {
+        const SOURCES: [&str; 3] = ["usage_metadata", "metadata", "execution_metadata"];
+        let data = response.get("data").filter(|data| data.is_object());
+        let sources: Vec<&serde_json::Value> = [Some(response), data]
+            .into_iter()
+            .flatten()
+            .flat_map(|level| SOURCES.iter().filter_map(|key| level.get(key)))
+            .filter(|source| source.is_object())
+            .collect();
+
+        let find = |keys: &[&str]| {
+            sources
+                .iter()
+                .find_map(|source| keys.iter().find_map(|key| source.get(key)))
+        };
+        let non_negative = |value: &serde_json::Value| {
+            value
+                .as_f64()
+                .filter(|value| value.is_finite() && *value >= 0.0)
+        };
+        let duration = find(&["duration", "execution_time", "runtime_seconds"])
+            .and_then(non_negative)
+            .map(Duration::from_secs_f64)
+            .or_else(|| {
+                find(&["duration_ms", "execution_time_ms"])
+                    .and_then(non_negative)
+                    .map(|millis| Duration::from_secs_f64(millis / 1000.0))
+            });
+
+        Self {
+            duration,
+            input_tokens: find(&["input_tokens", "prompt_tokens"]).and_then(|v| v.as_u64()),
+            output_tokens: find(&["output_tokens", "completion_tokens"]).and_then(|v| v.as_u64()),
+            model: find(&["model", "model_name"])
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Payload of a non-streaming run, telling a missing payload apart from `null`
+///
+/// Returned by `RunAgentClient::run_outcome`; `run` collapses both `EmptyNull`
+/// and `NoPayload` to `Value::Null`. Response shapes map as follows:
+///
+/// | Response | Outcome |
+/// |----------|---------|
+/// | a payload decoding to a non-null value | `Payload(value)` |
+/// | `"data": null`, `"output_data": null`, or a payload decoding to `null` (e.g. `{"type": "null", "payload": "null"}`) | `EmptyNull` |
+/// | no `data` or `output_data` field (and no configured payload path matches) | `NoPayload` |
+///
+/// See [`ResponseEnvelope`] for where the payload is looked for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunOutcome {
+    /// The agent returned a value
+    Payload(serde_json::Value),
+    /// The agent returned `null` (e.g. a Python function returning `None`)
+    EmptyNull,
+    /// The response carried no payload the client could find
+    NoPayload,
+}
+
+impl RunOutcome {
+    /// Classify a decoded payload, `None` meaning none was found
+    pub(crate) fn from_payload(payload: Option<serde_json::Value>) -> Self {
+        match payload {
+            None => Self::NoPayload,
+            Some(serde_json::Value::Null) => Self::EmptyNull,
+            Some(value) => Self::Payload(value),
+        }
+    }
+
+    /// The payload as `run` returns it: `Value::Null` unless there is a value
+    pub fn into_value(self) -> serde_json::Value {
+        match self {
+            Self::Payload(value) => value,
+            Self::EmptyNull | Self::NoPayload => serde_json::Value::Null,
+        }
+    }
+}
+
+/// A non-streaming run response, classified by where it carries its payload
+///
+/// The contract for `{"success", "data", "error"}` run responses, in order of
+/// precedence:
+///
+/// | Response | Envelope |
+/// |----------|----------|
+/// | `success` is not `true` | `Failure`, from `error` or `detail` |
+/// | `data` is a string (a serialized payload, possibly `{type, payload}`) | `Serialized` |
+/// | `data.result_data.data` is present (legacy detailed execution payload) | `ResultData` |
+/// | `data` is an object (possibly `{type, payload}`) | `Object` |
+/// | `data` is `null` | `Null` |
+/// | `data` is an array, number or boolean | `Other` |
+/// | `output_data` is present (older servers) | `OutputData` |
+/// | none of the above | `Empty` |
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::types::ResponseEnvelope;
+/// use serde_json::json;
+///
+/// let envelope = ResponseEnvelope::parse(&json!({"success": true, "data": [1, 2]}));
+/// assert_eq!(envelope.into_payload(), Some(json!([1, 2])));
+///
+/// let envelope = ResponseEnvelope::parse(&json!({
+///     "success": false,
+///     "error": {"code": "AGENT_ERROR", "message": "boom"}
+/// }));
+/// assert!(!envelope.is_success());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseEnvelope {
+    /// The run failed
+    Failure(ErrorDetail),
+    /// `data` as a string still to be decoded
+    Serialized(serde_json::Value),
+    /// `data.result_data.data`
+    ResultData(serde_json::Value),
+    /// `data` as an object
+    Object(serde_json::Value),
+    /// `data: null`: the agent returned nothing
+    Null,
+    /// `data` as an array, number or boolean
+    Other(serde_json::Value),
+    /// `output_data`
+    OutputData(serde_json::Value),
+    /// A successful response without a payload
+    Empty,
+}
+
+impl ResponseEnvelope {
+    /// Classify a run response
+    pub fn parse(response: &serde_json::Value) -> Self {
+        use serde_json::Value;
+
+        if response.get("success").and_then(Value::as_bool) != Some(true) {
+            let error = response.get("error").or_else(|| response.get("detail"));
+            return Self::Failure(ErrorDetail::from_value(error));
+        }
+        match response.get("data") {
+            Some(data @ Value::String(_)) => Self::Serialized(data.clone()),
+            Some(data @ Value::Object(_)) => {
+                match data
+                    .get("result_data")
+                    .and_then(|result| result.get("data"))
+                {
+                    Some(output) => Self::ResultData(output.clone()),
+                    None => Self::Object(data.clone()),
+                }
+            }
+            Some(Value::Null) => Self::Null,
+            Some(data) => Self::Other(data.clone()),
+            None => match response.get("output_data") {
+                Some(output) => Self::OutputData(output.clone()),
+                None => Self::Empty,
+            },
+        }
+    }
+
+    /// Whether the run succeeded
+    pub fn is_success(&self) -> bool {
+        !matches!(self, Self::Failure(_))
+    }
+
+    /// The raw payload, before decoding
+    ///
+    /// `Some(Value::Null)` for `Null`; `None` for `Empty` and `Failure`.
+    pub fn into_payload(self) -> Option<serde_json::Value> {
+        match self {
+            Self::Serialized(payload)
+            | Self::ResultData(payload)
+            | Self::Object(payload)
+            | Self::Other(payload)
+            | Self::OutputData(payload) => Some(payload),
+            Self::Null => Some(serde_json::Value::Null),
+            Self::Empty | Self::Failure(_) => None,
+        }
+    }
+}
+
 /// Response for streaming execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamChunk {
@@ -136,6 +452,26 @@ pub struct ValidationResponse {
     pub suggestions: Vec<String>,
 }
 
+/// A deployed agent, as listed for the authenticated account
+///
+/// Fields other than `agent_id` are whatever the server reports; unknown fields
+/// are kept in `extra`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSummary {
+    #[serde(alias = "id")]
+    pub agent_id: String,
+    #[serde(default, alias = "agent_name")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub framework: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
 /// Paginated response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaginatedResponse<T> {
@@ -206,6 +542,86 @@ mod tests {
         assert!(!response.has_prev);
     }
 
+    #[test]
+    fn test_response_envelope_shapes() {
+        use serde_json::json;
+
+        let cases = [
+            (
+                json!({"success": true, "data": "\"hi\""}),
+                ResponseEnvelope::Serialized(json!("\"hi\"")),
+            ),
+            (
+                json!({"success": true, "data": {"result_data": {"data": "out"}}}),
+                ResponseEnvelope::ResultData(json!("out")),
+            ),
+            (
+                json!({"success": true, "data": {"type": "dict", "payload": "{}"}}),
+                ResponseEnvelope::Object(json!({"type": "dict", "payload": "{}"})),
+            ),
+            (
+                json!({"success": true, "data": null}),
+                ResponseEnvelope::Null,
+            ),
+            (
+                json!({"success": true, "data": [1, 2]}),
+                ResponseEnvelope::Other(json!([1, 2])),
+            ),
+            (
+                json!({"success": true, "data": 0}),
+                ResponseEnvelope::Other(json!(0)),
+            ),
+            (
+                json!({"success": true, "output_data": {"answer": 42}}),
+                ResponseEnvelope::OutputData(json!({"answer": 42})),
+            ),
+            (json!({"success": true}), ResponseEnvelope::Empty),
+        ];
+        for (response, envelope) in cases {
+            assert_eq!(ResponseEnvelope::parse(&response), envelope, "{}", response);
+            assert!(envelope.is_success());
+        }
+        assert_eq!(
+            ResponseEnvelope::Null.into_payload(),
+            Some(serde_json::Value::Null)
+        );
+        assert_eq!(ResponseEnvelope::Empty.into_payload(), None);
+    }
+
+    #[test]
+    fn test_response_envelope_failures() {
+        use serde_json::json;
+
+        let detail = |response: serde_json::Value| match ResponseEnvelope::parse(&response) {
+            ResponseEnvelope::Failure(detail) => detail,
+            other => panic!("expected a failure, got {:?}", other),
+        };
+
+        let coded = detail(json!({
+            "success": false,
+            "data": "ignored",
+            "error": {"code": "AGENT_ERROR", "message": "boom"}
+        }));
+        assert_eq!(coded.code.as_deref(), Some("AGENT_ERROR"));
+        assert_eq!(coded.message, "boom");
+        assert_eq!(
+            RunAgentError::from(coded).to_string(),
+            "Server error: [AGENT_ERROR] boom"
+        );
+        assert_eq!(
+            detail(json!({"success": false, "detail": "Not authorized"})).to_string(),
+            "Not authorized"
+        );
+        // A response without `success: true` never yields a payload
+        assert_eq!(
+            detail(json!({"data": "hi"})),
+            ErrorDetail {
+                message: "Unknown error".to_string(),
+                code: None
+            }
+        );
+    }
+
     #[test]
     fn test_stream_chunk() {
         let chunk = StreamChunk {
@@ -218,4 +634,64 @@ mod tests {
         assert_eq!(chunk.chunk_id, "chunk-1");
         assert!(!chunk.is_final);
     }
+
+    #[test]
+    fn test_agent_response_accessors() {
+        #[derive(Deserialize)]
+        struct Chat {
+            response: String,
+            thread_id: String,
+        }
+
+        let response = AgentResponse::from(serde_json::json!({
+            "content": "Hello",
+            "thread_id": "t1",
+            "messages": [{}, {}]
+        }));
+        assert_eq!(response.text(), Some("Hello"));
+        assert_eq!(response.message_count(), Some(2));
+        assert_eq!(
+            AgentResponse::from(serde_json::json!("plain")).text(),
+            Some("plain")
+        );
+
+        let err = response.clone().into::<Chat>().err().unwrap();
+        assert_eq!(err.category(), "validation");
+
+        let chat: Chat =
+            AgentResponse::from(serde_json::json!({"response": "Hi", "thread_id": "t2"}))
+                .into()
+                .unwrap();
+        assert_eq!(
+            (chat.response.as_str(), chat.thread_id.as_str()),
+            ("Hi", "t2")
+        );
+    }
+
+    #[test]
+    fn test_execution_metadata_from_envelope() {
+        let metadata = ExecutionMetadata::from_response(&serde_json::json!({
+            "success": true,
+            "data": {
+                "content": "Hi",
+                "usage_metadata": {"prompt_tokens": 7, "completion_tokens": 3, "model_name": "claude"}
+            },
+            "execution_metadata": {"runtime_seconds": 0.25}
+        }));
+        assert_eq!(
+            metadata,
+            ExecutionMetadata {
+                duration: Some(Duration::from_millis(250)),
+                input_tokens: Some(7),
+                output_tokens: Some(3),
+                model: Some("claude".to_string()),
+            }
+        );
+
+        let bare = serde_json::json!({"success": true, "data": "\"ok\""});
+        assert_eq!(
+            ExecutionMetadata::from_response(&bare),
+            ExecutionMetadata::default()
+        );
+    }
 }
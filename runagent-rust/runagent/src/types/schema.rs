@@ -233,6 +233,15 @@ pub struct AgentRun {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// Run counts for one agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRunStats {
+    pub agent_id: String,
+    pub total_runs: i64,
+    pub successful_runs: i64,
+    pub failed_runs: i64,
+}
+
 /// Configuration for local server
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
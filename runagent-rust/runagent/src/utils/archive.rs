@@ -0,0 +1,207 @@
+//! Streaming tar.gz archives of agent folders for upload
+
+use crate::types::{RunAgentError, RunAgentResult};
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+use futures::Stream;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// Size of the chunks handed to the HTTP body
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A file to archive and its path inside the archive
+#[derive(Debug, Clone)]
+pub(crate) struct ArchiveEntry {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+/// List the files to upload from `folder`, failing once they exceed `max_bytes`
+///
+/// Paths matched by `ignore_file` (gitignore syntax, in any directory) and `.git`
+/// are skipped. Symlinks are followed; a link leading back into a directory
+/// being walked is skipped rather than followed again.
+pub(crate) fn collect_files(
+    folder: &Path,
+    ignore_file: &str,
+    max_bytes: u64,
+) -> RunAgentResult<Vec<ArchiveEntry>> {
+    if !folder.is_dir() {
+        return Err(RunAgentError::validation(format!(
+            "Agent folder {} does not exist or is not a directory",
+            folder.display()
+        )));
+    }
+
+    let walker = ignore::WalkBuilder::new(folder)
+        .standard_filters(false)
+        .add_custom_ignore_filename(ignore_file)
+        .follow_links(true)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut entries = Vec::new();
+    let mut total_bytes = 0u64;
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) if is_loop(&e) => {
+                tracing::warn!("Skipping symlink loop: {}", e);
+                continue;
+            }
+            Err(e) => {
+                return Err(RunAgentError::validation(format!(
+                    "Failed to read agent folder: {}",
+                    e
+                )))
+            }
+        };
+        if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+            continue;
+        }
+
+        total_bytes += entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        if total_bytes > max_bytes {
+            return Err(RunAgentError::validation(format!(
+                "Agent folder {} is larger than the {} MB upload limit; add large files to {}",
+                folder.display(),
+                max_bytes / (1024 * 1024),
+                ignore_file
+            )));
+        }
+
+        let relative = entry.path().strip_prefix(folder).unwrap_or(entry.path());
+        let name = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        entries.push(ArchiveEntry {
+            path: entry.path().to_path_buf(),
+            name,
+        });
+    }
+    Ok(entries)
+}
+
+fn is_loop(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
+    }
+}
+
+/// Stream a gzipped tar of `entries`, built on a blocking thread as it is read
+///
+/// Only a few chunks are buffered at a time, so large folders are never held in
+/// memory. Must be called from within a Tokio runtime.
+pub(crate) fn tar_gz_stream(
+    entries: Vec<ArchiveEntry>,
+) -> impl Stream<Item = io::Result<Bytes>> + Send + 'static {
+    let (sender, receiver) = mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let writer = ChunkWriter {
+            sender: sender.clone(),
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        };
+        let result = (|| {
+            let mut archive = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+            for entry in &entries {
+                archive.append_path_with_name(&entry.path, &entry.name)?;
+            }
+            archive.into_inner()?.finish()?.flush()
+        })();
+        if let Err(e) = result {
+            let _ = sender.blocking_send(Err(e));
+        }
+    });
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    })
+}
+
+/// Writer forwarding fixed-size chunks to an async receiver
+struct ChunkWriter {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+    buffer: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(
+            &mut self.buffer,
+            Vec::with_capacity(CHUNK_SIZE),
+        ));
+        self.sender
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "upload was aborted"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_archive_respects_ignore_file_and_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("main.py"), "print('hi')").unwrap();
+        fs::write(root.join("src/agent.py"), "x = 1").unwrap();
+        fs::write(root.join("debug.log"), "noise").unwrap();
+        fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        fs::write(root.join(".runagentignore"), "*.log\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root, root.join("src/loop")).unwrap();
+
+        let entries = collect_files(root, ".runagentignore", 1024 * 1024).unwrap();
+        let mut names: Vec<_> = entries.iter().map(|e| e.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, vec![".runagentignore", "main.py", "src/agent.py"]);
+
+        let bytes: Vec<u8> = tar_gz_stream(entries)
+            .map_ok(|chunk| chunk.to_vec())
+            .try_concat()
+            .await
+            .unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..]));
+        let archived = archive.entries().unwrap().count();
+        assert_eq!(archived, 3);
+    }
+
+    #[test]
+    fn test_oversized_folder_fails_early() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("big.bin"), vec![0u8; 2048]).unwrap();
+        let err = collect_files(dir.path(), ".runagentignore", 1024).unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert!(err.to_string().contains("upload limit"));
+    }
+}
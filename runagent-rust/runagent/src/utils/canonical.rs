@@ -0,0 +1,96 @@
+//! Canonical JSON for stable cache and deduplication keys
+
+use serde_json::Value;
+
+/// Canonical JSON for a set of keyword arguments
+///
+/// Object keys are sorted at every depth and no insignificant whitespace is
+/// emitted, so semantically equal kwargs always produce the same string no matter
+/// the order they were given in. A repeated key keeps its last value, as when the
+/// kwargs are sent to the server.
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::utils::canonicalize;
+/// use serde_json::json;
+///
+/// let a = canonicalize(&[("query", json!("hi")), ("opts", json!({"b": 1, "a": 2}))]);
+/// let b = canonicalize(&[("opts", json!({"a": 2, "b": 1})), ("query", json!("hi"))]);
+/// assert_eq!(a, b);
+/// assert_eq!(a, r#"{"opts":{"a":2,"b":1},"query":"hi"}"#);
+/// ```
+pub fn canonicalize(kwargs: &[(&str, Value)]) -> String {
+    let object: serde_json::Map<String, Value> = kwargs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect();
+    canonical_json(&Value::Object(object))
+}
+
+/// Canonical JSON for any value (sorted keys, compact)
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonicalize_is_order_independent() {
+        let parsed: Value =
+            serde_json::from_str(r#"{ "z": [ {"y": 1, "x": null} ], "a": "text" }"#).unwrap();
+        let built = json!({"a": "text", "z": [{"x": null, "y": 1}]});
+        assert_eq!(canonical_json(&parsed), canonical_json(&built));
+        assert_eq!(
+            canonical_json(&built),
+            r#"{"a":"text","z":[{"x":null,"y":1}]}"#
+        );
+
+        // Array order is significant
+        assert_ne!(
+            canonical_json(&json!([1, 2])),
+            canonical_json(&json!([2, 1]))
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_kwargs_last_value_wins() {
+        let kwargs = [("n", json!(1)), ("m", json!("x")), ("n", json!(2))];
+        assert_eq!(canonicalize(&kwargs), r#"{"m":"x","n":2}"#);
+    }
+}
@@ -0,0 +1,99 @@
+//! Time source abstraction for timing-dependent logic
+//!
+//! Backoff delays and deadlines read time through [`Clock`] so they can be tested
+//! with `TestClock` instead of real sleeps. Public APIs always use [`SystemClock`].
+
+use futures::future::BoxFuture;
+use std::time::{Duration, Instant};
+
+/// Source of the current time and of delays
+pub(crate) trait Clock: Send + Sync {
+    /// Current instant
+    fn now(&self) -> Instant;
+
+    /// Wait for the given duration
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// Wall-clock time backed by Tokio timers
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Manually driven clock for tests
+///
+/// Time only moves through [`TestClock::advance`] or `sleep`, which advances the
+/// clock immediately and records the requested duration.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct TestClock {
+    start: Instant,
+    state: std::sync::Mutex<TestClockState>,
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct TestClockState {
+    elapsed: Duration,
+    sleeps: Vec<Duration>,
+}
+
+#[cfg(test)]
+impl TestClock {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            state: Default::default(),
+        }
+    }
+
+    /// Move the clock forward
+    pub(crate) fn advance(&self, duration: Duration) {
+        self.state.lock().unwrap().elapsed += duration;
+    }
+
+    /// Durations passed to `sleep`, in call order
+    pub(crate) fn sleeps(&self) -> Vec<Duration> {
+        self.state.lock().unwrap().sleeps.clone()
+    }
+}
+
+#[cfg(test)]
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.start + self.state.lock().unwrap().elapsed
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let mut state = self.state.lock().unwrap();
+        state.elapsed += duration;
+        state.sleeps.push(duration);
+        Box::pin(futures::future::ready(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clock_advances_without_waiting() {
+        let clock = TestClock::new();
+        let start = clock.now();
+
+        clock.advance(Duration::from_secs(5));
+        clock.sleep(Duration::from_secs(60)).await;
+
+        assert_eq!(clock.now() - start, Duration::from_secs(65));
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(60)]);
+    }
+}
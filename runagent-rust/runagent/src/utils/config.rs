@@ -1,11 +1,36 @@
 //! Configuration management for the RunAgent SDK
-
-use crate::constants::{DEFAULT_BASE_URL, ENV_RUNAGENT_API_KEY, ENV_RUNAGENT_BASE_URL};
+//!
+//! Settings can come from a user config file with named profiles, e.g.
+//! `~/.runagent/config.toml`:
+//!
+//! ```toml
+//! base_url = "http://localhost:8333"
+//!
+//! [profiles.staging]
+//! base_url = "https://staging.run-agent.ai"
+//! api_key = "rau_..."
+//! ```
+//!
+//! The active profile is chosen with `RUNAGENT_PROFILE` (or passed explicitly to
+//! [`Config::load_profile`]) and overrides the flat top-level values.
+//!
+//! Settings are resolved with this precedence, highest first:
+//!
+//! 1. explicit arguments (e.g. to [`Config::setup`] or the client config builders)
+//! 2. `RUNAGENT_*` environment variables
+//! 3. a `.env` file in the current directory (with the `dotenv` feature)
+//! 4. the config file, profile values over flat values
+//! 5. built-in defaults
+
+use crate::constants::{
+    DEFAULT_BASE_URL, ENV_RUNAGENT_API_KEY, ENV_RUNAGENT_BASE_URL, ENV_RUNAGENT_CACHE_DIR,
+    ENV_RUNAGENT_PROFILE, USER_CONFIG_FILE_NAME,
+};
 use crate::types::{RunAgentError, RunAgentResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Configuration for the RunAgent SDK
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +43,25 @@ pub struct Config {
     pub auth_validated: Option<bool>,
     #[serde(default)]
     pub user_info: HashMap<String, serde_json::Value>,
+    /// Name of the profile the values were loaded from, if any
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Values a config profile may set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+}
+
+/// On-disk layout of the user config file
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(flatten)]
+    defaults: ProfileConfig,
+    #[serde(default)]
+    profiles: HashMap<String, ProfileConfig>,
 }
 
 impl Default for Config {
@@ -30,22 +74,48 @@ impl Default for Config {
             user_tier: None,
             auth_validated: None,
             user_info: HashMap::new(),
+            profile: None,
         }
     }
 }
 
 impl Config {
-    /// Load configuration from environment variables
-    /// All configuration is now stored in SQLite database, this only loads from env vars
+    /// Load configuration from the user config file and environment variables
+    ///
+    /// The profile named by `RUNAGENT_PROFILE` is applied when set. Environment
+    /// variables (`RUNAGENT_API_KEY`, `RUNAGENT_BASE_URL`) override file values.
     pub fn load() -> RunAgentResult<Self> {
-        let mut config = Self::default();
+        Self::load_from(Self::user_config_path())
+    }
+
+    /// Load configuration like [`Config::load`], reading the config file at `path`
+    pub fn load_from(path: impl AsRef<Path>) -> RunAgentResult<Self> {
+        let env = EnvSource::from_process();
+        let profile = env.get(ENV_RUNAGENT_PROFILE).filter(|p| !p.is_empty());
+        Self::load_layered(path.as_ref(), profile.as_deref(), &env)
+    }
+
+    /// Load configuration using a named profile from the user config file
+    ///
+    /// This is what a `--profile` flag should call. Returns a config error if the
+    /// profile is not defined.
+    pub fn load_profile(profile: &str) -> RunAgentResult<Self> {
+        Self::load_layered(
+            &Self::user_config_path(),
+            Some(profile),
+            &EnvSource::from_process(),
+        )
+    }
+
+    fn load_layered(path: &Path, profile: Option<&str>, env: &EnvSource) -> RunAgentResult<Self> {
+        let mut config = Self::from_file(path, profile)?;
 
-        // Load from environment variables
-        if let Ok(env_api_key) = std::env::var(ENV_RUNAGENT_API_KEY) {
+        // Environment (or .env) values override the config file
+        if let Some(env_api_key) = env.get(ENV_RUNAGENT_API_KEY) {
             config.api_key = Some(env_api_key);
         }
 
-        if let Ok(env_base_url) = std::env::var(ENV_RUNAGENT_BASE_URL) {
+        if let Some(env_base_url) = env.get(ENV_RUNAGENT_BASE_URL) {
             config.base_url = env_base_url;
         }
 
@@ -57,8 +127,72 @@ impl Config {
         Ok(config)
     }
 
+    /// Path of the user config file (`$RUNAGENT_CACHE_DIR/config.toml`, default `~/.runagent/config.toml`)
+    pub fn user_config_path() -> PathBuf {
+        let cache_dir = std::env::var(ENV_RUNAGENT_CACHE_DIR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join(".runagent")
+            });
+        cache_dir.join(USER_CONFIG_FILE_NAME)
+    }
+
+    /// Build a config from a config file, applying the given profile on top of its flat values
+    ///
+    /// A missing file yields the defaults (unless a profile was requested).
+    fn from_file(path: &Path, profile: Option<&str>) -> RunAgentResult<Self> {
+        let file: ConfigFile = if path.exists() {
+            config::Config::builder()
+                .add_source(config::File::from(path))
+                .build()
+                .and_then(|c| c.try_deserialize())
+                .map_err(|e| {
+                    RunAgentError::config(format!(
+                        "Failed to read config file {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?
+        } else {
+            ConfigFile::default()
+        };
+
+        let mut config = Self::default();
+        config.apply_profile(&file.defaults);
+
+        if let Some(name) = profile {
+            let values = file.profiles.get(name).ok_or_else(|| {
+                let mut available: Vec<&str> = file.profiles.keys().map(|k| k.as_str()).collect();
+                available.sort_unstable();
+                RunAgentError::config(format!(
+                    "Profile `{}` not found in {}. Available profiles: {:?}",
+                    name,
+                    path.display(),
+                    available
+                ))
+            })?;
+            config.apply_profile(values);
+            config.profile = Some(name.to_string());
+        }
+
+        Ok(config)
+    }
+
+    fn apply_profile(&mut self, values: &ProfileConfig) {
+        if let Some(ref api_key) = values.api_key {
+            self.api_key = Some(api_key.clone());
+        }
+        if let Some(ref base_url) = values.base_url {
+            self.base_url = base_url.clone();
+        }
+    }
+
     /// Setup and validate configuration
-    /// Note: Configuration is stored in SQLite database, not in files
+    ///
+    /// Starts from [`Config::load`]. `_save` is ignored; nothing is written back
+    /// to the config file.
     pub fn setup(
         api_key: Option<String>,
         base_url: Option<String>,
@@ -91,8 +225,6 @@ impl Config {
             ));
         }
 
-        // Note: save parameter is ignored - configuration is stored in SQLite database
-
         Ok(config)
     }
 
@@ -130,6 +262,7 @@ impl Config {
             serde_json::json!(self.api_key.is_some()),
         );
         status.insert("base_url".to_string(), serde_json::json!(self.base_url));
+        status.insert("profile".to_string(), serde_json::json!(self.profile));
         status.insert("user_info".to_string(), serde_json::json!(self.user_info));
 
         status
@@ -268,6 +401,46 @@ impl Config {
     }
 }
 
+/// `RUNAGENT_*` settings from the environment, falling back to a `.env` file
+#[derive(Debug, Default)]
+struct EnvSource {
+    env: HashMap<String, String>,
+    dotenv: HashMap<String, String>,
+}
+
+impl EnvSource {
+    /// Read the process environment and, with the `dotenv` feature, `./.env`
+    ///
+    /// The `.env` values are not exported to the process environment.
+    fn from_process() -> Self {
+        let env = std::env::vars()
+            .filter(|(key, _)| key.starts_with("RUNAGENT_"))
+            .collect();
+
+        #[cfg(feature = "dotenv")]
+        let dotenv = match dotenvy::from_filename_iter(".env") {
+            Ok(iter) => iter
+                .filter_map(|item| match item {
+                    Ok(pair) => Some(pair),
+                    Err(e) => {
+                        tracing::warn!("Skipping unreadable .env entry: {}", e);
+                        None
+                    }
+                })
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        #[cfg(not(feature = "dotenv"))]
+        let dotenv = HashMap::new();
+
+        Self { env, dotenv }
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.env.get(key).or_else(|| self.dotenv.get(key)).cloned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,6 +494,95 @@ mod tests {
         assert!(config_file.exists());
     }
 
+    #[test]
+    fn test_profile_overrides_flat_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+base_url = "http://localhost:8333"
+api_key = "default-key"
+
+[profiles.staging]
+base_url = "https://staging.example.com"
+api_key = "staging-key"
+
+[profiles.prod]
+api_key = "prod-key"
+"#,
+        )
+        .unwrap();
+
+        let flat = Config::from_file(&path, None).unwrap();
+        assert_eq!(flat.base_url, "http://localhost:8333");
+        assert_eq!(flat.api_key.as_deref(), Some("default-key"));
+        assert!(flat.profile.is_none());
+
+        let staging = Config::from_file(&path, Some("staging")).unwrap();
+        assert_eq!(staging.base_url, "https://staging.example.com");
+        assert_eq!(staging.api_key.as_deref(), Some("staging-key"));
+        assert_eq!(staging.profile.as_deref(), Some("staging"));
+
+        // Values a profile leaves unset fall back to the flat defaults
+        let prod = Config::from_file(&path, Some("prod")).unwrap();
+        assert_eq!(prod.base_url, "http://localhost:8333");
+        assert_eq!(prod.api_key.as_deref(), Some("prod-key"));
+
+        let missing = Config::from_file(&path, Some("qa"));
+        assert!(matches!(missing, Err(RunAgentError::Config { .. })));
+    }
+
+    #[test]
+    fn test_env_layering_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "base_url = \"http://file.example.com\"\napi_key = \"file-key\"\n\n[profiles.dev]\napi_key = \"dev-key\"\n",
+        )
+        .unwrap();
+        let vars = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        // .env overrides the config file
+        let env = EnvSource {
+            env: HashMap::new(),
+            dotenv: vars(&[(ENV_RUNAGENT_API_KEY, "dotenv-key")]),
+        };
+        let config = Config::load_layered(&path, None, &env).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("dotenv-key"));
+        assert_eq!(config.base_url, "http://file.example.com");
+
+        // The environment overrides .env, and both override the profile
+        let env = EnvSource {
+            env: vars(&[(ENV_RUNAGENT_API_KEY, "env-key")]),
+            dotenv: vars(&[
+                (ENV_RUNAGENT_API_KEY, "dotenv-key"),
+                (ENV_RUNAGENT_BASE_URL, "dotenv.example.com"),
+            ]),
+        };
+        let config = Config::load_layered(&path, Some("dev"), &env).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("env-key"));
+        assert_eq!(config.base_url, "https://dotenv.example.com");
+
+        // Without environment values the profile applies
+        let config = Config::load_layered(&path, Some("dev"), &EnvSource::default()).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("dev-key"));
+    }
+
+    #[test]
+    fn test_missing_config_file_uses_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::from_file(&temp_dir.path().join("config.toml"), None).unwrap();
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+        assert!(config.api_key.is_none());
+    }
+
     #[test]
     fn test_status_generation() {
         let config = Config::default();
@@ -0,0 +1,167 @@
+//! Framework detection for agent project directories
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Frameworks with the Python modules and PyPI packages that identify them
+///
+/// Checked in order: frameworks built on others come first (`langgraph` projects
+/// import `langchain`, most projects depend on `openai`).
+const FRAMEWORKS: &[(&str, &[&str], &[&str])] = &[
+    ("langgraph", &["langgraph"], &["langgraph"]),
+    ("crewai", &["crewai"], &["crewai"]),
+    ("ag2", &[], &["ag2"]),
+    (
+        "autogen",
+        &["autogen", "autogen_agentchat", "autogen_core"],
+        &["pyautogen", "autogen-agentchat", "autogen-core"],
+    ),
+    (
+        "letta",
+        &["letta", "letta_client"],
+        &["letta", "letta-client"],
+    ),
+    ("llamaindex", &["llama_index"], &["llama-index"]),
+    ("agno", &["agno"], &["agno"]),
+    ("parlant", &["parlant"], &["parlant"]),
+    ("langchain", &["langchain"], &["langchain"]),
+    (
+        "openai",
+        &["openai", "agents"],
+        &["openai", "openai-agents"],
+    ),
+];
+
+/// Detect the framework of the agent project in `path`
+///
+/// The heuristics, first match wins:
+/// 1. the `framework` field of `runagent.config.json`;
+/// 2. imports in the directory's top-level `.py` files (`import crewai`,
+///    `from langgraph.graph import StateGraph`);
+/// 3. packages listed in `requirements.txt`.
+///
+/// Imports and packages are matched most specific framework first, so a
+/// LangGraph project that also imports `langchain` and `openai` is reported as
+/// `langgraph`. Returns `None` when nothing matches or `path` is unreadable.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use runagent::utils::detect_framework;
+///
+/// if let Some(framework) = detect_framework("./my-agent") {
+///     println!("Detected {}", framework);
+/// }
+/// ```
+pub fn detect_framework(path: impl AsRef<Path>) -> Option<String> {
+    let path = path.as_ref();
+    configured_framework(path)
+        .or_else(|| imported_framework(path))
+        .or_else(|| required_framework(path))
+}
+
+fn configured_framework(path: &Path) -> Option<String> {
+    let config = fs::read_to_string(path.join("runagent.config.json")).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+    config["framework"]
+        .as_str()
+        .filter(|framework| !framework.is_empty())
+        .map(str::to_string)
+}
+
+fn imported_framework(path: &Path) -> Option<String> {
+    let mut modules = HashSet::new();
+    for entry in fs::read_dir(path).ok()?.flatten() {
+        let file = entry.path();
+        if file.extension().is_some_and(|ext| ext == "py") {
+            if let Ok(source) = fs::read_to_string(&file) {
+                modules.extend(imported_modules(&source));
+            }
+        }
+    }
+    FRAMEWORKS
+        .iter()
+        .find(|(_, imports, _)| imports.iter().any(|module| modules.contains(*module)))
+        .map(|(framework, _, _)| framework.to_string())
+}
+
+/// Top-level modules named by `import` and `from ... import` statements
+fn imported_modules(source: &str) -> Vec<String> {
+    let mut modules = Vec::new();
+    for line in source.lines().map(str::trim) {
+        let names = if let Some(rest) = line.strip_prefix("from ") {
+            rest.split_whitespace()
+                .next()
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else if let Some(rest) = line.strip_prefix("import ") {
+            rest.split(',')
+                .filter_map(|name| name.split_whitespace().next())
+                .collect()
+        } else {
+            continue;
+        };
+        modules.extend(
+            names
+                .into_iter()
+                .filter_map(|name| name.split('.').next())
+                .filter(|root| !root.is_empty())
+                .map(str::to_string),
+        );
+    }
+    modules
+}
+
+fn required_framework(path: &Path) -> Option<String> {
+    let requirements = fs::read_to_string(path.join("requirements.txt")).ok()?;
+    let packages: HashSet<String> = requirements
+        .lines()
+        .map(|line| {
+            line.split(|c: char| "=<>!~[;# ".contains(c))
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase()
+                .replace('_', "-")
+        })
+        .filter(|package| !package.is_empty())
+        .collect();
+    FRAMEWORKS
+        .iter()
+        .find(|(_, _, names)| names.iter().any(|name| packages.contains(*name)))
+        .map(|(framework, _, _)| framework.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_framework_heuristics() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect_framework(dir.path()), None);
+
+        fs::write(
+            dir.path().join("requirements.txt"),
+            "openai>=1.0\nllama_index==0.10 # rag\n",
+        )
+        .unwrap();
+        assert_eq!(detect_framework(dir.path()).as_deref(), Some("llamaindex"));
+
+        fs::write(
+            dir.path().join("agents.py"),
+            "import os, openai\nfrom langchain_core.messages import HumanMessage\n\
+             from langgraph.graph import StateGraph\n",
+        )
+        .unwrap();
+        assert_eq!(detect_framework(dir.path()).as_deref(), Some("langgraph"));
+
+        fs::write(
+            dir.path().join("runagent.config.json"),
+            r#"{"agent_name": "crew", "framework": "crewai"}"#,
+        )
+        .unwrap();
+        assert_eq!(detect_framework(dir.path()).as_deref(), Some("crewai"));
+    }
+}
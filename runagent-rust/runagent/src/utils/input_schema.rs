@@ -0,0 +1,169 @@
+//! Checking keyword arguments against an entrypoint's input schema
+//!
+//! Entrypoints may publish an `input_schema` in the agent architecture: a JSON
+//! Schema object whose `properties` describe the keyword arguments. Only the top
+//! level is checked (`required`, `additionalProperties: false` and each
+//! property's `type`); nested values are left to the agent.
+
+use crate::types::{RunAgentError, RunAgentResult};
+use serde_json::Value;
+
+/// Check `kwargs` against an object `schema`
+///
+/// Returns a validation error listing every missing, unexpected and mistyped
+/// argument. Schemas that are not objects accept any input.
+///
+/// # Example
+///
+/// ```rust
+/// use runagent::utils::validate_kwargs;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"query": {"type": "string"}, "top_n": {"type": "integer"}},
+///     "required": ["query"]
+/// });
+/// assert!(validate_kwargs(&schema, &[("query", json!("hi"))]).is_ok());
+///
+/// let err = validate_kwargs(&schema, &[("top_n", json!("3"))]).unwrap_err();
+/// assert!(err.to_string().contains("missing: query"));
+/// ```
+pub fn validate_kwargs(schema: &Value, kwargs: &[(&str, Value)]) -> RunAgentResult<()> {
+    let Some(schema) = schema.as_object() else {
+        return Ok(());
+    };
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    let missing: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|name| !kwargs.iter().any(|(key, _)| key == name))
+        .collect();
+
+    let mut extra = Vec::new();
+    let mut mistyped = Vec::new();
+    let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+    for (key, value) in kwargs {
+        match properties.and_then(|properties| properties.get(*key)) {
+            Some(property) => {
+                if let Some(expected) = property.get("type") {
+                    if !matches_type(expected, value) {
+                        mistyped.push(format!("{} (expected {})", key, expected_name(expected)));
+                    }
+                }
+            }
+            None if closed => extra.push(*key),
+            None => {}
+        }
+    }
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing: {}", missing.join(", ")));
+    }
+    if !extra.is_empty() {
+        problems.push(format!("unexpected: {}", extra.join(", ")));
+    }
+    if !mistyped.is_empty() {
+        problems.push(format!("wrong type: {}", mistyped.join(", ")));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(RunAgentError::validation(format!(
+            "Input does not match the entrypoint schema ({})",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Whether `value` has the JSON Schema `type` (a name or a list of names)
+fn matches_type(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => matches_type_name(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| matches_type_name(name, value)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(name: &str, value: &Value) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        // Unknown type names are not ours to reject
+        _ => true,
+    }
+}
+
+fn expected_name(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        other => other.as_str().unwrap_or("any").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_every_problem() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "constraints": {"type": "object"},
+                "top_n": {"type": ["integer", "null"]}
+            },
+            "required": ["query", "constraints"],
+            "additionalProperties": false
+        });
+
+        let err = validate_kwargs(
+            &schema,
+            &[
+                ("constraints", json!("{\"max\": 3}")),
+                ("top_n", json!(1.5)),
+                ("verbose", json!(true)),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err.category(), "validation");
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Input does not match the entrypoint schema (missing: query; \
+             unexpected: verbose; wrong type: constraints (expected object), top_n (expected integer | null))"
+        );
+
+        let ok = [
+            ("query", json!("hi")),
+            ("constraints", json!({})),
+            ("top_n", json!(null)),
+        ];
+        assert!(validate_kwargs(&schema, &ok).is_ok());
+    }
+
+    #[test]
+    fn test_open_schema_allows_extra_arguments() {
+        let schema = json!({"properties": {"query": {"type": "string"}}});
+        assert!(validate_kwargs(&schema, &[("other", json!(1))]).is_ok());
+        assert!(validate_kwargs(&json!(true), &[("query", json!(1))]).is_ok());
+    }
+}
@@ -0,0 +1,51 @@
+//! Run, stream and error metrics emitted through the `metrics` facade
+//!
+//! Recording is a no-op unless the `metrics` feature is enabled and the
+//! application has installed a recorder (e.g. `metrics-exporter-prometheus`).
+
+use crate::types::RunAgentResult;
+use std::time::Duration;
+
+/// Runs and streams started, labeled by `kind` (`run` or `stream`)
+pub const RUNS_TOTAL: &str = "runagent_runs_total";
+/// Duration of non-streaming runs
+pub const RUN_DURATION_SECONDS: &str = "runagent_run_duration_seconds";
+/// Stream items yielded to the caller
+pub const STREAM_CHUNKS_TOTAL: &str = "runagent_stream_chunks_total";
+/// Failed runs and stream items, labeled by error `category`
+pub const ERRORS_TOTAL: &str = "runagent_errors_total";
+
+/// Record a finished non-streaming run
+pub(crate) fn record_run<T>(elapsed: Duration, result: &RunAgentResult<T>) {
+    #[cfg(feature = "metrics")]
+    {
+        ::metrics::counter!(RUNS_TOTAL, "kind" => "run").increment(1);
+        ::metrics::histogram!(RUN_DURATION_SECONDS).record(elapsed.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = elapsed;
+    record_result(result);
+}
+
+/// Record an attempt to open a stream
+pub(crate) fn record_stream_start<T>(result: &RunAgentResult<T>) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(RUNS_TOTAL, "kind" => "stream").increment(1);
+    record_result(result);
+}
+
+/// Record one item yielded by a stream
+pub(crate) fn record_stream_chunk<T>(item: &RunAgentResult<T>) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(STREAM_CHUNKS_TOTAL).increment(1);
+    record_result(item);
+}
+
+fn record_result<T>(result: &RunAgentResult<T>) {
+    #[cfg(feature = "metrics")]
+    if let Err(e) = result {
+        ::metrics::counter!(ERRORS_TOTAL, "category" => e.category()).increment(1);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = result;
+}
@@ -3,9 +3,19 @@
 //! This module contains various utility functions and helpers used throughout
 //! the SDK for configuration management and serialization.
 
+#[cfg(feature = "archive")]
+pub(crate) mod archive;
+pub mod canonical;
+pub(crate) mod clock;
 pub mod config;
+pub mod framework;
+pub mod input_schema;
+pub mod metrics;
 pub mod serializer;
 
 // Re-export commonly used utilities
+pub use canonical::{canonical_json, canonicalize};
 pub use config::Config;
+pub use framework::detect_framework;
+pub use input_schema::validate_kwargs;
 pub use serializer::CoreSerializer;
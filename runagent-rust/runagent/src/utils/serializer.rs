@@ -3,19 +3,49 @@
 use crate::types::{RunAgentError, RunAgentResult, SafeMessage};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io;
 
 /// Core serializer for handling object serialization and deserialization
 #[derive(Clone)]
 pub struct CoreSerializer {
     max_size_bytes: usize,
+    unwrap_envelopes: bool,
 }
 
 impl CoreSerializer {
-    /// Create a new CoreSerializer with specified size limit
+    /// Create a new CoreSerializer with a payload size limit in megabytes (MiB)
     pub fn new(max_size_mb: f64) -> RunAgentResult<Self> {
-        Ok(Self {
+        Ok(Self::with_max_mb(max_size_mb))
+    }
+
+    /// Create a CoreSerializer that rejects payloads larger than `max_size_mb` MiB
+    ///
+    /// The limit is enforced by [`CoreSerializer::try_prepare_for_deserialization`] and
+    /// [`CoreSerializer::deserialize_object`], measured on the compact JSON form.
+    pub fn with_max_mb(max_size_mb: f64) -> Self {
+        Self {
             max_size_bytes: (max_size_mb * 1024.0 * 1024.0) as usize,
-        })
+            unwrap_envelopes: true,
+        }
+    }
+
+    /// Unwrap `{type, payload}` envelopes in [`CoreSerializer::deserialize_object`]
+    /// (default: `true`)
+    ///
+    /// When disabled, envelopes are returned as ordinary objects.
+    pub fn with_envelope_unwrap(mut self, unwrap: bool) -> Self {
+        self.unwrap_envelopes = unwrap;
+        self
+    }
+
+    /// Whether `{type, payload}` envelopes are unwrapped
+    pub fn unwraps_envelopes(&self) -> bool {
+        self.unwrap_envelopes
+    }
+
+    /// Get the payload size limit in bytes
+    pub fn max_size_bytes(&self) -> usize {
+        self.max_size_bytes
     }
 
     /// Serialize an object to JSON string
@@ -38,6 +68,9 @@ impl CoreSerializer {
     /// If the value is a JSON string, parses it first.
     /// Otherwise returns the value as-is.
     /// This handles cases where responses come as JSON strings.
+    ///
+    /// Does not enforce the size limit; see
+    /// [`CoreSerializer::try_prepare_for_deserialization`].
     pub fn prepare_for_deserialization(&self, value: Value) -> Value {
         if let Some(str_val) = value.as_str() {
             // Try to parse as JSON first
@@ -50,6 +83,14 @@ impl CoreSerializer {
         }
     }
 
+    /// [`CoreSerializer::prepare_for_deserialization`] within the size limit
+    ///
+    /// Fails with [`RunAgentError::Serialization`] if the value exceeds the size limit.
+    pub fn try_prepare_for_deserialization(&self, value: Value) -> RunAgentResult<Value> {
+        self.enforce_size_limit(&value)?;
+        Ok(self.prepare_for_deserialization(value))
+    }
+
     /// Deserialize JSON response to object
     ///
     /// Handles multiple response formats:
@@ -57,33 +98,20 @@ impl CoreSerializer {
     /// 2. String payload - parses JSON string
     /// 3. Direct value - reconstructs nested JSON
     ///
-    /// Handles multiple response formats including `{type, payload}` structures.
+    /// Envelopes are unwrapped repeatedly, so a payload that is itself an
+    /// envelope (directly or as a JSON-encoded string) yields the innermost value.
+    /// See [`CoreSerializer::with_envelope_unwrap`].
+    ///
+    /// Fails with [`RunAgentError::Serialization`] if the value exceeds the size limit.
     pub fn deserialize_object(&self, json_resp: Value) -> RunAgentResult<Value> {
-        // Handle {type, payload} structure
-        if let Value::Object(ref map) = json_resp {
-            if map.contains_key("type") && map.contains_key("payload") {
-                let payload_val = map.get("payload").unwrap();
-
-                // If payload is a string, try to parse it as JSON
-                if let Some(payload_str) = payload_val.as_str() {
-                    // The payload is a JSON-encoded string, parse it to get the actual value
-                    // Example: payload_str = "\"Hello\"" -> parsed = "Hello"
-                    match serde_json::from_str::<Value>(payload_str) {
-                        Ok(parsed) => {
-                            // Parse succeeded - return the parsed value
-                            return Ok(parsed);
-                        }
-                        Err(_) => {
-                            // Parse failed - return the string as-is
-                            return Ok(Value::String(payload_str.to_string()));
-                        }
-                    }
-                }
+        self.enforce_size_limit(&json_resp)?;
 
-                // Payload is not a string - reconstruct it directly
-                return self.reconstruct_nested_json(payload_val.clone());
-            }
+        // Handle {type, payload} structure
+        if self.unwrap_envelopes && is_envelope(&json_resp) {
+            return self.unwrap_envelope(json_resp);
+        }
 
+        if let Value::Object(ref map) = json_resp {
             // Handle {content} structure (legacy format)
             if let Some(content) = map.get("content") {
                 return self.reconstruct_nested_json(content.clone());
@@ -94,7 +122,13 @@ impl CoreSerializer {
         if let Some(str_val) = json_resp.as_str() {
             match serde_json::from_str::<Value>(str_val) {
                 Ok(parsed) => return self.reconstruct_nested_json(parsed),
-                Err(_) => return Ok(Value::String(str_val.to_string())),
+                Err(_) => {
+                    tracing::debug!(
+                        payload_len = str_val.len(),
+                        "Response is a plain string, passing it through"
+                    );
+                    return Ok(Value::String(str_val.to_string()));
+                }
             }
         }
 
@@ -102,6 +136,82 @@ impl CoreSerializer {
         self.reconstruct_nested_json(json_resp)
     }
 
+    /// Payload of a `{type, payload}` envelope, unwrapping nested envelopes
+    fn unwrap_envelope(&self, mut envelope: Value) -> RunAgentResult<Value> {
+        loop {
+            let envelope_type = envelope
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("<non-string>")
+                .to_string();
+            let payload = envelope
+                .as_object_mut()
+                .and_then(|map| map.remove("payload"))
+                .unwrap_or(Value::Null);
+
+            let Value::String(payload_str) = payload else {
+                // Payload is not a string - reconstruct it directly
+                if is_envelope(&payload) {
+                    envelope = payload;
+                    continue;
+                }
+                return self.reconstruct_nested_json(payload);
+            };
+
+            // The payload is a JSON-encoded string, parse it to get the actual value
+            // Example: payload_str = "\"Hello\"" -> parsed = "Hello"
+            match serde_json::from_str::<Value>(&payload_str) {
+                Ok(parsed) if is_envelope(&parsed) => envelope = parsed,
+                Ok(parsed) => return Ok(parsed),
+                Err(e) => {
+                    // Parse failed - return the string as-is, but flag it: the
+                    // envelope promised a JSON-encoded payload
+                    tracing::warn!(
+                        envelope_type,
+                        payload_len = payload_str.len(),
+                        error = %e,
+                        "Payload is not valid JSON, passing it through as a string"
+                    );
+                    return Ok(Value::String(payload_str));
+                }
+            }
+        }
+    }
+
+    /// Serialize an object to MessagePack
+    ///
+    /// Produces the same structure as [`CoreSerializer::serialize_object`], encoded
+    /// as MessagePack instead of JSON text.
+    #[cfg(feature = "msgpack")]
+    pub fn serialize_object_msgpack(&self, obj: Value) -> RunAgentResult<Vec<u8>> {
+        let serialized_data = self.try_serialize_strategies(obj)?;
+        let bytes = value_to_msgpack(&Value::Object(serialized_data.into_iter().collect()))?;
+
+        if bytes.len() > self.max_size_bytes {
+            tracing::warn!(
+                "Serialized object exceeds size limit: {} bytes",
+                bytes.len()
+            );
+        }
+
+        Ok(bytes)
+    }
+
+    /// Deserialize a MessagePack response to object
+    ///
+    /// Decodes `bytes` and then applies [`CoreSerializer::deserialize_object`].
+    /// MessagePack `bin` and `ext` values become arrays of byte values.
+    ///
+    /// Fails with [`RunAgentError::Serialization`] if the encoded or decoded
+    /// payload exceeds the size limit.
+    #[cfg(feature = "msgpack")]
+    pub fn deserialize_object_msgpack(&self, bytes: &[u8]) -> RunAgentResult<Value> {
+        if bytes.len() > self.max_size_bytes {
+            return Err(self.size_error(bytes.len()));
+        }
+        self.deserialize_object(msgpack_to_value(bytes)?)
+    }
+
     /// Serialize SafeMessage to JSON string
     pub fn serialize_message(&self, message: &SafeMessage) -> RunAgentResult<String> {
         let message_dict = message.to_dict();
@@ -165,6 +275,33 @@ impl CoreSerializer {
         json_str.len() <= self.max_size_bytes
     }
 
+    /// Reject values whose payload is larger than the size limit
+    ///
+    /// Strings are measured by their text, since they usually carry JSON-encoded
+    /// payloads; other values by their compact JSON encoding.
+    fn enforce_size_limit(&self, value: &Value) -> RunAgentResult<()> {
+        let size = match value {
+            Value::String(s) => s.len(),
+            _ => json_len(value)?,
+        };
+        if size <= self.max_size_bytes {
+            return Ok(());
+        }
+        Err(self.size_error(size))
+    }
+
+    fn size_error(&self, size: usize) -> RunAgentError {
+        const MIB: f64 = 1024.0 * 1024.0;
+        RunAgentError::serialization(format!(
+            "Payload is {} bytes ({:.2} MB), exceeding the {} byte ({:.2} MB) limit; \
+             raise it with RunAgentClientConfig::with_max_response_mb",
+            size,
+            size as f64 / MIB,
+            self.max_size_bytes,
+            self.max_size_bytes as f64 / MIB
+        ))
+    }
+
     /// Try multiple serialization strategies
     fn try_serialize_strategies(&self, obj: Value) -> RunAgentResult<HashMap<String, Value>> {
         // Strategy 1: Direct JSON serializable
@@ -173,6 +310,10 @@ impl CoreSerializer {
         }
 
         // Strategy 2: Convert to string representation
+        tracing::warn!(
+            value_type = %self.get_value_type(&obj),
+            "Object is not directly serializable, falling back to string representation"
+        );
         let str_repr = self.value_to_string(&obj);
         Ok(self.create_response_with_metadata("string_repr", Value::String(str_repr), &obj))
     }
@@ -314,6 +455,109 @@ impl CoreSerializer {
     }
 }
 
+/// Whether `value` is a `{type, payload}` envelope
+fn is_envelope(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|map| map.contains_key("type") && map.contains_key("payload"))
+}
+
+/// Length of `value` as compact JSON, measured without building the string
+pub(crate) fn json_len(value: &Value) -> RunAgentResult<usize> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+/// Writer that only counts the bytes written to it
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Decode a MessagePack document into a JSON value
+///
+/// `bin` and `ext` data become arrays of byte values, and map keys that are not
+/// strings use their MessagePack text form.
+#[cfg(feature = "msgpack")]
+pub fn msgpack_to_value(bytes: &[u8]) -> RunAgentResult<Value> {
+    fn convert(value: rmpv::Value) -> Value {
+        match value {
+            rmpv::Value::Nil => Value::Null,
+            rmpv::Value::Boolean(b) => Value::Bool(b),
+            rmpv::Value::Integer(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(n), _) => Value::from(n),
+                (None, Some(n)) => Value::from(n),
+                (None, None) => Value::from(n.as_f64()),
+            },
+            rmpv::Value::F32(f) => Value::from(f as f64),
+            rmpv::Value::F64(f) => Value::from(f),
+            rmpv::Value::String(s) => match s.into_str() {
+                Some(s) => Value::String(s),
+                None => Value::Null,
+            },
+            rmpv::Value::Binary(bytes) | rmpv::Value::Ext(_, bytes) => {
+                Value::Array(bytes.into_iter().map(Value::from).collect())
+            }
+            rmpv::Value::Array(items) => Value::Array(items.into_iter().map(convert).collect()),
+            rmpv::Value::Map(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = match key {
+                            rmpv::Value::String(s) if s.is_str() => {
+                                s.into_str().unwrap_or_default()
+                            }
+                            other => other.to_string(),
+                        };
+                        (key, convert(value))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    let value = rmpv::decode::read_value(&mut &bytes[..])
+        .map_err(|e| RunAgentError::serialization(format!("Invalid MessagePack: {}", e)))?;
+    Ok(convert(value))
+}
+
+/// Encode a JSON value as a MessagePack document
+#[cfg(feature = "msgpack")]
+pub fn value_to_msgpack(value: &Value) -> RunAgentResult<Vec<u8>> {
+    fn convert(value: &Value) -> rmpv::Value {
+        match value {
+            Value::Null => rmpv::Value::Nil,
+            Value::Bool(b) => rmpv::Value::Boolean(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(n), _) => rmpv::Value::from(n),
+                (None, Some(n)) => rmpv::Value::from(n),
+                (None, None) => rmpv::Value::F64(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => rmpv::Value::from(s.as_str()),
+            Value::Array(items) => rmpv::Value::Array(items.iter().map(convert).collect()),
+            Value::Object(map) => rmpv::Value::Map(
+                map.iter()
+                    .map(|(key, value)| (rmpv::Value::from(key.as_str()), convert(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    let mut bytes = Vec::new();
+    rmpv::encode::write_value(&mut bytes, &convert(value))
+        .map_err(|e| RunAgentError::serialization(format!("MessagePack encoding failed: {}", e)))?;
+    Ok(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,6 +605,25 @@ mod tests {
         assert!(!serializer.check_size_limit(&large_str));
     }
 
+    #[test]
+    fn test_oversized_payload_is_rejected() {
+        let serializer = CoreSerializer::with_max_mb(0.001); // 1048 bytes
+        let large = serde_json::json!({"text": "a".repeat(2000)});
+
+        let err = serializer.deserialize_object(large.clone()).unwrap_err();
+        assert_eq!(err.category(), "serialization");
+        assert!(err.to_string().contains("2011 bytes"));
+        assert!(err.to_string().contains("1048 byte"));
+
+        let err = serializer
+            .try_prepare_for_deserialization(Value::String(large.to_string()))
+            .unwrap_err();
+        assert_eq!(err.category(), "serialization");
+
+        let small = serde_json::json!({"text": "ok"});
+        assert_eq!(serializer.deserialize_object(small.clone()).unwrap(), small);
+    }
+
     #[test]
     fn test_json_serializable_check() {
         let serializer = CoreSerializer::new(10.0).unwrap();
@@ -375,6 +638,41 @@ mod tests {
         assert!(serializer.is_json_serializable(&array_obj));
     }
 
+    #[test]
+    fn test_non_json_envelope_payload_passes_through() {
+        let serializer = CoreSerializer::new(10.0).unwrap();
+
+        // A raw (not JSON-encoded) payload falls back to string passthrough
+        let envelope = serde_json::json!({"type": "string", "payload": "Hello, world"});
+        let result = serializer.deserialize_object(envelope).unwrap();
+        assert_eq!(result, serde_json::json!("Hello, world"));
+    }
+
+    #[test]
+    fn test_nested_envelopes_are_unwrapped() {
+        let serializer = CoreSerializer::new(10.0).unwrap();
+
+        // Object envelope around a stringified envelope
+        let inner = serde_json::json!({"type": "object", "payload": "{\"answer\": 42}"});
+        let envelope = serde_json::json!({"type": "object", "payload": inner.clone()});
+        assert_eq!(
+            serializer.deserialize_object(envelope.clone()).unwrap(),
+            serde_json::json!({"answer": 42})
+        );
+
+        // Stringified envelope inside a stringified envelope
+        let double = serde_json::json!({"type": "object", "payload": inner.to_string()});
+        assert_eq!(
+            serializer.deserialize_object(double).unwrap(),
+            serde_json::json!({"answer": 42})
+        );
+
+        let raw = CoreSerializer::new(10.0)
+            .unwrap()
+            .with_envelope_unwrap(false);
+        assert_eq!(raw.deserialize_object(envelope.clone()).unwrap(), envelope);
+    }
+
     #[test]
     fn test_nested_reconstruction() {
         let serializer = CoreSerializer::new(10.0).unwrap();
@@ -393,4 +691,39 @@ mod tests {
         let reconstructed = result.unwrap();
         assert_eq!(reconstructed, nested_data);
     }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        let serializer = CoreSerializer::new(10.0).unwrap();
+        let embedding = serde_json::json!({"vector": [0.25, -1.5, 3.0], "dims": 3, "id": -7});
+
+        let bytes = serializer
+            .serialize_object_msgpack(embedding.clone())
+            .unwrap();
+        assert!(
+            bytes.len()
+                < serializer
+                    .serialize_object(embedding.clone())
+                    .unwrap()
+                    .len()
+        );
+        assert_eq!(
+            serializer.deserialize_object_msgpack(&bytes).unwrap(),
+            embedding
+        );
+
+        // Raw bytes decode to an array of byte values
+        let mut image = Vec::new();
+        rmpv::encode::write_value(&mut image, &rmpv::Value::Binary(vec![0x89, 0x50])).unwrap();
+        assert_eq!(
+            msgpack_to_value(&image).unwrap(),
+            serde_json::json!([137, 80])
+        );
+
+        let err = CoreSerializer::with_max_mb(0.000001)
+            .deserialize_object_msgpack(&bytes)
+            .unwrap_err();
+        assert_eq!(err.category(), "serialization");
+    }
 }
@@ -16,66 +16,20 @@ async fn main() -> runagent::RunAgentResult<()> {
     println!("LangGraph Chatbot Test Suite");
     println!("{}", "=".repeat(70));
 
-    // Initialize clients for different entrypoints
+    // One client per entrypoint; the others reuse chat_client's architecture and transports
     let chat_client = RunAgentClient::new(RunAgentClientConfig {
         agent_id: AGENT_ID.to_string(),
         entrypoint_tag: "chat".to_string(),
         local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
         user_id: Some(USER_ID.to_string()),
         persistent_memory: Some(true),
+        ..RunAgentClientConfig::default()
     })
     .await?;
 
-    let stream_client = RunAgentClient::new(RunAgentClientConfig {
-        agent_id: AGENT_ID.to_string(),
-        entrypoint_tag: "chat_stream".to_string(),
-        local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
-        user_id: Some(USER_ID.to_string()),
-        persistent_memory: Some(true),
-    })
-    .await?;
-
-    let history_client = RunAgentClient::new(RunAgentClientConfig {
-        agent_id: AGENT_ID.to_string(),
-        entrypoint_tag: "get_history".to_string(),
-        local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
-        user_id: Some(USER_ID.to_string()),
-        persistent_memory: Some(true),
-    })
-    .await?;
-
-    let threads_client = RunAgentClient::new(RunAgentClientConfig {
-        agent_id: AGENT_ID.to_string(),
-        entrypoint_tag: "list_threads".to_string(),
-        local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
-        user_id: Some(USER_ID.to_string()),
-        persistent_memory: Some(true),
-    })
-    .await?;
+    let stream_client = chat_client.with_entrypoint("chat_stream")?;
+    let history_client = chat_client.with_entrypoint("get_history")?;
+    let threads_client = chat_client.with_entrypoint("list_threads")?;
 
     //////////////////////////// TESTS ////////////////////////////
     test_basic_conversation(&chat_client).await?;
@@ -327,14 +281,9 @@ async fn test_persistence_across_sessions(
         agent_id: agent_id.to_string(),
         entrypoint_tag: "chat".to_string(),
         local: Some(local_mode),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
         user_id: Some(user_id.to_string()),
         persistent_memory: Some(true),
+        ..RunAgentClientConfig::default()
     })
     .await?;
 
@@ -13,14 +13,9 @@ async fn main() -> runagent::RunAgentResult<()> {
         agent_id: AGENT_ID.to_string(),
         entrypoint_tag: "ingest_text".to_string(),
         local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
         user_id: Some(USER_ID.to_string()),
         persistent_memory: Some(true),
+        ..RunAgentClientConfig::default()
     })
     .await?;
 
@@ -29,14 +24,9 @@ async fn main() -> runagent::RunAgentResult<()> {
         agent_id: AGENT_ID.to_string(),
         entrypoint_tag: "query_rag".to_string(),
         local: Some(LOCAL_MODE),
-        host: None,
-        port: None,
-        api_key: None,
-        base_url: None,
-        extra_params: None,
-        enable_registry: None,
         user_id: Some(USER_ID.to_string()),
         persistent_memory: Some(true),
+        ..RunAgentClientConfig::default()
     })
     .await?;
 